@@ -0,0 +1,72 @@
+use crab_nbt::{NbtCompound, NbtTag};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+const SIZES: [usize; 4] = [10, 100, 1_000, 10_000];
+
+fn keys(size: usize) -> Vec<String> {
+    (0..size).map(|i| format!("minecraft:entry_{i}")).collect()
+}
+
+fn compound(keys: &[String]) -> NbtCompound {
+    keys.iter()
+        .enumerate()
+        .map(|(i, key)| (key.clone(), NbtTag::Int(i as i32)))
+        .collect()
+}
+
+fn benchmark(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("compound_put");
+    for size in SIZES {
+        let keys = keys(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &keys, |b, keys| {
+            b.iter_batched(
+                || keys.clone(),
+                |keys| {
+                    let mut compound = NbtCompound::new();
+                    for (i, key) in keys.into_iter().enumerate() {
+                        compound.put(key, i as i32);
+                    }
+                    compound
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+
+    let mut group = criterion.benchmark_group("compound_get");
+    for size in SIZES {
+        let keys = keys(size);
+        let compound = compound(&keys);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &keys, |b, keys| {
+            b.iter(|| {
+                keys.iter()
+                    .filter_map(|key| compound.get_int(key))
+                    .sum::<i32>()
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = criterion.benchmark_group("compound_remove");
+    for size in SIZES {
+        let keys = keys(size);
+        let compound = compound(&keys);
+        // Removes every tenth tag, so that the remaining ones have to be moved
+        group.bench_with_input(BenchmarkId::from_parameter(size), &keys, |b, keys| {
+            b.iter_batched_ref(
+                || compound.clone(),
+                |compound| {
+                    for key in keys.iter().step_by(10) {
+                        compound.remove(key);
+                    }
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark);
+criterion_main!(benches);
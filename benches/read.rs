@@ -1,5 +1,5 @@
 use bytes::Bytes;
-use crab_nbt::Nbt;
+use crab_nbt::{Nbt, NbtRef};
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
 
 #[cfg(feature = "serde")]
@@ -21,6 +21,15 @@ fn benchmark_file(criterion: &mut Criterion, file_name: &str, bytes: Bytes) {
     });
 }
 
+fn benchmark_file_borrowed(criterion: &mut Criterion, file_name: &str, bytes: Bytes) {
+    let mut group = criterion.benchmark_group("read_borrowed");
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+
+    group.bench_function(file_name, |b| {
+        b.iter(|| NbtRef::read(&bytes).expect("Failed to parse NBT"))
+    });
+}
+
 #[cfg(feature = "serde")]
 fn benchmark_file_serde<T: serde::de::DeserializeOwned>(
     criterion: &mut Criterion,
@@ -44,6 +53,7 @@ fn benchmark_file_serde<T: serde::de::DeserializeOwned>(
 fn benchmark(criterion: &mut Criterion) {
     let bytes = utils::read_file("tests/data/complex_player.dat", true);
     benchmark_file(criterion, "complex_player", Bytes::clone(&bytes));
+    benchmark_file_borrowed(criterion, "complex_player", Bytes::clone(&bytes));
     #[cfg(feature = "serde")]
     benchmark_file_serde::<test_data_definitions::ComplexPlayer>(
         criterion,
@@ -53,6 +63,7 @@ fn benchmark(criterion: &mut Criterion) {
 
     let bytes = utils::read_file("tests/data/chunk.nbt", false);
     benchmark_file(criterion, "chunk", Bytes::clone(&bytes));
+    benchmark_file_borrowed(criterion, "chunk", Bytes::clone(&bytes));
     #[cfg(feature = "serde")]
     benchmark_file_serde::<test_data_definitions::Chunk>(criterion, "chunk", bytes);
 }
@@ -0,0 +1,26 @@
+#![no_main]
+
+use crab_nbt::{Nbt, NbtCompound, NbtFlavor, NbtRef, NbtTag, ReadOptions};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    for flavor in [
+        NbtFlavor::JavaBE,
+        NbtFlavor::BedrockLE,
+        NbtFlavor::BedrockNetwork,
+    ] {
+        let _ = Nbt::read_with_flavor(&mut &data[..], flavor);
+        let _ = Nbt::read_unnamed_with_flavor(&mut &data[..], flavor);
+        let _ = NbtTag::deserialize_with_flavor(&mut &data[..], flavor);
+        let _ = NbtCompound::deserialize_content_with_flavor(&mut &data[..], flavor);
+
+        let options = ReadOptions::network().with_flavor(flavor);
+        let _ = Nbt::read_unnamed_with_options(&mut &data[..], &options);
+    }
+
+    if let Ok(nbt) = NbtRef::read(data) {
+        // Accessing the view must not panic either
+        let _ = nbt.to_nbt();
+    }
+    let _ = NbtRef::read_unnamed(data);
+});
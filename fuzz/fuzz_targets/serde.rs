@@ -0,0 +1,42 @@
+#![no_main]
+
+use crab_nbt::serde::de::{from_bytes, from_bytes_unnamed, from_slice_unnamed};
+use crab_nbt::NbtCompound;
+use libfuzzer_sys::fuzz_target;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Player {
+    name: String,
+    health: f32,
+    pos: Vec<f64>,
+    #[serde(rename = "Inventory")]
+    inventory: Vec<Item>,
+    flags: Option<HashMap<String, bool>>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct BorrowedItem<'a> {
+    id: &'a str,
+    data: &'a [u8],
+    tags: Vec<&'a str>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Item {
+    id: String,
+    count: i8,
+    tag: Option<NbtCompound>,
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = from_bytes::<NbtCompound>(&mut &data[..]);
+    let _ = from_bytes_unnamed::<NbtCompound>(&mut &data[..]);
+    let _ = from_bytes::<Player>(&mut &data[..]);
+    let _ = from_bytes_unnamed::<Player>(&mut &data[..]);
+    let _ = from_slice_unnamed::<BorrowedItem>(data);
+});
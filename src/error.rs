@@ -1,4 +1,5 @@
 use bytes::TryGetError;
+use crab_nbt::nbt::path::NbtPath;
 #[cfg(feature = "serde")]
 use serde::{de, ser};
 #[cfg(feature = "serde")]
@@ -14,16 +15,123 @@ pub enum Error {
     NoRootCompound(u8),
     #[error("Requested {requested} bytes of {remaining} available")]
     NoDataRemaining { requested: usize, remaining: usize },
+    #[error("Length of a list or an array can't be negative. Received length: {0}")]
+    NegativeLength(i32),
+    #[error("List of tags with id {list_type} can't contain a tag with id {tag_type}.")]
+    ListTypeMismatch { list_type: u8, tag_type: u8 },
+    #[error("List with {0} elements doesn't specify the type of its elements.")]
+    MissingListType(usize),
     #[error("The provided string is not a valid Java string.")]
     InvalidJavaString,
+    #[error("The provided string is not valid UTF-8.")]
+    InvalidUtf8String,
+    #[error("Variable-length integer is longer than {0} bytes.")]
+    VarIntTooBig(usize),
     #[error("Encountered an unknown NBT tag id {0}.")]
     UnknownTagId(u8),
+    #[error("NBT is nested deeper than the limit of {0} levels.")]
+    DepthLimitExceeded(usize),
+    #[error("NBT is larger than the limit of {0} bytes.")]
+    SizeLimitExceeded(usize),
+    #[error("List or array with {len} elements exceeds the limit of {max} elements.")]
+    ArrayTooLong { len: usize, max: usize },
     #[error("Serde error: {0}")]
     SerdeError(String),
     #[error("NBT doesn't support this type {0}")]
     UnsupportedType(String),
+    #[error("Invalid SNBT at position {position}: {message}")]
+    InvalidSnbt { position: usize, message: String },
+    #[error("Invalid NBT path at position {position}: {message}")]
+    InvalidPath { position: usize, message: String },
+    #[error("Invalid Bedrock level.dat header: {0}")]
+    InvalidBedrockHeader(String),
+    #[error("Invalid use of NbtWriter: {0}")]
+    InvalidWrite(String),
+    #[error("Invalid region file: {0}")]
+    InvalidRegion(String),
+    #[error("Unsupported chunk compression type {0}.")]
+    UnsupportedCompression(u8),
+    #[error("Invalid NBT frame: {0}")]
+    InvalidFrame(String),
     #[error(transparent)]
     Io(#[from] io::Error),
+    /// Error that occurred while reading, with the location where it happened.
+    ///
+    /// Every error returned while reading or deserializing NBT is wrapped in it, including errors
+    /// of headers and frames around the NBT and of the underlying reader, so match
+    /// [Error::inner] or [Error::into_inner] to check for a specific error.
+    /// Errors of region files name the chunk instead, only errors of its NBT are located.
+    #[error("{source} (at byte {offset}{})", fmt_path(path))]
+    At {
+        /// Number of bytes read before the error occurred.
+        offset: usize,
+        /// Path to the tag that failed to be read.
+        path: NbtPath,
+        source: Box<Error>,
+    },
+}
+
+fn fmt_path(path: &NbtPath) -> String {
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!(", in `{path}`")
+    }
+}
+
+impl Error {
+    /// Returns the underlying error, without the location added by [Error::At].
+    pub fn inner(&self) -> &Error {
+        match self {
+            Error::At { source, .. } => source.inner(),
+            error => error,
+        }
+    }
+
+    /// Converts the error into the underlying one, without the location added by [Error::At].
+    pub fn into_inner(self) -> Error {
+        match self {
+            Error::At { source, .. } => source.into_inner(),
+            error => error,
+        }
+    }
+
+    /// Number of bytes read before the error occurred, if known.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Error::At { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Locates an error of data which starts `offset` bytes into the input, by adding the offset
+    /// to its location or by locating it at the offset if it doesn't have a location yet.
+    pub(crate) fn located_at(self, offset: usize) -> Error {
+        match self {
+            Error::At {
+                offset: inner_offset,
+                path,
+                source,
+            } => Error::At {
+                offset: offset + inner_offset,
+                path,
+                source,
+            },
+            error => Error::At {
+                offset,
+                path: NbtPath::new(),
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// Path to the tag that failed to be read, if known.
+    pub fn path(&self) -> Option<&NbtPath> {
+        match self {
+            Error::At { path, .. } => Some(path),
+            _ => None,
+        }
+    }
 }
 
 impl From<TryGetError> for Error {
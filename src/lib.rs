@@ -3,11 +3,24 @@
 pub mod error;
 mod macros;
 mod nbt;
+#[cfg(feature = "region")]
+pub mod region;
 #[cfg(feature = "serde")]
 pub mod serde;
 
+#[cfg(feature = "codec")]
+pub use crab_nbt::nbt::codec::NbtCodec;
 pub use crab_nbt::nbt::compound::NbtCompound;
+#[cfg(feature = "compression")]
+pub use crab_nbt::nbt::compression::Compression;
+pub use crab_nbt::nbt::flavor::NbtFlavor;
+pub use crab_nbt::nbt::list::{MixedTags, NbtList, NbtTagView, NbtTagViewMut};
+pub use crab_nbt::nbt::options::ReadOptions;
+pub use crab_nbt::nbt::path::{NbtPath, PathSegment};
+pub use crab_nbt::nbt::reader::{ArrayChunk, BufInput, NbtEvent, NbtInput, NbtReader, ReadInput};
 pub use crab_nbt::nbt::tag::NbtTag;
+pub use crab_nbt::nbt::view::{ArrayRef, CompoundRef, ListRef, NbtRef, NbtTagRef};
+pub use crab_nbt::nbt::writer::NbtWriter;
 pub use crab_nbt::nbt::Nbt;
 
 extern crate self as crab_nbt;
@@ -46,6 +46,10 @@
 /// # Returns
 ///
 /// An `Nbt` object with the specified name and content.
+///
+/// # Panics
+///
+/// Panics if a list contains [NbtTag::End](crate::NbtTag::End).
 #[cfg(feature = "macro")]
 #[macro_export]
 macro_rules! nbt {
@@ -84,10 +88,16 @@ macro_rules! nbt_inner {
         $crate::NbtTag::ByteArray(::bytes::Bytes::from_iter([$($lit),*]))
     };
     ([$($lit:literal),* $(,)?]) => {
-        $crate::NbtTag::List(::std::vec![$($lit.into()),*])
+        nbt_inner!(@list ::std::vec![$($crate::NbtTag::from($lit)),*])
     };
     ([$($t:tt),* $(,)?]) => {
-        $crate::NbtTag::List(::std::vec![$(nbt_inner!($t).into()),*])
+        nbt_inner!(@list ::std::vec![$($crate::NbtTag::from(nbt_inner!($t))),*])
     };
+    (@list $tags:expr) => {{
+        let tags: ::std::vec::Vec<$crate::NbtTag> = $tags;
+        $crate::NbtTag::List(
+            $crate::NbtList::try_from_tags(tags).expect("lists can't contain END tags"),
+        )
+    }};
 
 }
@@ -1,14 +1,36 @@
 use crate::error::Error;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use crab_nbt::nbt::compound::NbtCompound;
+use crab_nbt::nbt::flavor::NbtFlavor;
+use crab_nbt::nbt::options::{ReadContext, ReadOptions};
+use crab_nbt::nbt::reader::{NbtInput, NbtReader};
+use crab_nbt::nbt::tag::NbtTag;
 use crab_nbt::nbt::utils::*;
+use crab_nbt::nbt::writer::NbtWriter;
 use std::fmt::{self, Display, Formatter};
-use std::io::{Cursor, Write};
-use std::ops::Deref;
+use std::io::{Cursor, Read, Write};
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
 
+#[cfg(feature = "tokio")]
+pub(crate) mod async_io;
+mod bedrock;
+#[cfg(feature = "codec")]
+pub mod codec;
 pub mod compound;
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod flavor;
+pub mod list;
+pub mod options;
+pub mod path;
+mod query;
+pub mod reader;
+mod snbt;
 pub mod tag;
 pub mod utils;
+pub mod view;
+pub mod writer;
 
 /// Represents the main NBT structure.
 /// It contains the root compound tag of the NBT structure and its associated name
@@ -27,16 +49,88 @@ impl Nbt {
     }
 
     pub fn read(bytes: &mut impl Buf) -> Result<Nbt, Error> {
-        let tag_type_id = bytes.try_get_u8()?;
+        Self::read_with_flavor(bytes, NbtFlavor::JavaBE)
+    }
 
-        if tag_type_id != COMPOUND_ID {
-            return Err(Error::NoRootCompound(tag_type_id));
-        }
+    pub fn read_with_flavor(bytes: &mut impl Buf, flavor: NbtFlavor) -> Result<Nbt, Error> {
+        Self::read_with_options(bytes, &ReadOptions::default().with_flavor(flavor))
+    }
 
-        Ok(Nbt {
-            name: get_nbt_string(bytes)?,
-            root_tag: NbtCompound::deserialize_content(bytes)?,
-        })
+    /// Reads NBT, enforcing limits from the given [ReadOptions].
+    /// Use it with [ReadOptions::network] when reading untrusted data.
+    ///
+    /// Errors are returned as [Error::At], containing the location where reading failed.
+    pub fn read_with_options(bytes: &mut impl Buf, options: &ReadOptions) -> Result<Nbt, Error> {
+        let mut context = ReadContext::new(*options, bytes);
+        Self::read_root(bytes, &mut context, true).map_err(|error| context.locate(bytes, error))
+    }
+
+    /// Reads NBT, recovering from malformed data instead of failing, useful for salvaging
+    /// damaged files. Other read methods are strict and fail on the first error.
+    ///
+    /// Returns everything that could be read, together with the errors that were recovered from.
+    /// Invalid strings are decoded with replacement characters and lists of `END` tags are read
+    /// as empty, while other errors stop reading, keeping the tags read before them.
+    /// Errors that happen before the root compound and exceeded limits are still returned.
+    pub fn read_lenient(
+        bytes: &mut impl Buf,
+        options: &ReadOptions,
+    ) -> Result<(Nbt, Vec<Error>), Error> {
+        let mut context = ReadContext::lenient(*options, bytes);
+        let nbt = Self::read_root(bytes, &mut context, true)
+            .map_err(|error| context.locate(bytes, error))?;
+        Ok((nbt, context.into_diagnostics()))
+    }
+
+    /// Parses an SNBT compound, like the ones used in commands, into an unnamed [Nbt].
+    ///
+    /// To parse the output of [Nbt]'s [Display] implementation (which includes the root name)
+    /// use [FromStr] instead.
+    pub fn parse_snbt(snbt: &str) -> Result<Nbt, Error> {
+        Ok(Nbt::from(snbt.parse::<NbtCompound>()?))
+    }
+
+    /// Reads NBT from a reader, pulling the data as it's needed instead of reading
+    /// all of it first. Works with decompressors like `flate2::read::GzDecoder`.
+    ///
+    /// Only the bytes of the NBT are read, in small parts, so unbuffered readers
+    /// should be wrapped in a [BufReader](std::io::BufReader).
+    pub fn from_reader(reader: impl Read) -> Result<Nbt, Error> {
+        Self::from_reader_with_options(reader, &ReadOptions::default())
+    }
+
+    pub fn from_reader_with_options(
+        reader: impl Read,
+        options: &ReadOptions,
+    ) -> Result<Nbt, Error> {
+        Self::read_events(NbtReader::from_reader_with_options(reader, options))
+    }
+
+    /// Like [Nbt::from_reader], but for NBT that doesn't contain the name of the root compound.
+    pub fn from_reader_unnamed(reader: impl Read) -> Result<Nbt, Error> {
+        Self::from_reader_unnamed_with_options(reader, &ReadOptions::default())
+    }
+
+    pub fn from_reader_unnamed_with_options(
+        reader: impl Read,
+        options: &ReadOptions,
+    ) -> Result<Nbt, Error> {
+        Self::read_events(NbtReader::from_reader_with_options(reader, options).unnamed())
+    }
+
+    fn read_events(mut reader: NbtReader<impl NbtInput>) -> Result<Nbt, Error> {
+        Ok(Self::from_root(reader.read_tag()?))
+    }
+
+    /// Creates NBT from the root compound read by an [NbtReader].
+    pub(crate) fn from_root(root: Option<(Option<String>, NbtTag)>) -> Nbt {
+        match root {
+            Some((name, NbtTag::Compound(root_tag))) => Nbt {
+                name: name.unwrap_or_default(),
+                root_tag,
+            },
+            tag => unreachable!("{:?} is not a root compound", tag),
+        }
     }
 
     pub fn read_from_cursor(cursor: &mut Cursor<&[u8]>) -> Result<Nbt, Error> {
@@ -46,15 +140,51 @@ impl Nbt {
     /// Reads an NBT tag that doesn't contain the name of the root compound.
     /// Used in [Network NBT](https://wiki.vg/NBT#Network_NBT_(Java_Edition)).
     pub fn read_unnamed(bytes: &mut impl Buf) -> Result<Nbt, Error> {
+        Self::read_unnamed_with_flavor(bytes, NbtFlavor::JavaBE)
+    }
+
+    pub fn read_unnamed_with_flavor(bytes: &mut impl Buf, flavor: NbtFlavor) -> Result<Nbt, Error> {
+        Self::read_unnamed_with_options(bytes, &ReadOptions::default().with_flavor(flavor))
+    }
+
+    pub fn read_unnamed_with_options(
+        bytes: &mut impl Buf,
+        options: &ReadOptions,
+    ) -> Result<Nbt, Error> {
+        let mut context = ReadContext::new(*options, bytes);
+        Self::read_root(bytes, &mut context, false).map_err(|error| context.locate(bytes, error))
+    }
+
+    /// Like [Nbt::read_lenient], but for NBT that doesn't contain the name of the root compound.
+    pub fn read_unnamed_lenient(
+        bytes: &mut impl Buf,
+        options: &ReadOptions,
+    ) -> Result<(Nbt, Vec<Error>), Error> {
+        let mut context = ReadContext::lenient(*options, bytes);
+        let nbt = Self::read_root(bytes, &mut context, false)
+            .map_err(|error| context.locate(bytes, error))?;
+        Ok((nbt, context.into_diagnostics()))
+    }
+
+    fn read_root(
+        bytes: &mut impl Buf,
+        context: &mut ReadContext,
+        is_named: bool,
+    ) -> Result<Nbt, Error> {
         let tag_type_id = bytes.try_get_u8()?;
 
         if tag_type_id != COMPOUND_ID {
             return Err(Error::NoRootCompound(tag_type_id));
         }
 
+        let name = if is_named {
+            context.get_string(bytes)?
+        } else {
+            String::new()
+        };
         Ok(Nbt {
-            name: String::new(),
-            root_tag: NbtCompound::deserialize_content(bytes)?,
+            name,
+            root_tag: NbtCompound::read_content(bytes, context)?,
         })
     }
 
@@ -69,13 +199,27 @@ impl Nbt {
     }
 
     pub fn write_into(&self, bytes: &mut BytesMut) {
+        self.write_into_with_flavor(bytes, NbtFlavor::JavaBE)
+    }
+
+    pub fn write_with_flavor(&self, flavor: NbtFlavor) -> Bytes {
+        let mut bytes = BytesMut::new();
+        self.write_into_with_flavor(&mut bytes, flavor);
+        bytes.freeze()
+    }
+
+    pub fn write_into_with_flavor(&self, bytes: &mut BytesMut, flavor: NbtFlavor) {
         bytes.put_u8(COMPOUND_ID);
-        serialize_str_into(&self.name, bytes);
-        self.root_tag.serialize_content_into(bytes);
+        flavor.put_string(bytes, &self.name);
+        self.root_tag
+            .serialize_content_into_with_flavor(bytes, flavor);
     }
 
-    pub fn write_to_writer<W: Write>(&self, mut writer: W) -> Result<(), Error> {
-        writer.write_all(&self.write())?;
+    /// Writes the NBT directly to the writer, without buffering all of it first.
+    pub fn write_to_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = NbtWriter::new(writer);
+        writer.write_compound(&self.name, &self.root_tag)?;
+        writer.finish()?;
         Ok(())
     }
 
@@ -88,12 +232,25 @@ impl Nbt {
     }
 
     pub fn write_unnamed_into(&self, bytes: &mut BytesMut) {
+        self.write_unnamed_into_with_flavor(bytes, NbtFlavor::JavaBE)
+    }
+
+    pub fn write_unnamed_with_flavor(&self, flavor: NbtFlavor) -> Bytes {
+        let mut bytes = BytesMut::new();
+        self.write_unnamed_into_with_flavor(&mut bytes, flavor);
+        bytes.freeze()
+    }
+
+    pub fn write_unnamed_into_with_flavor(&self, bytes: &mut BytesMut, flavor: NbtFlavor) {
         bytes.put_u8(COMPOUND_ID);
-        self.root_tag.serialize_content_into(bytes);
+        self.root_tag
+            .serialize_content_into_with_flavor(bytes, flavor);
     }
 
-    pub fn write_unnamed_to_writer<W: Write>(&self, mut writer: W) -> Result<(), Error> {
-        writer.write_all(&self.write_unnamed())?;
+    pub fn write_unnamed_to_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = NbtWriter::new(writer).unnamed();
+        writer.write_compound("", &self.root_tag)?;
+        writer.finish()?;
         Ok(())
     }
 }
@@ -106,6 +263,12 @@ impl Deref for Nbt {
     }
 }
 
+impl DerefMut for Nbt {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.root_tag
+    }
+}
+
 impl From<NbtCompound> for Nbt {
     fn from(value: NbtCompound) -> Self {
         Nbt::new(String::new(), value)
@@ -130,6 +293,29 @@ impl AsMut<NbtCompound> for Nbt {
 
 impl Display for Nbt {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{{\"{}\": {}}}", self.name, self.root_tag)
+        write!(
+            f,
+            "{{{}: {}}}",
+            escape_string_value(&self.name),
+            self.root_tag
+        )
+    }
+}
+
+/// Parses the format produced by [Nbt]'s [Display] implementation,
+/// a compound with a single entry mapping the root name to the root compound.
+impl FromStr for Nbt {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wrapper: NbtCompound = s.parse()?;
+        let mut entries = wrapper.into_iter();
+        match (entries.next(), entries.next()) {
+            (Some((name, NbtTag::Compound(root_tag))), None) => Ok(Nbt::new(name, root_tag)),
+            _ => Err(Error::InvalidSnbt {
+                position: 0,
+                message: "Expected a compound with a single named root compound".to_owned(),
+            }),
+        }
     }
 }
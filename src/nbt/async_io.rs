@@ -0,0 +1,136 @@
+use crate::error::Error;
+use crab_nbt::nbt::compound::NbtCompound;
+use crab_nbt::nbt::list::NbtList;
+use crab_nbt::nbt::options::ReadOptions;
+use crab_nbt::nbt::reader::NbtReader;
+use crab_nbt::nbt::tag::NbtTag;
+use crab_nbt::nbt::utils::{COMPOUND_ID, LIST_ID};
+use crab_nbt::nbt::writer::NbtWriter;
+use crab_nbt::nbt::Nbt;
+use indexmap::map::Iter;
+use std::slice;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+impl Nbt {
+    /// Reads NBT from an [AsyncRead]er, like [Nbt::from_reader].
+    ///
+    /// Only the bytes of the NBT are read, in small parts, so unbuffered readers
+    /// should be wrapped in a [BufReader](tokio::io::BufReader).
+    pub async fn read_async(reader: impl AsyncRead + Unpin) -> Result<Nbt, Error> {
+        Self::read_async_with_options(reader, &ReadOptions::default()).await
+    }
+
+    /// Reads NBT from an [AsyncRead]er, enforcing limits from the given [ReadOptions]
+    /// before the data is received. Use it with [ReadOptions::network] for data sent by clients.
+    pub async fn read_async_with_options(
+        reader: impl AsyncRead + Unpin,
+        options: &ReadOptions,
+    ) -> Result<Nbt, Error> {
+        let mut reader = NbtReader::from_async_reader_with_options(reader, options);
+        Ok(Self::from_root(reader.read_tag_async().await?))
+    }
+
+    /// Like [Nbt::read_async], but for NBT that doesn't contain the name of the root compound.
+    pub async fn read_unnamed_async(reader: impl AsyncRead + Unpin) -> Result<Nbt, Error> {
+        Self::read_unnamed_async_with_options(reader, &ReadOptions::default()).await
+    }
+
+    pub async fn read_unnamed_async_with_options(
+        reader: impl AsyncRead + Unpin,
+        options: &ReadOptions,
+    ) -> Result<Nbt, Error> {
+        let mut reader = NbtReader::from_async_reader_with_options(reader, options).unnamed();
+        Ok(Self::from_root(reader.read_tag_async().await?))
+    }
+
+    /// Writes NBT to an [AsyncWrite]r in parts of a few kilobytes, like [NbtWriter] does.
+    pub async fn write_async(&self, writer: impl AsyncWrite + Unpin) -> Result<(), Error> {
+        write_nbt(self, NbtWriter::new(Vec::new()), writer).await
+    }
+
+    pub async fn write_unnamed_async(&self, writer: impl AsyncWrite + Unpin) -> Result<(), Error> {
+        write_nbt(self, NbtWriter::new(Vec::new()).unnamed(), writer).await
+    }
+}
+
+/// Reads the bytes of a single NBT, stopping right after its end.
+#[cfg(feature = "serde")]
+pub(crate) async fn read_nbt_bytes(
+    reader: impl AsyncRead + Unpin,
+    options: &ReadOptions,
+    is_named: bool,
+) -> Result<Vec<u8>, Error> {
+    let mut reader = NbtReader::from_async_reader_with_options(reader, options).keeping_data();
+    if !is_named {
+        reader = reader.unnamed();
+    }
+    while reader.next_event_async().await?.is_some() {}
+    Ok(reader.into_inner().into_data())
+}
+
+/// Compound or list whose tags are being written by [write_nbt].
+enum Parent<'a> {
+    Compound(Iter<'a, String, NbtTag>),
+    Compounds(slice::Iter<'a, NbtCompound>),
+    Lists(slice::Iter<'a, NbtList>),
+}
+
+/// Tag of a [Parent], with compounds and lists being written part by part.
+enum Child<'a> {
+    Compound(&'a NbtCompound),
+    List(&'a NbtList),
+    Tag(&'a NbtTag),
+}
+
+/// Writes NBT without recursion, passing the parts written by `nbt_writer` on to `writer`.
+async fn write_nbt(
+    nbt: &Nbt,
+    mut nbt_writer: NbtWriter<Vec<u8>>,
+    mut writer: impl AsyncWrite + Unpin,
+) -> Result<(), Error> {
+    nbt_writer.begin_compound(&nbt.name)?;
+    let mut stack = vec![Parent::Compound(nbt.root_tag.iter())];
+    while let Some(parent) = stack.last_mut() {
+        let child = match parent {
+            Parent::Compound(tags) => tags.next().map(|(name, tag)| {
+                let child = match tag {
+                    NbtTag::Compound(compound) => Child::Compound(compound),
+                    NbtTag::List(list) => Child::List(list),
+                    tag => Child::Tag(tag),
+                };
+                (name.as_str(), child)
+            }),
+            Parent::Compounds(compounds) => compounds.next().map(|c| ("", Child::Compound(c))),
+            Parent::Lists(lists) => lists.next().map(|list| ("", Child::List(list))),
+        };
+
+        match child {
+            None => {
+                nbt_writer.end()?;
+                stack.pop();
+            }
+            Some((name, Child::Compound(compound))) => {
+                nbt_writer.begin_compound(name)?;
+                stack.push(Parent::Compound(compound.iter()));
+            }
+            Some((name, Child::List(NbtList::Compounds(compounds)))) => {
+                nbt_writer.begin_list(name, COMPOUND_ID, compounds.len())?;
+                stack.push(Parent::Compounds(compounds.iter()));
+            }
+            Some((name, Child::List(NbtList::Lists(lists)))) => {
+                nbt_writer.begin_list(name, LIST_ID, lists.len())?;
+                stack.push(Parent::Lists(lists.iter()));
+            }
+            Some((name, Child::List(list))) => nbt_writer.write_list(name, list)?,
+            Some((name, Child::Tag(tag))) => nbt_writer.write_tag(name, tag)?,
+        }
+
+        let part = nbt_writer.get_mut();
+        if !part.is_empty() {
+            writer.write_all(part).await?;
+            part.clear();
+        }
+    }
+    writer.write_all(&nbt_writer.finish()?).await?;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::error::Error;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use crab_nbt::nbt::flavor::NbtFlavor;
+use crab_nbt::nbt::Nbt;
+
+/// Size of the header preceding the root compound in Bedrock `level.dat` files.
+const LEVEL_DAT_HEADER_SIZE: usize = 8;
+
+impl Nbt {
+    /// Reads a Bedrock Edition `level.dat` file, returning its storage version and the NBT.
+    ///
+    /// These files start with a little-endian header containing the storage version
+    /// and the length of the little-endian NBT that follows it.
+    pub fn read_bedrock_level_dat(bytes: &mut impl Buf) -> Result<(i32, Nbt), Error> {
+        let storage_version = bytes
+            .try_get_i32_le()
+            .map_err(|error| Error::from(error).located_at(0))?;
+        let length = bytes
+            .try_get_i32_le()
+            .map_err(|error| Error::from(error).located_at(size_of::<i32>()))?;
+
+        let length = usize::try_from(length).map_err(|_| {
+            Error::InvalidBedrockHeader(format!("Negative payload length {length}"))
+                .located_at(LEVEL_DAT_HEADER_SIZE)
+        })?;
+        if length > bytes.remaining() {
+            return Err(Error::InvalidBedrockHeader(format!(
+                "Payload length {length} is bigger than {} remaining bytes",
+                bytes.remaining()
+            ))
+            .located_at(LEVEL_DAT_HEADER_SIZE));
+        }
+
+        let mut payload = bytes.copy_to_bytes(length);
+        let nbt = Nbt::read_with_flavor(&mut payload, NbtFlavor::BedrockLE)
+            .map_err(|error| error.located_at(LEVEL_DAT_HEADER_SIZE))?;
+        if payload.has_remaining() {
+            return Err(Error::InvalidBedrockHeader(format!(
+                "Payload length {length} is bigger than the NBT by {} bytes",
+                payload.remaining()
+            ))
+            .located_at(LEVEL_DAT_HEADER_SIZE + length - payload.remaining()));
+        }
+
+        Ok((storage_version, nbt))
+    }
+
+    /// Writes the NBT as a Bedrock Edition `level.dat` file with the given storage version.
+    pub fn write_bedrock_level_dat(&self, storage_version: i32) -> Bytes {
+        let mut bytes = BytesMut::new();
+        bytes.put_i32_le(storage_version);
+        // Placeholder for the payload length
+        bytes.put_i32_le(0);
+        self.write_into_with_flavor(&mut bytes, NbtFlavor::BedrockLE);
+
+        let length = (bytes.len() - LEVEL_DAT_HEADER_SIZE) as i32;
+        bytes[4..LEVEL_DAT_HEADER_SIZE].copy_from_slice(&length.to_le_bytes());
+        bytes.freeze()
+    }
+}
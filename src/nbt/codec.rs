@@ -0,0 +1,117 @@
+use crate::error::Error;
+use bytes::{Buf, BufMut, BytesMut};
+use crab_nbt::nbt::flavor::{get_var_u64, put_var_u64};
+use crab_nbt::nbt::options::ReadOptions;
+use crab_nbt::nbt::Nbt;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Maximum size of the length prefix, which is a 32-bit VarInt.
+const MAX_PREFIX_LEN: usize = 5;
+
+/// [Decoder] and [Encoder] of NBT frames sent one after another in a byte stream,
+/// to be used with [Framed](tokio_util::codec::Framed) and similar transports.
+///
+/// Every frame starts with its length in bytes, encoded as an unsigned LEB128 VarInt
+/// like the lengths of Minecraft packets, followed by the NBT which has to fill the whole frame.
+/// Lengths exceeding [ReadOptions::max_total_bytes] are rejected before the frame is received.
+///
+/// By default unnamed NBT is used, limited by [ReadOptions::network].
+#[derive(Debug)]
+pub struct NbtCodec {
+    options: ReadOptions,
+    is_named: bool,
+}
+
+impl NbtCodec {
+    pub fn new() -> Self {
+        Self::with_options(&ReadOptions::network())
+    }
+
+    /// Creates a codec which uses the flavor and limits of the given [ReadOptions].
+    pub fn with_options(options: &ReadOptions) -> Self {
+        NbtCodec {
+            options: *options,
+            is_named: false,
+        }
+    }
+
+    /// Uses NBT which contains the name of the root compound.
+    pub fn named(self) -> Self {
+        NbtCodec {
+            is_named: true,
+            ..self
+        }
+    }
+}
+
+impl Default for NbtCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for NbtCodec {
+    type Item = Nbt;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Nbt>, Error> {
+        let mut prefix = &src[..];
+        let len = match get_var_u64(&mut prefix, MAX_PREFIX_LEN) {
+            Ok(len) => len,
+            Err(Error::NoDataRemaining { .. }) => return Ok(None),
+            Err(error) => return Err(error.located_at(0)),
+        };
+        // Errors are located relative to the start of the frame, including its length
+        let prefix_len = src.len() - prefix.len();
+        let len = usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.options.max_total_bytes)
+            .ok_or_else(|| {
+                Error::SizeLimitExceeded(self.options.max_total_bytes).located_at(prefix_len)
+            })?;
+        if prefix.len() < len {
+            src.reserve(len - prefix.len());
+            return Ok(None);
+        }
+
+        src.advance(prefix_len);
+        let mut frame = src.split_to(len).freeze();
+        let nbt = if self.is_named {
+            Nbt::read_with_options(&mut frame, &self.options)
+        } else {
+            Nbt::read_unnamed_with_options(&mut frame, &self.options)
+        }
+        .map_err(|error| error.located_at(prefix_len))?;
+        if frame.has_remaining() {
+            return Err(Error::InvalidFrame(format!(
+                "{} bytes of the {len} byte frame remain after the NBT",
+                frame.remaining()
+            ))
+            .located_at(prefix_len + len - frame.remaining()));
+        }
+        Ok(Some(nbt))
+    }
+}
+
+impl Encoder<Nbt> for NbtCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Nbt, dst: &mut BytesMut) -> Result<(), Error> {
+        let mut frame = BytesMut::new();
+        if self.is_named {
+            item.write_into_with_flavor(&mut frame, self.options.flavor);
+        } else {
+            item.write_unnamed_into_with_flavor(&mut frame, self.options.flavor);
+        }
+        if u32::try_from(frame.len()).is_err() {
+            return Err(Error::InvalidFrame(format!(
+                "NBT of {} bytes is too large for a frame",
+                frame.len()
+            )));
+        }
+        dst.reserve(MAX_PREFIX_LEN + frame.len());
+        put_var_u64(dst, frame.len() as u64);
+        dst.put_slice(&frame);
+        Ok(())
+    }
+}
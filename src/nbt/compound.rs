@@ -1,44 +1,114 @@
-use crate::nbt::utils::{escape_name, join_formatted, serialize_str_into};
+use crate::nbt::flavor::NbtFlavor;
+use crate::nbt::list::NbtList;
+use crate::nbt::options::{ReadContext, ReadOptions};
+use crate::nbt::path::PathSegment;
+use crate::nbt::utils::{escape_name, join_formatted};
 use crate::{error::Error, Nbt};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
+use crab_nbt::nbt::snbt::SnbtParser;
 use crab_nbt::nbt::tag::NbtTag;
-use crab_nbt::nbt::utils::{get_nbt_string, END_ID};
+use crab_nbt::nbt::utils::END_ID;
 use derive_more::Into;
+use indexmap::map::{Entry, IntoIter, Iter, IterMut, Keys, Values, ValuesMut};
+use indexmap::IndexMap;
+use std::cmp::Ordering;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::io::{Cursor, Write};
-use std::vec::IntoIter;
+use std::str::FromStr;
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Into)]
+/// Compound tag, keeping its child tags in insertion order.
+#[derive(Clone, Debug, Default, Into)]
 pub struct NbtCompound {
-    pub child_tags: Vec<(String, NbtTag)>,
+    pub child_tags: IndexMap<String, NbtTag>,
 }
 
 impl NbtCompound {
     pub fn new() -> NbtCompound {
         NbtCompound {
-            child_tags: Vec::new(),
+            child_tags: IndexMap::new(),
         }
     }
 
     pub fn deserialize_content(bytes: &mut impl Buf) -> Result<NbtCompound, Error> {
-        let mut compound = NbtCompound::new();
+        Self::deserialize_content_with_flavor(bytes, NbtFlavor::JavaBE)
+    }
 
-        while bytes.has_remaining() {
-            let tag_id = bytes.try_get_u8()?;
-            if tag_id == END_ID {
-                break;
-            }
+    pub fn deserialize_content_with_flavor(
+        bytes: &mut impl Buf,
+        flavor: NbtFlavor,
+    ) -> Result<NbtCompound, Error> {
+        Self::deserialize_content_with_options(bytes, &ReadOptions::default().with_flavor(flavor))
+    }
 
-            let name = get_nbt_string(bytes)?;
+    /// Like [NbtCompound::deserialize_content], but enforcing limits from the given [ReadOptions].
+    pub fn deserialize_content_with_options(
+        bytes: &mut impl Buf,
+        options: &ReadOptions,
+    ) -> Result<NbtCompound, Error> {
+        let mut context = ReadContext::new(*options, bytes);
+        Self::read_content(bytes, &mut context).map_err(|error| context.locate(bytes, error))
+    }
+
+    /// Like [NbtCompound::deserialize_content_with_options], but recovers from malformed data,
+    /// see [Nbt::read_lenient].
+    pub fn deserialize_content_lenient(
+        bytes: &mut impl Buf,
+        options: &ReadOptions,
+    ) -> Result<(NbtCompound, Vec<Error>), Error> {
+        let mut context = ReadContext::lenient(*options, bytes);
+        let compound = Self::read_content(bytes, &mut context)
+            .map_err(|error| context.locate(bytes, error))?;
+        Ok((compound, context.into_diagnostics()))
+    }
 
-            if let Ok(tag) = NbtTag::deserialize_data(bytes, tag_id) {
-                compound.put(name, tag);
-            } else {
-                break;
+    pub(crate) fn read_content(
+        bytes: &mut impl Buf,
+        context: &mut ReadContext,
+    ) -> Result<NbtCompound, Error> {
+        context.nested(|context| {
+            let mut compound = NbtCompound::new();
+
+            // Running out of data before the end tag is an error, otherwise a list claiming
+            // to contain a huge amount of compounds could be filled with empty ones
+            loop {
+                match Self::read_entry(bytes, context) {
+                    Ok(Some((name, tag))) => compound.put(name, tag),
+                    Ok(None) => break,
+                    Err(error) => {
+                        context.recover(bytes, error)?;
+                        break;
+                    }
+                }
+                if context.is_stopped() {
+                    break;
+                }
             }
+
+            Ok(compound)
+        })
+    }
+
+    /// Reads a single child tag with its name, or `None` at the end of the compound.
+    fn read_entry(
+        bytes: &mut impl Buf,
+        context: &mut ReadContext,
+    ) -> Result<Option<(String, NbtTag)>, Error> {
+        let tag_id = bytes.try_get_u8()?;
+        context.reserve(bytes, 0)?;
+        if tag_id == END_ID {
+            return Ok(None);
         }
 
-        Ok(compound)
+        let name = context.get_string(bytes)?;
+
+        context.enter_child(|| PathSegment::Key(name.clone()));
+        let tag = NbtTag::read_data(bytes, tag_id, context);
+        context.exit_child();
+
+        match tag {
+            Ok(tag) => Ok(Some((name, tag))),
+            Err(error) => Err(context.locate_in(bytes, error, PathSegment::Key(name))),
+        }
     }
 
     pub fn deserialize_content_from_cursor(
@@ -54,10 +124,14 @@ impl NbtCompound {
     }
 
     pub fn serialize_content_into(&self, bytes: &mut BytesMut) {
+        self.serialize_content_into_with_flavor(bytes, NbtFlavor::JavaBE)
+    }
+
+    pub fn serialize_content_into_with_flavor(&self, bytes: &mut BytesMut, flavor: NbtFlavor) {
         for (name, tag) in &self.child_tags {
             bytes.put_u8(tag.get_type_id());
-            serialize_str_into(name, bytes);
-            tag.serialize_data_into(bytes);
+            flavor.put_string(bytes, name);
+            tag.serialize_data_into_with_flavor(bytes, flavor);
         }
         bytes.put_u8(END_ID);
     }
@@ -67,10 +141,61 @@ impl NbtCompound {
         Ok(())
     }
 
+    /// Adds a child tag, unless a tag with the same name is already present.
     pub fn put(&mut self, name: String, value: impl Into<NbtTag>) {
-        if !self.child_tags.iter().any(|(key, _)| key == &name) {
-            self.child_tags.push((name, value.into()));
-        }
+        self.child_tags.entry(name).or_insert_with(|| value.into());
+    }
+
+    /// Adds a child tag, replacing the existing tag with the same name and returning it.
+    /// The replaced tag keeps its position.
+    pub fn insert(&mut self, name: String, value: impl Into<NbtTag>) -> Option<NbtTag> {
+        self.child_tags.insert(name, value.into())
+    }
+
+    /// Removes a child tag, keeping the order of the remaining ones.
+    pub fn remove(&mut self, name: &str) -> Option<NbtTag> {
+        self.child_tags.shift_remove(name)
+    }
+
+    /// Keeps only the child tags for which `keep` returns `true`.
+    pub fn retain(&mut self, mut keep: impl FnMut(&str, &mut NbtTag) -> bool) {
+        self.child_tags.retain(|name, tag| keep(name, tag));
+    }
+
+    pub fn entry(&mut self, name: String) -> Entry<'_, String, NbtTag> {
+        self.child_tags.entry(name)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.child_tags.contains_key(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.child_tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.child_tags.is_empty()
+    }
+
+    pub fn keys(&self) -> Keys<'_, String, NbtTag> {
+        self.child_tags.keys()
+    }
+
+    pub fn values(&self) -> Values<'_, String, NbtTag> {
+        self.child_tags.values()
+    }
+
+    pub fn values_mut(&mut self) -> ValuesMut<'_, String, NbtTag> {
+        self.child_tags.values_mut()
+    }
+
+    pub fn iter(&self) -> Iter<'_, String, NbtTag> {
+        self.child_tags.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, String, NbtTag> {
+        self.child_tags.iter_mut()
     }
 
     pub fn get_byte(&self, name: &str) -> Option<i8> {
@@ -79,12 +204,12 @@ impl NbtCompound {
 
     #[inline]
     pub fn get(&self, name: &str) -> Option<&NbtTag> {
-        for (key, value) in &self.child_tags {
-            if key.as_str() == name {
-                return Some(value);
-            }
-        }
-        None
+        self.child_tags.get(name)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut NbtTag> {
+        self.child_tags.get_mut(name)
     }
 
     pub fn get_short(&self, name: &str) -> Option<i16> {
@@ -115,21 +240,44 @@ impl NbtCompound {
         self.get(name).and_then(|tag| tag.extract_string())
     }
 
-    pub fn get_list(&self, name: &str) -> Option<&Vec<NbtTag>> {
+    pub fn get_string_mut(&mut self, name: &str) -> Option<&mut String> {
+        self.get_mut(name).and_then(|tag| tag.extract_string_mut())
+    }
+
+    pub fn get_list(&self, name: &str) -> Option<&NbtList> {
         self.get(name).and_then(|tag| tag.extract_list())
     }
 
+    pub fn get_list_mut(&mut self, name: &str) -> Option<&mut NbtList> {
+        self.get_mut(name).and_then(|tag| tag.extract_list_mut())
+    }
+
     pub fn get_compound(&self, name: &str) -> Option<&NbtCompound> {
         self.get(name).and_then(|tag| tag.extract_compound())
     }
 
+    pub fn get_compound_mut(&mut self, name: &str) -> Option<&mut NbtCompound> {
+        self.get_mut(name)
+            .and_then(|tag| tag.extract_compound_mut())
+    }
+
     pub fn get_int_array(&self, name: &str) -> Option<&Vec<i32>> {
         self.get(name).and_then(|tag| tag.extract_int_array())
     }
 
+    pub fn get_int_array_mut(&mut self, name: &str) -> Option<&mut Vec<i32>> {
+        self.get_mut(name)
+            .and_then(|tag| tag.extract_int_array_mut())
+    }
+
     pub fn get_long_array(&self, name: &str) -> Option<&Vec<i64>> {
         self.get(name).and_then(|tag| tag.extract_long_array())
     }
+
+    pub fn get_long_array_mut(&mut self, name: &str) -> Option<&mut Vec<i64>> {
+        self.get_mut(name)
+            .and_then(|tag| tag.extract_long_array_mut())
+    }
 }
 
 impl From<Nbt> for NbtCompound {
@@ -150,16 +298,57 @@ impl FromIterator<(String, NbtTag)> for NbtCompound {
 
 impl IntoIterator for NbtCompound {
     type Item = (String, NbtTag);
-    type IntoIter = IntoIter<(String, NbtTag)>;
+    type IntoIter = IntoIter<String, NbtTag>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.child_tags.into_iter()
     }
 }
 
+impl<'a> IntoIterator for &'a NbtCompound {
+    type Item = (&'a String, &'a NbtTag);
+    type IntoIter = Iter<'a, String, NbtTag>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut NbtCompound {
+    type Item = (&'a String, &'a mut NbtTag);
+    type IntoIter = IterMut<'a, String, NbtTag>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
 impl Extend<(String, NbtTag)> for NbtCompound {
     fn extend<T: IntoIterator<Item = (String, NbtTag)>>(&mut self, iter: T) {
-        self.child_tags.extend(iter)
+        for (key, value) in iter {
+            self.put(key, value);
+        }
+    }
+}
+
+// Unlike `IndexMap`, compounds with the same tags in a different order are not equal
+impl PartialEq for NbtCompound {
+    fn eq(&self, other: &Self) -> bool {
+        self.child_tags.iter().eq(&other.child_tags)
+    }
+}
+
+impl Eq for NbtCompound {}
+
+impl PartialOrd for NbtCompound {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NbtCompound {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.child_tags.iter().cmp(&other.child_tags)
     }
 }
 
@@ -182,3 +371,17 @@ impl Display for NbtCompound {
         write!(f, "}}")
     }
 }
+
+impl FromStr for NbtCompound {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match SnbtParser::new(s).parse_complete()? {
+            NbtTag::Compound(compound) => Ok(compound),
+            tag => Err(Error::InvalidSnbt {
+                position: 0,
+                message: format!("Expected a compound, found {tag}"),
+            }),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::error::Error;
+use bytes::{Buf, Bytes};
+use crab_nbt::nbt::options::ReadOptions;
+use crab_nbt::nbt::Nbt;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use std::io::{Read, Write};
+
+/// Compression formats used by Minecraft for NBT files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// Used by player data and `level.dat` files.
+    Gzip,
+    /// Used by chunks stored in region files.
+    Zlib,
+    /// Raw, uncompressed NBT.
+    None,
+}
+
+impl Compression {
+    /// Detects compression of the data by looking at its first bytes.
+    /// Data without a gzip magic number or a valid zlib header is considered uncompressed.
+    pub fn detect(header: &[u8]) -> Compression {
+        match header {
+            [0x1f, 0x8b, ..] => Compression::Gzip,
+            // Zlib header starts with deflate compression method
+            // and its first two bytes are a multiple of 31
+            [cmf, flg, ..] if cmf & 0x0f == 8 && u16::from_be_bytes([*cmf, *flg]) % 31 == 0 => {
+                Compression::Zlib
+            }
+            _ => Compression::None,
+        }
+    }
+}
+
+impl Nbt {
+    pub fn read_compressed(bytes: &mut impl Buf, compression: Compression) -> Result<Nbt, Error> {
+        Self::read_compressed_with_options(bytes, compression, &ReadOptions::default())
+    }
+
+    /// Reads compressed NBT, enforcing limits from the given [ReadOptions].
+    /// [ReadOptions::max_total_bytes] limits the decompressed data, which is checked
+    /// while decompressing, so that a small input can't decompress into a huge buffer.
+    pub fn read_compressed_with_options(
+        bytes: &mut impl Buf,
+        compression: Compression,
+        options: &ReadOptions,
+    ) -> Result<Nbt, Error> {
+        // One more byte than the limit is enough to know that it was exceeded
+        let limit = options.max_total_bytes.saturating_add(1) as u64;
+        let mut decompressed = Vec::new();
+        let result = match compression {
+            Compression::Gzip => GzDecoder::new(bytes.reader())
+                .take(limit)
+                .read_to_end(&mut decompressed),
+            Compression::Zlib => ZlibDecoder::new(bytes.reader())
+                .take(limit)
+                .read_to_end(&mut decompressed),
+            Compression::None => return Nbt::read_with_options(bytes, options),
+        };
+        // Like errors of other readers, they are located after the data that was read
+        result.map_err(|error| Error::from(error).located_at(decompressed.len()))?;
+        if decompressed.len() > options.max_total_bytes {
+            return Err(Error::SizeLimitExceeded(options.max_total_bytes)
+                .located_at(options.max_total_bytes));
+        }
+        Nbt::read_with_options(&mut decompressed.as_slice(), options)
+    }
+
+    /// Reads NBT that may be compressed with gzip, zlib or not compressed at all.
+    /// See [Compression::detect].
+    pub fn read_compressed_auto(bytes: &mut impl Buf) -> Result<Nbt, Error> {
+        let compression = Compression::detect(bytes.chunk());
+        Self::read_compressed(bytes, compression)
+    }
+
+    pub fn write_compressed(&self, compression: Compression) -> Result<Bytes, Error> {
+        let mut bytes = Vec::new();
+        self.write_compressed_to_writer(&mut bytes, compression)?;
+        Ok(Bytes::from(bytes))
+    }
+
+    pub fn write_compressed_to_writer<W: Write>(
+        &self,
+        writer: W,
+        compression: Compression,
+    ) -> Result<(), Error> {
+        let level = flate2::Compression::default();
+        match compression {
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(writer, level);
+                self.write_to_writer(&mut encoder)?;
+                encoder.finish()?;
+            }
+            Compression::Zlib => {
+                let mut encoder = ZlibEncoder::new(writer, level);
+                self.write_to_writer(&mut encoder)?;
+                encoder.finish()?;
+            }
+            Compression::None => self.write_to_writer(writer)?,
+        }
+        Ok(())
+    }
+}
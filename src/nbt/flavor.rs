@@ -0,0 +1,260 @@
+use crate::error::Error;
+use bytes::{Buf, BufMut, BytesMut};
+use crab_nbt::nbt::utils::{decode_nbt_string, read_array, serialize_str_into, truncate_str};
+#[cfg(feature = "serde")]
+use std::borrow::Cow;
+
+/// Binary encodings of NBT used by different Minecraft editions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NbtFlavor {
+    /// Big-endian NBT used by Java Edition, both on disk and over the network.
+    #[default]
+    JavaBE,
+    /// Little-endian NBT used by Bedrock Edition on disk.
+    BedrockLE,
+    /// Little-endian NBT used by the Bedrock Edition protocol,
+    /// where ints, longs and lengths are encoded as variable-length integers.
+    BedrockNetwork,
+}
+
+impl NbtFlavor {
+    pub(crate) fn get_i16(self, bytes: &mut impl Buf) -> Result<i16, Error> {
+        Ok(match self {
+            NbtFlavor::JavaBE => bytes.try_get_i16()?,
+            NbtFlavor::BedrockLE | NbtFlavor::BedrockNetwork => bytes.try_get_i16_le()?,
+        })
+    }
+
+    pub(crate) fn get_i32(self, bytes: &mut impl Buf) -> Result<i32, Error> {
+        Ok(match self {
+            NbtFlavor::JavaBE => bytes.try_get_i32()?,
+            NbtFlavor::BedrockLE => bytes.try_get_i32_le()?,
+            NbtFlavor::BedrockNetwork => zigzag_decode(get_var_u64(bytes, 5)?) as i32,
+        })
+    }
+
+    pub(crate) fn get_i64(self, bytes: &mut impl Buf) -> Result<i64, Error> {
+        Ok(match self {
+            NbtFlavor::JavaBE => bytes.try_get_i64()?,
+            NbtFlavor::BedrockLE => bytes.try_get_i64_le()?,
+            NbtFlavor::BedrockNetwork => zigzag_decode(get_var_u64(bytes, 10)?),
+        })
+    }
+
+    pub(crate) fn get_f32(self, bytes: &mut impl Buf) -> Result<f32, Error> {
+        Ok(match self {
+            NbtFlavor::JavaBE => bytes.try_get_f32()?,
+            NbtFlavor::BedrockLE | NbtFlavor::BedrockNetwork => bytes.try_get_f32_le()?,
+        })
+    }
+
+    pub(crate) fn get_f64(self, bytes: &mut impl Buf) -> Result<f64, Error> {
+        Ok(match self {
+            NbtFlavor::JavaBE => bytes.try_get_f64()?,
+            NbtFlavor::BedrockLE | NbtFlavor::BedrockNetwork => bytes.try_get_f64_le()?,
+        })
+    }
+
+    pub(crate) fn get_string_len(self, bytes: &mut impl Buf) -> Result<usize, Error> {
+        Ok(match self {
+            NbtFlavor::JavaBE => bytes.try_get_u16()? as usize,
+            NbtFlavor::BedrockLE => bytes.try_get_u16_le()? as usize,
+            NbtFlavor::BedrockNetwork => get_var_u64(bytes, 5)? as u32 as usize,
+        })
+    }
+
+    /// Reads the content of a string, whose length was already read.
+    /// Java Edition uses modified UTF-8, while Bedrock Edition uses regular UTF-8.
+    pub(crate) fn decode_string(self, string_bytes: &[u8]) -> Result<String, Error> {
+        if self == NbtFlavor::JavaBE {
+            return decode_nbt_string(string_bytes);
+        }
+
+        String::from_utf8(string_bytes.to_vec()).map_err(|_| Error::InvalidUtf8String)
+    }
+
+    /// Like [NbtFlavor::decode_string], but borrows the string when it's valid UTF-8.
+    #[cfg(feature = "serde")]
+    pub(crate) fn decode_str(self, string_bytes: &[u8]) -> Result<Cow<'_, str>, Error> {
+        if self == NbtFlavor::JavaBE {
+            return simd_cesu8::decode(string_bytes).map_err(|_| Error::InvalidJavaString);
+        }
+
+        std::str::from_utf8(string_bytes)
+            .map(Cow::Borrowed)
+            .map_err(|_| Error::InvalidUtf8String)
+    }
+
+    /// Decodes a string, replacing invalid sequences with `U+FFFD`.
+    pub(crate) fn decode_string_lossy(self, string_bytes: &[u8]) -> String {
+        if self == NbtFlavor::JavaBE {
+            return simd_cesu8::decode_lossy(string_bytes).into_owned();
+        }
+
+        String::from_utf8_lossy(string_bytes).into_owned()
+    }
+
+    pub(crate) fn get_i32_array(self, bytes: &mut impl Buf, len: usize) -> Result<Vec<i32>, Error> {
+        const BYTES: usize = size_of::<i32>();
+
+        Ok(match self {
+            NbtFlavor::JavaBE => read_array::<i32, BYTES, _>(bytes, len, i32::from_be_bytes)?,
+            NbtFlavor::BedrockLE => read_array::<i32, BYTES, _>(bytes, len, i32::from_le_bytes)?,
+            NbtFlavor::BedrockNetwork => (0..len)
+                .map(|_| self.get_i32(bytes))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub(crate) fn get_i64_array(self, bytes: &mut impl Buf, len: usize) -> Result<Vec<i64>, Error> {
+        const BYTES: usize = size_of::<i64>();
+
+        Ok(match self {
+            NbtFlavor::JavaBE => read_array::<i64, BYTES, _>(bytes, len, i64::from_be_bytes)?,
+            NbtFlavor::BedrockLE => read_array::<i64, BYTES, _>(bytes, len, i64::from_le_bytes)?,
+            NbtFlavor::BedrockNetwork => (0..len)
+                .map(|_| self.get_i64(bytes))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub(crate) fn put_i16(self, bytes: &mut impl BufMut, value: i16) {
+        match self {
+            NbtFlavor::JavaBE => bytes.put_i16(value),
+            NbtFlavor::BedrockLE | NbtFlavor::BedrockNetwork => bytes.put_i16_le(value),
+        }
+    }
+
+    pub(crate) fn put_i32(self, bytes: &mut impl BufMut, value: i32) {
+        match self {
+            NbtFlavor::JavaBE => bytes.put_i32(value),
+            NbtFlavor::BedrockLE => bytes.put_i32_le(value),
+            NbtFlavor::BedrockNetwork => put_var_u64(bytes, zigzag_encode(value as i64)),
+        }
+    }
+
+    pub(crate) fn put_i64(self, bytes: &mut impl BufMut, value: i64) {
+        match self {
+            NbtFlavor::JavaBE => bytes.put_i64(value),
+            NbtFlavor::BedrockLE => bytes.put_i64_le(value),
+            NbtFlavor::BedrockNetwork => put_var_u64(bytes, zigzag_encode(value)),
+        }
+    }
+
+    pub(crate) fn put_f32(self, bytes: &mut impl BufMut, value: f32) {
+        match self {
+            NbtFlavor::JavaBE => bytes.put_f32(value),
+            NbtFlavor::BedrockLE | NbtFlavor::BedrockNetwork => bytes.put_f32_le(value),
+        }
+    }
+
+    pub(crate) fn put_f64(self, bytes: &mut impl BufMut, value: f64) {
+        match self {
+            NbtFlavor::JavaBE => bytes.put_f64(value),
+            NbtFlavor::BedrockLE | NbtFlavor::BedrockNetwork => bytes.put_f64_le(value),
+        }
+    }
+
+    pub(crate) fn put_byte_array(self, bytes: &mut impl BufMut, values: &[u8]) {
+        self.put_i32(bytes, values.len() as i32);
+        bytes.put_slice(values);
+    }
+
+    pub(crate) fn put_i32_array(self, bytes: &mut impl BufMut, values: &[i32]) {
+        self.put_i32(bytes, values.len() as i32);
+        for value in values {
+            self.put_i32(bytes, *value);
+        }
+    }
+
+    pub(crate) fn put_i64_array(self, bytes: &mut impl BufMut, values: &[i64]) {
+        self.put_i32(bytes, values.len() as i32);
+        for value in values {
+            self.put_i64(bytes, *value);
+        }
+    }
+
+    pub(crate) fn put_string(self, bytes: &mut BytesMut, s: &str) {
+        match self {
+            NbtFlavor::JavaBE => serialize_str_into(s, bytes),
+            NbtFlavor::BedrockLE => {
+                // Truncated like in Java Edition
+                let s = truncate_str(s, u16::MAX as usize, char::len_utf8);
+                bytes.put_u16_le(s.len() as u16);
+                bytes.put_slice(s.as_bytes());
+            }
+            NbtFlavor::BedrockNetwork => {
+                put_var_u64(bytes, s.len() as u64);
+                bytes.put_slice(s.as_bytes());
+            }
+        }
+    }
+}
+
+/// Reads unsigned LEB128 variable-length integer, which can't be longer than `max_bytes`.
+pub(crate) fn get_var_u64(bytes: &mut impl Buf, max_bytes: usize) -> Result<u64, Error> {
+    let mut value = 0;
+    for i in 0..max_bytes {
+        let byte = bytes.try_get_u8()?;
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(Error::VarIntTooBig(max_bytes))
+}
+
+pub(crate) fn put_var_u64(bytes: &mut impl BufMut, mut value: u64) {
+    while value >= 0x80 {
+        bytes.put_u8(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.put_u8(value as u8);
+}
+
+fn zigzag_decode(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn var_int_roundtrip() {
+        for value in [0, 1, -1, 63, -64, 64, i32::MAX, i32::MIN] {
+            let mut bytes = BytesMut::new();
+            NbtFlavor::BedrockNetwork.put_i32(&mut bytes, value);
+            assert!(bytes.len() <= 5);
+            assert_eq!(
+                NbtFlavor::BedrockNetwork.get_i32(&mut bytes).unwrap(),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn var_long_roundtrip() {
+        for value in [0, 1, -1, 300, -300, i64::MAX, i64::MIN] {
+            let mut bytes = BytesMut::new();
+            NbtFlavor::BedrockNetwork.put_i64(&mut bytes, value);
+            assert!(bytes.len() <= 10);
+            assert_eq!(
+                NbtFlavor::BedrockNetwork.get_i64(&mut bytes).unwrap(),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn zigzag() {
+        assert_eq!(zigzag_encode(0), 0);
+        assert_eq!(zigzag_encode(-1), 1);
+        assert_eq!(zigzag_encode(1), 2);
+        assert_eq!(zigzag_decode(3), -2);
+    }
+}
@@ -0,0 +1,1109 @@
+use crate::error::Error;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use crab_nbt::nbt::compound::NbtCompound;
+use crab_nbt::nbt::flavor::NbtFlavor;
+use crab_nbt::nbt::options::ReadContext;
+use crab_nbt::nbt::path::PathSegment;
+use crab_nbt::nbt::tag::NbtTag;
+use crab_nbt::nbt::utils::*;
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::iter::FusedIterator;
+use std::mem;
+use std::ops::Deref;
+
+/// Maximum number of bytes allocated for elements of a list before they are read.
+const MAX_PREALLOCATED_BYTES: usize = 64 * 1024;
+
+/// List tag, storing its elements in a vector of their type.
+///
+/// All empty lists are equal and written with the `END` element type,
+/// regardless of the variant storing them.
+#[derive(Clone, Debug, Default)]
+pub enum NbtList {
+    #[default]
+    Empty,
+    Bytes(Vec<i8>),
+    Shorts(Vec<i16>),
+    Ints(Vec<i32>),
+    Longs(Vec<i64>),
+    Floats(Vec<f32>),
+    Doubles(Vec<f64>),
+    ByteArrays(Vec<Bytes>),
+    Strings(Vec<String>),
+    Lists(Vec<NbtList>),
+    Compounds(Vec<NbtCompound>),
+    IntArrays(Vec<Vec<i32>>),
+    LongArrays(Vec<Vec<i64>>),
+    /// Elements of different types, supported since Minecraft 1.21.5, created with
+    /// [NbtList::try_from_tags]. Such lists are written as lists of compounds, with elements
+    /// that aren't compounds wrapped in a compound with an empty key.
+    /// See [ReadOptions::unwrap_mixed_lists](crate::ReadOptions::unwrap_mixed_lists)
+    /// for reading them back.
+    Mixed(MixedTags),
+}
+
+/// Elements of an [NbtList::Mixed]. They can only be created by [NbtList::try_from_tags],
+/// which makes sure that they don't contain `END` tags.
+#[derive(Clone, Debug)]
+pub struct MixedTags(Vec<NbtTag>);
+
+impl MixedTags {
+    pub fn into_vec(self) -> Vec<NbtTag> {
+        self.0
+    }
+}
+
+impl Deref for MixedTags {
+    type Target = [NbtTag];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Evaluates `$body` with `$elements` bound to the vector of a typed list.
+macro_rules! with_elements {
+    ($list:expr, $elements:ident => $body:expr, Empty => $empty:expr) => {
+        match $list {
+            NbtList::Empty => $empty,
+            NbtList::Bytes($elements) => $body,
+            NbtList::Shorts($elements) => $body,
+            NbtList::Ints($elements) => $body,
+            NbtList::Longs($elements) => $body,
+            NbtList::Floats($elements) => $body,
+            NbtList::Doubles($elements) => $body,
+            NbtList::ByteArrays($elements) => $body,
+            NbtList::Strings($elements) => $body,
+            NbtList::Lists($elements) => $body,
+            NbtList::Compounds($elements) => $body,
+            NbtList::IntArrays($elements) => $body,
+            NbtList::LongArrays($elements) => $body,
+            NbtList::Mixed(MixedTags($elements)) => $body,
+        }
+    };
+}
+
+/// Element type of one of the typed variants of [NbtList].
+trait ListElement: Sized {
+    fn view(&self) -> NbtTagView<'_>;
+    fn view_mut(&mut self) -> NbtTagViewMut<'_>;
+    fn from_tag(tag: NbtTag) -> Option<Self>;
+    fn into_tag(self) -> NbtTag;
+}
+
+macro_rules! impl_list_element {
+    ($($list:ident($element:ty) => $tag:ident($($deref:tt)?)),* $(,)?) => {$(
+        impl ListElement for $element {
+            fn view(&self) -> NbtTagView<'_> {
+                NbtTagView::$tag($($deref)? self)
+            }
+
+            fn view_mut(&mut self) -> NbtTagViewMut<'_> {
+                NbtTagViewMut::$tag(self)
+            }
+
+            fn from_tag(tag: NbtTag) -> Option<Self> {
+                match tag {
+                    NbtTag::$tag(element) => Some(element),
+                    _ => None,
+                }
+            }
+
+            fn into_tag(self) -> NbtTag {
+                NbtTag::$tag(self)
+            }
+        }
+
+        impl From<Vec<$element>> for NbtList {
+            fn from(elements: Vec<$element>) -> Self {
+                NbtList::$list(elements)
+            }
+        }
+    )*};
+}
+
+impl_list_element! {
+    Bytes(i8) => Byte(*),
+    Shorts(i16) => Short(*),
+    Ints(i32) => Int(*),
+    Longs(i64) => Long(*),
+    Floats(f32) => Float(*),
+    Doubles(f64) => Double(*),
+    ByteArrays(Bytes) => ByteArray(),
+    Strings(String) => String(),
+    Lists(NbtList) => List(),
+    Compounds(NbtCompound) => Compound(),
+    IntArrays(Vec<i32>) => IntArray(),
+    LongArrays(Vec<i64>) => LongArray(),
+}
+
+impl ListElement for NbtTag {
+    fn view(&self) -> NbtTagView<'_> {
+        self.into()
+    }
+
+    fn view_mut(&mut self) -> NbtTagViewMut<'_> {
+        self.into()
+    }
+
+    fn from_tag(tag: NbtTag) -> Option<Self> {
+        Some(tag)
+    }
+
+    fn into_tag(self) -> NbtTag {
+        self
+    }
+}
+
+impl NbtList {
+    pub fn new() -> NbtList {
+        NbtList::Empty
+    }
+
+    /// Creates a list from tags which can have different types, see [NbtList::Mixed].
+    /// Tags of a single type are still stored in the variant for that type,
+    /// and `END` tags are rejected.
+    pub fn try_from_tags(tags: Vec<NbtTag>) -> Result<NbtList, Error> {
+        let first_type = tags.first().map(NbtTag::get_type_id);
+        if tags.iter().all(|tag| Some(tag.get_type_id()) == first_type) {
+            return NbtList::try_from(tags);
+        }
+        if tags.iter().any(|tag| tag.get_type_id() == END_ID) {
+            return Err(Error::ListTypeMismatch {
+                list_type: COMPOUND_ID,
+                tag_type: END_ID,
+            });
+        }
+        Ok(NbtList::Mixed(MixedTags(tags)))
+    }
+
+    pub fn len(&self) -> usize {
+        with_elements!(self, elements => elements.len(), Empty => 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the id of the type of the elements, or `END` if the list is empty.
+    /// Lists with elements of different types are written as lists of compounds.
+    pub fn element_type_id(&self) -> u8 {
+        match self {
+            list if list.is_empty() => END_ID,
+            NbtList::Mixed(tags) => {
+                let first_type = tags[0].get_type_id();
+                if tags.iter().all(|tag| tag.get_type_id() == first_type) {
+                    first_type
+                } else {
+                    COMPOUND_ID
+                }
+            }
+            NbtList::Empty => END_ID,
+            NbtList::Bytes(_) => BYTE_ID,
+            NbtList::Shorts(_) => SHORT_ID,
+            NbtList::Ints(_) => INT_ID,
+            NbtList::Longs(_) => LONG_ID,
+            NbtList::Floats(_) => FLOAT_ID,
+            NbtList::Doubles(_) => DOUBLE_ID,
+            NbtList::ByteArrays(_) => BYTE_ARRAY_ID,
+            NbtList::Strings(_) => STRING_ID,
+            NbtList::Lists(_) => LIST_ID,
+            NbtList::Compounds(_) => COMPOUND_ID,
+            NbtList::IntArrays(_) => INT_ARRAY_ID,
+            NbtList::LongArrays(_) => LONG_ARRAY_ID,
+        }
+    }
+
+    /// Checks if the tag can be added to the list, which is the case if the list is empty,
+    /// the tag has the same type as its elements or the list is [NbtList::Mixed].
+    pub fn accepts(&self, tag: &NbtTag) -> bool {
+        match (self, tag) {
+            (_, NbtTag::End) => false,
+            (NbtList::Mixed(_), _) => true,
+            (list, tag) => list.is_empty() || list.element_type_id() == tag.get_type_id(),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<NbtTagView<'_>> {
+        with_elements!(self, elements => elements.get(index).map(ListElement::view), Empty => None)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<NbtTagViewMut<'_>> {
+        with_elements!(
+            self,
+            elements => elements.get_mut(index).map(ListElement::view_mut),
+            Empty => None
+        )
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            list: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut(with_elements!(
+            self,
+            elements => Box::new(elements.iter_mut().map(ListElement::view_mut)),
+            Empty => Box::new(std::iter::empty())
+        ))
+    }
+
+    /// Appends a tag to the list, returning an error if its type differs from the other elements.
+    pub fn push(&mut self, tag: impl Into<NbtTag>) -> Result<(), Error> {
+        let tag = tag.into();
+        if !self.accepts(&tag) {
+            return Err(self.mismatch(&tag));
+        }
+        if self.is_empty() && !matches!(self, NbtList::Mixed(_)) {
+            *self = Self::with_type_of(&tag);
+        }
+        with_elements!(
+            self,
+            elements => {
+                if let Some(element) = ListElement::from_tag(tag) {
+                    elements.push(element);
+                }
+            },
+            Empty => {}
+        );
+        Ok(())
+    }
+
+    /// Replaces the element at `index`, returning the previous one.
+    /// Returns an error if the type of the tag differs from the other elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn replace(&mut self, index: usize, tag: impl Into<NbtTag>) -> Result<NbtTag, Error> {
+        let tag = tag.into();
+        let len = self.len();
+        assert!(
+            index < len,
+            "index {index} is out of bounds of list with length {len}"
+        );
+
+        let mismatch = self.mismatch(&tag);
+        if !self.accepts(&tag) {
+            return Err(mismatch);
+        }
+        with_elements!(
+            self,
+            elements => {
+                let element = ListElement::from_tag(tag).ok_or(mismatch)?;
+                Ok(mem::replace(&mut elements[index], element).into_tag())
+            },
+            Empty => Err(mismatch)
+        )
+    }
+
+    /// Removes the element at `index`, shifting all elements after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> NbtTag {
+        let len = self.len();
+        assert!(
+            index < len,
+            "index {index} is out of bounds of list with length {len}"
+        );
+        with_elements!(
+            self,
+            elements => elements.remove(index).into_tag(),
+            Empty => NbtTag::End
+        )
+    }
+
+    pub fn pop(&mut self) -> Option<NbtTag> {
+        with_elements!(
+            self,
+            elements => elements.pop().map(ListElement::into_tag),
+            Empty => None
+        )
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(NbtTagView<'_>) -> bool) {
+        with_elements!(
+            self,
+            elements => elements.retain(|element| keep(element.view())),
+            Empty => {}
+        )
+    }
+
+    pub fn clear(&mut self) {
+        *self = NbtList::Empty;
+    }
+
+    fn with_type_of(tag: &NbtTag) -> NbtList {
+        match tag {
+            NbtTag::End => NbtList::Empty,
+            NbtTag::Byte(_) => NbtList::Bytes(Vec::new()),
+            NbtTag::Short(_) => NbtList::Shorts(Vec::new()),
+            NbtTag::Int(_) => NbtList::Ints(Vec::new()),
+            NbtTag::Long(_) => NbtList::Longs(Vec::new()),
+            NbtTag::Float(_) => NbtList::Floats(Vec::new()),
+            NbtTag::Double(_) => NbtList::Doubles(Vec::new()),
+            NbtTag::ByteArray(_) => NbtList::ByteArrays(Vec::new()),
+            NbtTag::String(_) => NbtList::Strings(Vec::new()),
+            NbtTag::List(_) => NbtList::Lists(Vec::new()),
+            NbtTag::Compound(_) => NbtList::Compounds(Vec::new()),
+            NbtTag::IntArray(_) => NbtList::IntArrays(Vec::new()),
+            NbtTag::LongArray(_) => NbtList::LongArrays(Vec::new()),
+        }
+    }
+
+    fn mismatch(&self, tag: &NbtTag) -> Error {
+        Error::ListTypeMismatch {
+            list_type: self.element_type_id(),
+            tag_type: tag.get_type_id(),
+        }
+    }
+
+    /// Writes the element type, the length and the elements of the list.
+    pub fn serialize_data_into_with_flavor(&self, bytes: &mut BytesMut, flavor: NbtFlavor) {
+        bytes.put_u8(self.element_type_id());
+        flavor.put_i32(bytes, self.len() as i32);
+        match self {
+            NbtList::Empty => {}
+            NbtList::Bytes(values) => bytes.extend(values.iter().map(|byte| *byte as u8)),
+            NbtList::Shorts(values) => values.iter().for_each(|v| flavor.put_i16(bytes, *v)),
+            NbtList::Ints(values) => values.iter().for_each(|v| flavor.put_i32(bytes, *v)),
+            NbtList::Longs(values) => values.iter().for_each(|v| flavor.put_i64(bytes, *v)),
+            NbtList::Floats(values) => values.iter().for_each(|v| flavor.put_f32(bytes, *v)),
+            NbtList::Doubles(values) => values.iter().for_each(|v| flavor.put_f64(bytes, *v)),
+            NbtList::ByteArrays(arrays) => {
+                arrays.iter().for_each(|a| flavor.put_byte_array(bytes, a))
+            }
+            NbtList::Strings(strings) => strings.iter().for_each(|s| flavor.put_string(bytes, s)),
+            NbtList::Lists(lists) => lists
+                .iter()
+                .for_each(|list| list.serialize_data_into_with_flavor(bytes, flavor)),
+            NbtList::Compounds(compounds) => compounds
+                .iter()
+                .for_each(|compound| compound.serialize_content_into_with_flavor(bytes, flavor)),
+            NbtList::IntArrays(arrays) => {
+                arrays.iter().for_each(|a| flavor.put_i32_array(bytes, a))
+            }
+            NbtList::LongArrays(arrays) => {
+                arrays.iter().for_each(|a| flavor.put_i64_array(bytes, a))
+            }
+            NbtList::Mixed(tags) if self.element_type_id() == COMPOUND_ID => {
+                for tag in tags.iter() {
+                    match tag {
+                        NbtTag::Compound(compound) if !is_wrapper(compound) => {
+                            compound.serialize_content_into_with_flavor(bytes, flavor)
+                        }
+                        tag => {
+                            bytes.put_u8(tag.get_type_id());
+                            flavor.put_string(bytes, "");
+                            tag.serialize_data_into_with_flavor(bytes, flavor);
+                            bytes.put_u8(END_ID);
+                        }
+                    }
+                }
+            }
+            NbtList::Mixed(tags) => tags
+                .iter()
+                .for_each(|tag| tag.serialize_data_into_with_flavor(bytes, flavor)),
+        }
+    }
+
+    /// Unwraps elements of a list which was read, if it has elements of different types
+    /// and [ReadOptions::unwrap_mixed_lists](crate::ReadOptions::unwrap_mixed_lists) is set.
+    pub(crate) fn unwrap_mixed(self, context: &ReadContext) -> Result<NbtList, Error> {
+        match self {
+            NbtList::Compounds(compounds)
+                if context.unwraps_mixed_lists() && compounds.iter().any(is_wrapper) =>
+            {
+                unwrap_elements(compounds)
+            }
+            list => Ok(list),
+        }
+    }
+
+    pub(crate) fn read(bytes: &mut impl Buf, context: &mut ReadContext) -> Result<NbtList, Error> {
+        let (tag_type_id, len) = context.get_list_header(bytes)?;
+        let flavor = context.flavor();
+        context.nested(|context| {
+            Ok(match tag_type_id {
+                END_ID => NbtList::Empty,
+                BYTE_ID => NbtList::Bytes(read_elements(bytes, context, len, |bytes, _| {
+                    Ok(bytes.try_get_i8()?)
+                })?),
+                SHORT_ID => NbtList::Shorts(read_elements(bytes, context, len, |bytes, _| {
+                    flavor.get_i16(bytes)
+                })?),
+                INT_ID => NbtList::Ints(read_elements(bytes, context, len, |bytes, _| {
+                    flavor.get_i32(bytes)
+                })?),
+                LONG_ID => NbtList::Longs(read_elements(bytes, context, len, |bytes, _| {
+                    flavor.get_i64(bytes)
+                })?),
+                FLOAT_ID => NbtList::Floats(read_elements(bytes, context, len, |bytes, _| {
+                    flavor.get_f32(bytes)
+                })?),
+                DOUBLE_ID => NbtList::Doubles(read_elements(bytes, context, len, |bytes, _| {
+                    flavor.get_f64(bytes)
+                })?),
+                BYTE_ARRAY_ID => {
+                    NbtList::ByteArrays(read_elements(bytes, context, len, |bytes, context| {
+                        context.get_byte_array(bytes)
+                    })?)
+                }
+                STRING_ID => {
+                    NbtList::Strings(read_elements(bytes, context, len, |bytes, context| {
+                        context.get_string(bytes)
+                    })?)
+                }
+                LIST_ID => NbtList::Lists(read_elements(bytes, context, len, NbtList::read)?),
+                COMPOUND_ID => read_compounds(bytes, context, len)?,
+                INT_ARRAY_ID => {
+                    NbtList::IntArrays(read_elements(bytes, context, len, |bytes, context| {
+                        context.get_int_array(bytes)
+                    })?)
+                }
+                LONG_ARRAY_ID => {
+                    NbtList::LongArrays(read_elements(bytes, context, len, |bytes, context| {
+                        context.get_long_array(bytes)
+                    })?)
+                }
+                _ => {
+                    read_elements::<(), _, _>(bytes, context, len, |_, _| {
+                        Err(Error::UnknownTagId(tag_type_id))
+                    })?;
+                    NbtList::Empty
+                }
+            })
+        })
+    }
+}
+
+/// Reads `len` elements of a list, stopping at the first error when reading leniently.
+fn read_elements<T, B: Buf, F>(
+    bytes: &mut B,
+    context: &mut ReadContext,
+    len: usize,
+    mut read: F,
+) -> Result<Vec<T>, Error>
+where
+    F: FnMut(&mut B, &mut ReadContext) -> Result<T, Error>,
+{
+    // The length can't be trusted and elements can take much more memory than their data,
+    // so only a few kilobytes are allocated up front and the rest as elements are read
+    let max_capacity = MAX_PREALLOCATED_BYTES / size_of::<T>().max(1);
+    let mut elements = Vec::with_capacity(len.min(bytes.remaining()).min(max_capacity));
+    for index in 0..len {
+        context.enter_child(|| PathSegment::Index(index));
+        let element = read(bytes, context);
+        context.exit_child();
+
+        match element {
+            Ok(element) => elements.push(element),
+            Err(error) => {
+                let error = context.locate_in(bytes, error, PathSegment::Index(index));
+                context.recover(bytes, error)?;
+                break;
+            }
+        }
+        if context.is_stopped() {
+            break;
+        }
+    }
+    Ok(elements)
+}
+
+fn read_compounds(
+    bytes: &mut impl Buf,
+    context: &mut ReadContext,
+    len: usize,
+) -> Result<NbtList, Error> {
+    let compounds = read_elements(bytes, context, len, NbtCompound::read_content)?;
+    NbtList::Compounds(compounds).unwrap_mixed(context)
+}
+
+/// Checks if the compound wraps an element of a list with elements of different types.
+fn is_wrapper(compound: &NbtCompound) -> bool {
+    compound.len() == 1 && compound.contains_key("")
+}
+
+fn unwrap_elements(compounds: Vec<NbtCompound>) -> Result<NbtList, Error> {
+    let tags = compounds
+        .into_iter()
+        .map(|mut compound| {
+            if is_wrapper(&compound) {
+                if let Some(tag) = compound.remove("") {
+                    return tag;
+                }
+            }
+            NbtTag::Compound(compound)
+        })
+        .collect();
+    NbtList::try_from_tags(tags)
+}
+
+impl TryFrom<Vec<NbtTag>> for NbtList {
+    type Error = Error;
+
+    /// Converts the tags into a typed list, failing if they don't all have the same type.
+    fn try_from(tags: Vec<NbtTag>) -> Result<Self, Self::Error> {
+        let mut list = NbtList::new();
+        for tag in tags {
+            list.push(tag)?;
+        }
+        Ok(list)
+    }
+}
+
+impl PartialEq for NbtList {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for NbtList {}
+
+impl PartialOrd for NbtList {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NbtList {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl Display for NbtList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        join_formatted(
+            f,
+            ", ",
+            self.iter()
+                .map(|tag| move |f: &mut Formatter<'_>| tag.fmt(f)),
+        )?;
+        write!(f, "]")
+    }
+}
+
+impl IntoIterator for NbtList {
+    type Item = NbtTag;
+    type IntoIter = std::vec::IntoIter<NbtTag>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let tags: Vec<NbtTag> = with_elements!(
+            self,
+            elements => elements.into_iter().map(ListElement::into_tag).collect(),
+            Empty => Vec::new()
+        );
+        tags.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a NbtList {
+    type Item = NbtTagView<'a>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut NbtList {
+    type Item = NbtTagViewMut<'a>;
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Iterator over views of the elements of a [NbtList].
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    list: &'a NbtList,
+    front: usize,
+    back: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = NbtTagView<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.list.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.list.get(self.back)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+/// Iterator over mutable views of the elements of a [NbtList].
+pub struct IterMut<'a>(Box<dyn Iterator<Item = NbtTagViewMut<'a>> + 'a>);
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = NbtTagViewMut<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Borrowed view of a tag, used for elements of lists which don't store them as [NbtTag]s.
+/// Numbers are copied, other values are borrowed.
+#[derive(Clone, Copy, Debug)]
+pub enum NbtTagView<'a> {
+    End,
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(&'a Bytes),
+    String(&'a String),
+    List(&'a NbtList),
+    Compound(&'a NbtCompound),
+    IntArray(&'a Vec<i32>),
+    LongArray(&'a Vec<i64>),
+}
+
+impl<'a> NbtTagView<'a> {
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            NbtTagView::End => END_ID,
+            NbtTagView::Byte(_) => BYTE_ID,
+            NbtTagView::Short(_) => SHORT_ID,
+            NbtTagView::Int(_) => INT_ID,
+            NbtTagView::Long(_) => LONG_ID,
+            NbtTagView::Float(_) => FLOAT_ID,
+            NbtTagView::Double(_) => DOUBLE_ID,
+            NbtTagView::ByteArray(_) => BYTE_ARRAY_ID,
+            NbtTagView::String(_) => STRING_ID,
+            NbtTagView::List(_) => LIST_ID,
+            NbtTagView::Compound(_) => COMPOUND_ID,
+            NbtTagView::IntArray(_) => INT_ARRAY_ID,
+            NbtTagView::LongArray(_) => LONG_ARRAY_ID,
+        }
+    }
+
+    /// Clones the viewed value into a tag.
+    pub fn to_tag(&self) -> NbtTag {
+        match *self {
+            NbtTagView::End => NbtTag::End,
+            NbtTagView::Byte(byte) => NbtTag::Byte(byte),
+            NbtTagView::Short(short) => NbtTag::Short(short),
+            NbtTagView::Int(int) => NbtTag::Int(int),
+            NbtTagView::Long(long) => NbtTag::Long(long),
+            NbtTagView::Float(float) => NbtTag::Float(float),
+            NbtTagView::Double(double) => NbtTag::Double(double),
+            NbtTagView::ByteArray(byte_array) => NbtTag::ByteArray(byte_array.clone()),
+            NbtTagView::String(string) => NbtTag::String(string.clone()),
+            NbtTagView::List(list) => NbtTag::List(list.clone()),
+            NbtTagView::Compound(compound) => NbtTag::Compound(compound.clone()),
+            NbtTagView::IntArray(int_array) => NbtTag::IntArray(int_array.clone()),
+            NbtTagView::LongArray(long_array) => NbtTag::LongArray(long_array.clone()),
+        }
+    }
+
+    pub fn extract_byte(&self) -> Option<i8> {
+        match self {
+            NbtTagView::Byte(byte) => Some(*byte),
+            _ => None,
+        }
+    }
+
+    pub fn extract_short(&self) -> Option<i16> {
+        match self {
+            NbtTagView::Short(short) => Some(*short),
+            _ => None,
+        }
+    }
+
+    pub fn extract_int(&self) -> Option<i32> {
+        match self {
+            NbtTagView::Int(int) => Some(*int),
+            _ => None,
+        }
+    }
+
+    pub fn extract_long(&self) -> Option<i64> {
+        match self {
+            NbtTagView::Long(long) => Some(*long),
+            _ => None,
+        }
+    }
+
+    pub fn extract_float(&self) -> Option<f32> {
+        match self {
+            NbtTagView::Float(float) => Some(*float),
+            _ => None,
+        }
+    }
+
+    pub fn extract_double(&self) -> Option<f64> {
+        match self {
+            NbtTagView::Double(double) => Some(*double),
+            _ => None,
+        }
+    }
+
+    pub fn extract_bool(&self) -> Option<bool> {
+        match self {
+            NbtTagView::Byte(byte) => Some(*byte != 0),
+            _ => None,
+        }
+    }
+
+    pub fn extract_byte_array(&self) -> Option<Bytes> {
+        match self {
+            NbtTagView::ByteArray(byte_array) => Some((*byte_array).clone()),
+            _ => None,
+        }
+    }
+
+    pub fn extract_string(&self) -> Option<&'a String> {
+        match self {
+            NbtTagView::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn extract_list(&self) -> Option<&'a NbtList> {
+        match self {
+            NbtTagView::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn extract_compound(&self) -> Option<&'a NbtCompound> {
+        match self {
+            NbtTagView::Compound(compound) => Some(compound),
+            _ => None,
+        }
+    }
+
+    pub fn extract_int_array(&self) -> Option<&'a Vec<i32>> {
+        match self {
+            NbtTagView::IntArray(int_array) => Some(int_array),
+            _ => None,
+        }
+    }
+
+    pub fn extract_long_array(&self) -> Option<&'a Vec<i64>> {
+        match self {
+            NbtTagView::LongArray(long_array) => Some(long_array),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<&'a NbtTag> for NbtTagView<'a> {
+    fn from(tag: &'a NbtTag) -> Self {
+        match tag {
+            NbtTag::End => NbtTagView::End,
+            NbtTag::Byte(byte) => NbtTagView::Byte(*byte),
+            NbtTag::Short(short) => NbtTagView::Short(*short),
+            NbtTag::Int(int) => NbtTagView::Int(*int),
+            NbtTag::Long(long) => NbtTagView::Long(*long),
+            NbtTag::Float(float) => NbtTagView::Float(*float),
+            NbtTag::Double(double) => NbtTagView::Double(*double),
+            NbtTag::ByteArray(byte_array) => NbtTagView::ByteArray(byte_array),
+            NbtTag::String(string) => NbtTagView::String(string),
+            NbtTag::List(list) => NbtTagView::List(list),
+            NbtTag::Compound(compound) => NbtTagView::Compound(compound),
+            NbtTag::IntArray(int_array) => NbtTagView::IntArray(int_array),
+            NbtTag::LongArray(long_array) => NbtTagView::LongArray(long_array),
+        }
+    }
+}
+
+impl PartialEq for NbtTagView<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl PartialEq<NbtTag> for NbtTagView<'_> {
+    fn eq(&self, other: &NbtTag) -> bool {
+        *self == NbtTagView::from(other)
+    }
+}
+
+impl Eq for NbtTagView<'_> {}
+
+impl PartialOrd for NbtTagView<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NbtTagView<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Byte(a), Self::Byte(b)) => a.cmp(b),
+            (Self::Short(a), Self::Short(b)) => a.cmp(b),
+            (Self::Int(a), Self::Int(b)) => a.cmp(b),
+            (Self::Long(a), Self::Long(b)) => a.cmp(b),
+            (Self::Float(a), Self::Float(b)) => a.total_cmp(b),
+            (Self::Double(a), Self::Double(b)) => a.total_cmp(b),
+            (Self::ByteArray(a), Self::ByteArray(b)) => a.cmp(b),
+            (Self::String(a), Self::String(b)) => a.cmp(b),
+            (Self::List(a), Self::List(b)) => a.cmp(b),
+            (Self::Compound(a), Self::Compound(b)) => a.cmp(b),
+            (Self::IntArray(a), Self::IntArray(b)) => a.cmp(b),
+            (Self::LongArray(a), Self::LongArray(b)) => a.cmp(b),
+            _ => self.get_type_id().cmp(&other.get_type_id()),
+        }
+    }
+}
+
+impl Display for NbtTagView<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::End => Ok(()),
+            Self::Byte(x) => write!(f, "{x}b"),
+            Self::Short(x) => write!(f, "{x}s"),
+            Self::Int(x) => write!(f, "{x}"),
+            Self::Long(x) => write!(f, "{x}L"),
+            // using debug here matches Minecraft on whole numbers (3.0 instead of 3)
+            Self::Float(x) => write!(f, "{x:?}f"),
+            Self::Double(x) => write!(f, "{x:?}d"),
+            Self::ByteArray(arr) => write_listlike(f, "B; ", "B", arr.iter().map(|b| *b as i8)),
+            Self::String(s) => write!(f, "{}", escape_string_value(s)),
+            Self::List(list) => write!(f, "{list}"),
+            Self::Compound(compound) => write!(f, "{compound}"),
+            Self::IntArray(arr) => write_listlike(f, "I; ", "", arr.iter()),
+            Self::LongArray(arr) => write_listlike(f, "L; ", "L", arr.iter()),
+        }
+    }
+}
+
+fn write_listlike<T: Display>(
+    f: &mut Formatter<'_>,
+    prefix: &'static str,
+    affix: &'static str,
+    arr: impl Iterator<Item = T>,
+) -> fmt::Result {
+    write!(f, "[{prefix}")?;
+    join_formatted(
+        f,
+        ", ",
+        arr.map(|x| move |f: &mut Formatter<'_>| write!(f, "{x}{affix}")),
+    )?;
+    write!(f, "]")
+}
+
+/// Mutable view of a tag, used for elements of lists which don't store them as [NbtTag]s.
+#[derive(Debug)]
+pub enum NbtTagViewMut<'a> {
+    End,
+    Byte(&'a mut i8),
+    Short(&'a mut i16),
+    Int(&'a mut i32),
+    Long(&'a mut i64),
+    Float(&'a mut f32),
+    Double(&'a mut f64),
+    ByteArray(&'a mut Bytes),
+    String(&'a mut String),
+    List(&'a mut NbtList),
+    Compound(&'a mut NbtCompound),
+    IntArray(&'a mut Vec<i32>),
+    LongArray(&'a mut Vec<i64>),
+}
+
+impl<'a> NbtTagViewMut<'a> {
+    /// Returns an immutable view of the same value.
+    pub fn as_view(&self) -> NbtTagView<'_> {
+        match self {
+            NbtTagViewMut::End => NbtTagView::End,
+            NbtTagViewMut::Byte(byte) => NbtTagView::Byte(**byte),
+            NbtTagViewMut::Short(short) => NbtTagView::Short(**short),
+            NbtTagViewMut::Int(int) => NbtTagView::Int(**int),
+            NbtTagViewMut::Long(long) => NbtTagView::Long(**long),
+            NbtTagViewMut::Float(float) => NbtTagView::Float(**float),
+            NbtTagViewMut::Double(double) => NbtTagView::Double(**double),
+            NbtTagViewMut::ByteArray(byte_array) => NbtTagView::ByteArray(byte_array),
+            NbtTagViewMut::String(string) => NbtTagView::String(string),
+            NbtTagViewMut::List(list) => NbtTagView::List(list),
+            NbtTagViewMut::Compound(compound) => NbtTagView::Compound(compound),
+            NbtTagViewMut::IntArray(int_array) => NbtTagView::IntArray(int_array),
+            NbtTagViewMut::LongArray(long_array) => NbtTagView::LongArray(long_array),
+        }
+    }
+
+    pub fn get_type_id(&self) -> u8 {
+        self.as_view().get_type_id()
+    }
+
+    /// Clones the viewed value into a tag.
+    pub fn to_tag(&self) -> NbtTag {
+        self.as_view().to_tag()
+    }
+
+    /// Overwrites the viewed value with the tag, returning an error if their types differ.
+    pub fn set(&mut self, tag: impl Into<NbtTag>) -> Result<(), Error> {
+        match (self, tag.into()) {
+            (NbtTagViewMut::Byte(old), NbtTag::Byte(new)) => **old = new,
+            (NbtTagViewMut::Short(old), NbtTag::Short(new)) => **old = new,
+            (NbtTagViewMut::Int(old), NbtTag::Int(new)) => **old = new,
+            (NbtTagViewMut::Long(old), NbtTag::Long(new)) => **old = new,
+            (NbtTagViewMut::Float(old), NbtTag::Float(new)) => **old = new,
+            (NbtTagViewMut::Double(old), NbtTag::Double(new)) => **old = new,
+            (NbtTagViewMut::ByteArray(old), NbtTag::ByteArray(new)) => **old = new,
+            (NbtTagViewMut::String(old), NbtTag::String(new)) => **old = new,
+            (NbtTagViewMut::List(old), NbtTag::List(new)) => **old = new,
+            (NbtTagViewMut::Compound(old), NbtTag::Compound(new)) => **old = new,
+            (NbtTagViewMut::IntArray(old), NbtTag::IntArray(new)) => **old = new,
+            (NbtTagViewMut::LongArray(old), NbtTag::LongArray(new)) => **old = new,
+            (NbtTagViewMut::End, NbtTag::End) => {}
+            (old, new) => {
+                return Err(Error::ListTypeMismatch {
+                    list_type: old.get_type_id(),
+                    tag_type: new.get_type_id(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    pub fn extract_byte_mut(self) -> Option<&'a mut i8> {
+        match self {
+            NbtTagViewMut::Byte(byte) => Some(byte),
+            _ => None,
+        }
+    }
+
+    pub fn extract_short_mut(self) -> Option<&'a mut i16> {
+        match self {
+            NbtTagViewMut::Short(short) => Some(short),
+            _ => None,
+        }
+    }
+
+    pub fn extract_int_mut(self) -> Option<&'a mut i32> {
+        match self {
+            NbtTagViewMut::Int(int) => Some(int),
+            _ => None,
+        }
+    }
+
+    pub fn extract_long_mut(self) -> Option<&'a mut i64> {
+        match self {
+            NbtTagViewMut::Long(long) => Some(long),
+            _ => None,
+        }
+    }
+
+    pub fn extract_float_mut(self) -> Option<&'a mut f32> {
+        match self {
+            NbtTagViewMut::Float(float) => Some(float),
+            _ => None,
+        }
+    }
+
+    pub fn extract_double_mut(self) -> Option<&'a mut f64> {
+        match self {
+            NbtTagViewMut::Double(double) => Some(double),
+            _ => None,
+        }
+    }
+
+    pub fn extract_byte_array_mut(self) -> Option<&'a mut Bytes> {
+        match self {
+            NbtTagViewMut::ByteArray(byte_array) => Some(byte_array),
+            _ => None,
+        }
+    }
+
+    pub fn extract_string_mut(self) -> Option<&'a mut String> {
+        match self {
+            NbtTagViewMut::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn extract_list_mut(self) -> Option<&'a mut NbtList> {
+        match self {
+            NbtTagViewMut::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn extract_compound_mut(self) -> Option<&'a mut NbtCompound> {
+        match self {
+            NbtTagViewMut::Compound(compound) => Some(compound),
+            _ => None,
+        }
+    }
+
+    pub fn extract_int_array_mut(self) -> Option<&'a mut Vec<i32>> {
+        match self {
+            NbtTagViewMut::IntArray(int_array) => Some(int_array),
+            _ => None,
+        }
+    }
+
+    pub fn extract_long_array_mut(self) -> Option<&'a mut Vec<i64>> {
+        match self {
+            NbtTagViewMut::LongArray(long_array) => Some(long_array),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<&'a mut NbtTag> for NbtTagViewMut<'a> {
+    fn from(tag: &'a mut NbtTag) -> Self {
+        match tag {
+            NbtTag::End => NbtTagViewMut::End,
+            NbtTag::Byte(byte) => NbtTagViewMut::Byte(byte),
+            NbtTag::Short(short) => NbtTagViewMut::Short(short),
+            NbtTag::Int(int) => NbtTagViewMut::Int(int),
+            NbtTag::Long(long) => NbtTagViewMut::Long(long),
+            NbtTag::Float(float) => NbtTagViewMut::Float(float),
+            NbtTag::Double(double) => NbtTagViewMut::Double(double),
+            NbtTag::ByteArray(byte_array) => NbtTagViewMut::ByteArray(byte_array),
+            NbtTag::String(string) => NbtTagViewMut::String(string),
+            NbtTag::List(list) => NbtTagViewMut::List(list),
+            NbtTag::Compound(compound) => NbtTagViewMut::Compound(compound),
+            NbtTag::IntArray(int_array) => NbtTagViewMut::IntArray(int_array),
+            NbtTag::LongArray(long_array) => NbtTagViewMut::LongArray(long_array),
+        }
+    }
+}
+
+impl PartialEq<NbtTag> for NbtTagViewMut<'_> {
+    fn eq(&self, other: &NbtTag) -> bool {
+        self.as_view() == NbtTagView::from(other)
+    }
+}
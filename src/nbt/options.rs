@@ -0,0 +1,357 @@
+use crate::error::Error;
+use bytes::{Buf, Bytes};
+use crab_nbt::nbt::flavor::NbtFlavor;
+use crab_nbt::nbt::path::{NbtPath, PathSegment};
+use crab_nbt::nbt::utils::{try_copy_to_bytes, END_ID};
+
+/// Nesting limit used by vanilla.
+pub(crate) const DEFAULT_MAX_DEPTH: usize = 512;
+
+/// Options used when reading NBT, including limits protecting against malicious input.
+///
+/// The default options don't limit the size of the data, but still limit the nesting depth
+/// to the same value as vanilla. Use [ReadOptions::network] for data sent by clients.
+/// Options are changed with the `with_*` methods, so that new ones can be added.
+///
+/// ```
+/// use crab_nbt::ReadOptions;
+///
+/// let options = ReadOptions::network().with_max_depth(16).with_max_array_len(1024);
+/// assert_eq!(options.max_depth, 16);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ReadOptions {
+    pub flavor: NbtFlavor,
+    /// Maximum number of nested compounds and lists, including the root compound.
+    pub max_depth: usize,
+    /// Maximum number of bytes that can be read from the input.
+    pub max_total_bytes: usize,
+    /// Maximum number of elements in a single list or array.
+    pub max_array_len: usize,
+    /// Whether to unwrap elements of lists of compounds which are compounds with a single
+    /// empty key, used since Minecraft 1.21.5 for lists with elements of different types.
+    /// Such lists are read as [NbtList::Mixed](crate::NbtList::Mixed).
+    /// Only used when reading into tags, serde unwraps elements of tuples regardless of it.
+    pub unwrap_mixed_lists: bool,
+}
+
+impl ReadOptions {
+    /// Limits used by vanilla servers for NBT received over the network.
+    pub const fn network() -> ReadOptions {
+        ReadOptions {
+            flavor: NbtFlavor::JavaBE,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_total_bytes: 2 * 1024 * 1024,
+            max_array_len: usize::MAX,
+            unwrap_mixed_lists: false,
+        }
+    }
+
+    pub const fn with_flavor(self, flavor: NbtFlavor) -> ReadOptions {
+        ReadOptions { flavor, ..self }
+    }
+
+    pub const fn with_max_depth(self, max_depth: usize) -> ReadOptions {
+        ReadOptions { max_depth, ..self }
+    }
+
+    pub const fn with_max_total_bytes(self, max_total_bytes: usize) -> ReadOptions {
+        ReadOptions {
+            max_total_bytes,
+            ..self
+        }
+    }
+
+    pub const fn with_max_array_len(self, max_array_len: usize) -> ReadOptions {
+        ReadOptions {
+            max_array_len,
+            ..self
+        }
+    }
+
+    pub const fn with_unwrap_mixed_lists(self, unwrap_mixed_lists: bool) -> ReadOptions {
+        ReadOptions {
+            unwrap_mixed_lists,
+            ..self
+        }
+    }
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        ReadOptions {
+            flavor: NbtFlavor::JavaBE,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_total_bytes: usize::MAX,
+            max_array_len: usize::MAX,
+            unwrap_mixed_lists: false,
+        }
+    }
+}
+
+/// State of a single read, used to enforce [ReadOptions].
+#[derive(Debug)]
+pub(crate) struct ReadContext {
+    options: ReadOptions,
+    depth: usize,
+    /// Remaining bytes of the input when the read started,
+    /// used to calculate how many bytes were consumed.
+    start_remaining: usize,
+    /// Present when reading leniently.
+    recovery: Option<Recovery>,
+}
+
+/// State of a lenient read.
+#[derive(Debug, Default)]
+struct Recovery {
+    /// Path to the tag that is currently being read.
+    path: NbtPath,
+    diagnostics: Vec<Error>,
+    /// Set after an error which makes it impossible to read the rest of the input.
+    stopped: bool,
+}
+
+impl ReadContext {
+    pub(crate) fn new(options: ReadOptions, bytes: &impl Buf) -> ReadContext {
+        ReadContext {
+            options,
+            depth: 0,
+            start_remaining: bytes.remaining(),
+            recovery: None,
+        }
+    }
+
+    /// Creates a context which recovers from malformed data instead of failing,
+    /// see [ReadContext::recover].
+    pub(crate) fn lenient(options: ReadOptions, bytes: &impl Buf) -> ReadContext {
+        ReadContext {
+            recovery: Some(Recovery::default()),
+            ..ReadContext::new(options, bytes)
+        }
+    }
+
+    /// Errors that were recovered from during a lenient read.
+    pub(crate) fn into_diagnostics(self) -> Vec<Error> {
+        self.recovery
+            .map(|recovery| recovery.diagnostics)
+            .unwrap_or_default()
+    }
+
+    /// Whether a lenient read stopped, in which case every compound and list
+    /// has to return what it has read so far.
+    pub(crate) fn is_stopped(&self) -> bool {
+        self.recovery
+            .as_ref()
+            .is_some_and(|recovery| recovery.stopped)
+    }
+
+    /// Marks the start of reading the child tag at `segment`, so that diagnostics contain
+    /// its path. Every call has to be followed by [ReadContext::exit_child].
+    pub(crate) fn enter_child(&mut self, segment: impl FnOnce() -> PathSegment) {
+        if let Some(recovery) = &mut self.recovery {
+            recovery.path.push(segment());
+        }
+    }
+
+    pub(crate) fn exit_child(&mut self) {
+        if let Some(recovery) = &mut self.recovery {
+            recovery.path.pop();
+        }
+    }
+
+    /// Handles an error that occurred while reading a compound or a list.
+    ///
+    /// When reading strictly the error is returned. When reading leniently it's recorded
+    /// and reading stops, since the position of the next tag can't be known anymore.
+    /// Exceeded limits are never recovered from.
+    pub(crate) fn recover(&mut self, bytes: &impl Buf, error: Error) -> Result<(), Error> {
+        let is_limit = matches!(
+            error.inner(),
+            Error::DepthLimitExceeded(_) | Error::SizeLimitExceeded(_) | Error::ArrayTooLong { .. }
+        );
+        if is_limit || self.recovery.is_none() {
+            return Err(error);
+        }
+
+        self.warn(bytes, error);
+        if let Some(recovery) = &mut self.recovery {
+            recovery.stopped = true;
+        }
+        Ok(())
+    }
+
+    /// Records an error after which a lenient read can continue.
+    fn warn(&mut self, bytes: &impl Buf, error: Error) {
+        let current_offset = self.offset(bytes);
+        let Some(recovery) = &mut self.recovery else {
+            return;
+        };
+
+        // Errors located by the tag that failed only contain the path relative to it
+        let mut path = recovery.path.clone();
+        let (offset, source) = match error {
+            Error::At {
+                offset,
+                path: relative_path,
+                source,
+            } => {
+                relative_path
+                    .segments()
+                    .iter()
+                    .for_each(|segment| path.push(segment.clone()));
+                (offset, source)
+            }
+            error => (current_offset, Box::new(error)),
+        };
+        recovery.diagnostics.push(Error::At {
+            offset,
+            path,
+            source,
+        });
+    }
+
+    pub(crate) fn flavor(&self) -> NbtFlavor {
+        self.options.flavor
+    }
+
+    pub(crate) fn unwraps_mixed_lists(&self) -> bool {
+        self.options.unwrap_mixed_lists
+    }
+
+    /// Accounts for `len` bytes added to the input after the read started,
+    /// used when the input is read incrementally.
+    pub(crate) fn extend_input(&mut self, len: usize) {
+        self.start_remaining += len;
+    }
+
+    /// Number of bytes read so far.
+    pub(crate) fn offset(&self, bytes: &impl Buf) -> usize {
+        self.start_remaining.saturating_sub(bytes.remaining())
+    }
+
+    /// Adds the current offset to an error, unless it already has a location.
+    pub(crate) fn locate(&self, bytes: &impl Buf, error: Error) -> Error {
+        match error {
+            Error::At { .. } => error,
+            error => Error::At {
+                offset: self.offset(bytes),
+                path: NbtPath::new(),
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// Locates an error that occurred while reading the child tag at `segment`.
+    pub(crate) fn locate_in(&self, bytes: &impl Buf, error: Error, segment: PathSegment) -> Error {
+        let mut error = self.locate(bytes, error);
+        if let Error::At { path, .. } = &mut error {
+            path.push_front(segment);
+        }
+        error
+    }
+
+    /// Runs `read` one nesting level deeper, used for the content of compounds and lists.
+    pub(crate) fn nested<T>(
+        &mut self,
+        read: impl FnOnce(&mut ReadContext) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        self.enter()?;
+        let result = read(self);
+        self.exit();
+        result
+    }
+
+    /// Goes one nesting level deeper. Every call has to be followed by [ReadContext::exit].
+    pub(crate) fn enter(&mut self) -> Result<(), Error> {
+        if self.depth >= self.options.max_depth {
+            return Err(Error::DepthLimitExceeded(self.options.max_depth));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    pub(crate) fn exit(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Makes sure that `additional` bytes can be read without exceeding the size limit.
+    pub(crate) fn reserve(&self, bytes: &impl Buf, additional: usize) -> Result<(), Error> {
+        if self.offset(bytes).saturating_add(additional) > self.options.max_total_bytes {
+            return Err(Error::SizeLimitExceeded(self.options.max_total_bytes));
+        }
+        Ok(())
+    }
+
+    /// Reads the length of a list or an array, whose elements take at least `element_size` bytes.
+    pub(crate) fn get_len(
+        &self,
+        bytes: &mut impl Buf,
+        element_size: usize,
+    ) -> Result<usize, Error> {
+        let len = self.flavor().get_i32(bytes)?;
+        let len = usize::try_from(len).map_err(|_| Error::NegativeLength(len))?;
+        if len > self.options.max_array_len {
+            return Err(Error::ArrayTooLong {
+                len,
+                max: self.options.max_array_len,
+            });
+        }
+        self.reserve(bytes, len.saturating_mul(element_size))?;
+        Ok(len)
+    }
+
+    /// Reads the type of the elements and the length of a list.
+    pub(crate) fn get_list_header(&mut self, bytes: &mut impl Buf) -> Result<(u8, usize), Error> {
+        let element_type = bytes.try_get_u8()?;
+        // Every element takes at least one byte, apart from `END`s which can't be in a non-empty list
+        let len = self.get_len(bytes, 1)?;
+        if element_type == END_ID && len > 0 {
+            if self.recovery.is_none() {
+                return Err(Error::MissingListType(len));
+            }
+            // `END`s don't have any data, so the list can be read as an empty one
+            self.warn(bytes, Error::MissingListType(len));
+            return Ok((END_ID, 0));
+        }
+        Ok((element_type, len))
+    }
+
+    /// Size of an array element with the given fixed size in the current flavor.
+    pub(crate) fn array_element_size(&self, size: usize) -> usize {
+        match self.flavor() {
+            // Variable-length integers take at least one byte
+            NbtFlavor::BedrockNetwork => 1,
+            NbtFlavor::JavaBE | NbtFlavor::BedrockLE => size,
+        }
+    }
+
+    pub(crate) fn get_byte_array(&self, bytes: &mut impl Buf) -> Result<Bytes, Error> {
+        let len = self.get_len(bytes, 1)?;
+        try_copy_to_bytes(bytes, len)
+    }
+
+    pub(crate) fn get_int_array(&self, bytes: &mut impl Buf) -> Result<Vec<i32>, Error> {
+        let len = self.get_len(bytes, self.array_element_size(size_of::<i32>()))?;
+        self.flavor().get_i32_array(bytes, len)
+    }
+
+    pub(crate) fn get_long_array(&self, bytes: &mut impl Buf) -> Result<Vec<i64>, Error> {
+        let len = self.get_len(bytes, self.array_element_size(size_of::<i64>()))?;
+        self.flavor().get_i64_array(bytes, len)
+    }
+
+    /// Reads a string. When reading leniently, invalid characters are replaced with `U+FFFD`.
+    pub(crate) fn get_string(&mut self, bytes: &mut impl Buf) -> Result<String, Error> {
+        let len = self.flavor().get_string_len(bytes)?;
+        self.reserve(bytes, len)?;
+        let string_bytes = try_copy_to_bytes(bytes, len)?;
+        match self.flavor().decode_string(&string_bytes) {
+            Err(error) if self.recovery.is_some() => {
+                self.warn(bytes, error);
+                Ok(self.flavor().decode_string_lossy(&string_bytes))
+            }
+            result => result,
+        }
+    }
+}
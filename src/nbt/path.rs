@@ -0,0 +1,124 @@
+use crate::error::Error;
+use crab_nbt::nbt::compound::NbtCompound;
+use crab_nbt::nbt::snbt::SnbtParser;
+use crab_nbt::nbt::utils::escape_string_value;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Path leading to tags inside of NBT, like `Level.Sections[3].BlockStates`.
+///
+/// Paths can be parsed from the syntax used by the `/data` command,
+/// including patterns like `Items[{Slot:3b}]` and wildcards like `Inventory[].id`,
+/// and used with [NbtCompound::query] and related methods.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NbtPath {
+    segments: Vec<PathSegment>,
+}
+
+/// Single step of a [NbtPath].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    /// Child of a compound with the given name.
+    Key(String),
+    /// Element of a list or an array at the given index.
+    Index(usize),
+    /// Element of a list counted from its end, where `1` is the last element (`[-1]`).
+    IndexFromEnd(usize),
+    /// Every element of a list (`[]`).
+    AllElements,
+    /// Elements of a list which match the pattern (`[{Slot:3b}]`).
+    MatchElements(NbtCompound),
+    /// Child of a compound with the given name, if it matches the pattern (`Items{id:"stone"}`).
+    MatchKey(String, NbtCompound),
+    /// The current compound, if it matches the pattern. Only used at the start of a path (`{}`).
+    MatchRoot(NbtCompound),
+}
+
+impl NbtPath {
+    pub fn new() -> NbtPath {
+        NbtPath::default()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+
+    /// Adds a segment at the start of the path, used when building it from the innermost tag.
+    pub(crate) fn push_front(&mut self, segment: PathSegment) {
+        self.segments.insert(0, segment);
+    }
+}
+
+impl From<Vec<PathSegment>> for NbtPath {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        NbtPath { segments }
+    }
+}
+
+impl FromIterator<PathSegment> for NbtPath {
+    fn from_iter<T: IntoIterator<Item = PathSegment>>(iter: T) -> Self {
+        NbtPath {
+            segments: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Display for NbtPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) => {
+                    if i > 0 {
+                        write!(f, ".")?;
+                    }
+                    write!(f, "{}", escape_path_key(key))?;
+                }
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+                PathSegment::IndexFromEnd(index) => write!(f, "[-{index}]")?,
+                PathSegment::AllElements => write!(f, "[]")?,
+                PathSegment::MatchElements(pattern) => write!(f, "[{pattern}]")?,
+                PathSegment::MatchKey(key, pattern) => {
+                    if i > 0 {
+                        write!(f, ".")?;
+                    }
+                    write!(f, "{}{pattern}", escape_path_key(key))?;
+                }
+                PathSegment::MatchRoot(pattern) => write!(f, "{pattern}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for NbtPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SnbtParser::new(s).parse_path()
+    }
+}
+
+/// Quotes keys which can't be used in a path without quotes, following the `/data` command.
+fn escape_path_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(is_allowed_in_path_key) {
+        key.to_owned()
+    } else {
+        escape_string_value(key)
+    }
+}
+
+pub(crate) fn is_allowed_in_path_key(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '.' | '"' | '\'' | '[' | ']' | '{' | '}')
+}
@@ -0,0 +1,334 @@
+use crab_nbt::nbt::compound::NbtCompound;
+use crab_nbt::nbt::list::{NbtList, NbtTagView, NbtTagViewMut};
+use crab_nbt::nbt::path::{NbtPath, PathSegment};
+use crab_nbt::nbt::tag::NbtTag;
+use std::mem;
+
+/// Path queries following the behavior of the `/data` command.
+///
+/// Elements of byte, int and long arrays are not stored as tags, so they can't be accessed.
+impl NbtCompound {
+    /// Returns every tag at the given path.
+    pub fn query(&self, path: &NbtPath) -> Vec<NbtTagView<'_>> {
+        let Some((first, rest)) = self.root_segments(path).split_first() else {
+            return Vec::new();
+        };
+
+        let mut tags = Vec::new();
+        compound_children(self, first, &mut tags);
+        for segment in rest {
+            let mut children = Vec::new();
+            for tag in tags {
+                tag_children(tag, segment, &mut children);
+            }
+            tags = children;
+        }
+        tags
+    }
+
+    /// Like [NbtCompound::query], but returns mutable references.
+    pub fn query_mut(&mut self, path: &NbtPath) -> Vec<NbtTagViewMut<'_>> {
+        let segments = self.root_segments(path);
+        let Some((first, rest)) = segments.split_first() else {
+            return Vec::new();
+        };
+
+        let mut tags = Vec::new();
+        compound_children_mut(self, first, &mut tags);
+        for segment in rest {
+            let mut children = Vec::new();
+            for tag in tags {
+                tag_children_mut(tag, segment, &mut children);
+            }
+            tags = children;
+        }
+        tags
+    }
+
+    /// Sets every tag at the given path, creating missing compounds and lists leading to it.
+    /// Elements of lists are only replaced with tags of the same type.
+    ///
+    /// Returns the number of tags that were changed.
+    pub fn set_at(&mut self, path: &NbtPath, tag: impl Into<NbtTag>) -> usize {
+        let tag = tag.into();
+        let Some((last, parents)) = path.segments().split_last() else {
+            return 0;
+        };
+
+        let mut tags = vec![NbtTagViewMut::Compound(self)];
+        for (i, segment) in parents.iter().enumerate() {
+            let next = parents.get(i + 1).unwrap_or(last);
+            let mut children = Vec::new();
+            for tag in tags {
+                get_or_create_children(tag, segment, next, &mut children);
+            }
+            tags = children;
+        }
+        tags.into_iter()
+            .map(|parent| set_child(parent, last, &tag))
+            .sum()
+    }
+
+    /// Removes every tag at the given path. Returns the number of removed tags.
+    pub fn remove_at(&mut self, path: &NbtPath) -> usize {
+        let Some((last, parents)) = path.segments().split_last() else {
+            return 0;
+        };
+
+        let mut tags = vec![NbtTagViewMut::Compound(self)];
+        for segment in parents {
+            let mut children = Vec::new();
+            for tag in tags {
+                tag_children_mut(tag, segment, &mut children);
+            }
+            tags = children;
+        }
+        tags.into_iter()
+            .map(|parent| remove_child(parent, last))
+            .sum()
+    }
+
+    /// Matches patterns at the start of the path against this compound,
+    /// returning the rest of the path or nothing if they don't match.
+    fn root_segments<'a>(&self, path: &'a NbtPath) -> &'a [PathSegment] {
+        let mut segments = path.segments();
+        while let Some((PathSegment::MatchRoot(pattern), rest)) = segments.split_first() {
+            if !compound_matches(pattern, self) {
+                return &[];
+            }
+            segments = rest;
+        }
+        segments
+    }
+}
+
+/// Checks if the tag contains everything from the pattern.
+/// Lists match if every element of the pattern matches one of their elements.
+fn matches(pattern: NbtTagView, tag: NbtTagView) -> bool {
+    match (pattern, tag) {
+        (NbtTagView::Compound(pattern), NbtTagView::Compound(compound)) => {
+            compound_matches(pattern, compound)
+        }
+        (NbtTagView::List(pattern), NbtTagView::List(list)) if !pattern.is_empty() => pattern
+            .iter()
+            .all(|pattern| list.iter().any(|tag| matches(pattern, tag))),
+        _ => pattern == tag,
+    }
+}
+
+fn compound_matches(pattern: &NbtCompound, compound: &NbtCompound) -> bool {
+    pattern.iter().all(|(key, pattern)| {
+        compound
+            .get(key)
+            .is_some_and(|tag| matches(pattern.into(), tag.into()))
+    })
+}
+
+fn is_matching_compound(pattern: &NbtCompound, tag: NbtTagView) -> bool {
+    tag.extract_compound()
+        .is_some_and(|compound| compound_matches(pattern, compound))
+}
+
+/// Resolves an index segment for a list with the given length.
+fn list_index(segment: &PathSegment, len: usize) -> Option<usize> {
+    match segment {
+        PathSegment::Index(index) if *index < len => Some(*index),
+        PathSegment::IndexFromEnd(index) if *index > 0 => len.checked_sub(*index),
+        _ => None,
+    }
+}
+
+fn compound_children<'a>(
+    compound: &'a NbtCompound,
+    segment: &PathSegment,
+    children: &mut Vec<NbtTagView<'a>>,
+) {
+    let child = match segment {
+        PathSegment::Key(key) => compound.get(key),
+        PathSegment::MatchKey(key, pattern) => compound
+            .get(key)
+            .filter(|tag| is_matching_compound(pattern, (*tag).into())),
+        _ => None,
+    };
+    children.extend(child.map(NbtTagView::from));
+}
+
+fn tag_children<'a>(
+    tag: NbtTagView<'a>,
+    segment: &PathSegment,
+    children: &mut Vec<NbtTagView<'a>>,
+) {
+    match (segment, tag) {
+        (PathSegment::MatchRoot(pattern), tag) if is_matching_compound(pattern, tag) => {
+            children.push(tag);
+        }
+        (_, NbtTagView::Compound(compound)) => compound_children(compound, segment, children),
+        (PathSegment::AllElements, NbtTagView::List(list)) => children.extend(list),
+        (PathSegment::MatchElements(pattern), NbtTagView::List(list)) => children.extend(
+            list.iter()
+                .filter(|tag| is_matching_compound(pattern, *tag)),
+        ),
+        (_, NbtTagView::List(list)) => {
+            children.extend(list_index(segment, list.len()).and_then(|index| list.get(index)));
+        }
+        _ => {}
+    }
+}
+
+fn compound_children_mut<'a>(
+    compound: &'a mut NbtCompound,
+    segment: &PathSegment,
+    children: &mut Vec<NbtTagViewMut<'a>>,
+) {
+    let child = match segment {
+        PathSegment::Key(key) => compound.get_mut(key),
+        PathSegment::MatchKey(key, pattern) => compound
+            .get_mut(key)
+            .filter(|tag| is_matching_compound(pattern, NbtTagView::from(&**tag))),
+        _ => None,
+    };
+    children.extend(child.map(NbtTagViewMut::from));
+}
+
+fn tag_children_mut<'a>(
+    tag: NbtTagViewMut<'a>,
+    segment: &PathSegment,
+    children: &mut Vec<NbtTagViewMut<'a>>,
+) {
+    match (segment, tag) {
+        (PathSegment::MatchRoot(pattern), tag) if is_matching_compound(pattern, tag.as_view()) => {
+            children.push(tag);
+        }
+        (_, NbtTagViewMut::Compound(compound)) => {
+            compound_children_mut(compound, segment, children)
+        }
+        (PathSegment::AllElements, NbtTagViewMut::List(list)) => children.extend(list),
+        (PathSegment::MatchElements(pattern), NbtTagViewMut::List(list)) => children.extend(
+            list.iter_mut()
+                .filter(|tag| is_matching_compound(pattern, tag.as_view())),
+        ),
+        (_, NbtTagViewMut::List(list)) => {
+            if let Some(index) = list_index(segment, list.len()) {
+                children.extend(list.get_mut(index));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Empty tag that can contain the tag selected by `segment`,
+/// used for creating missing tags leading to it.
+fn parent_for(segment: &PathSegment) -> NbtTag {
+    match segment {
+        PathSegment::Key(_) | PathSegment::MatchKey(..) | PathSegment::MatchRoot(_) => {
+            NbtTag::Compound(NbtCompound::new())
+        }
+        PathSegment::Index(_)
+        | PathSegment::IndexFromEnd(_)
+        | PathSegment::AllElements
+        | PathSegment::MatchElements(_) => NbtTag::List(NbtList::new()),
+    }
+}
+
+/// Like [tag_children_mut], but creates missing tags which can contain the tag selected by `next`.
+fn get_or_create_children<'a>(
+    tag: NbtTagViewMut<'a>,
+    segment: &PathSegment,
+    next: &PathSegment,
+    children: &mut Vec<NbtTagViewMut<'a>>,
+) {
+    match (segment, tag) {
+        (PathSegment::Key(key), NbtTagViewMut::Compound(compound)) => children.push(
+            compound
+                .entry(key.clone())
+                .or_insert_with(|| parent_for(next))
+                .into(),
+        ),
+        (PathSegment::MatchKey(key, pattern), NbtTagViewMut::Compound(compound)) => {
+            let child = compound
+                .entry(key.clone())
+                .or_insert_with(|| NbtTag::Compound(pattern.clone()));
+            if is_matching_compound(pattern, NbtTagView::from(&*child)) {
+                children.push(child.into());
+            }
+        }
+        (PathSegment::AllElements, NbtTagViewMut::List(list)) => {
+            if list.is_empty() {
+                // Empty lists accept tags of any type
+                let _ = list.push(parent_for(next));
+            }
+            children.extend(list);
+        }
+        (PathSegment::MatchElements(pattern), NbtTagViewMut::List(list)) => {
+            let has_match = list.iter().any(|tag| is_matching_compound(pattern, tag));
+            if !has_match {
+                // Lists of other tags can't contain the compound, so they are left unchanged
+                let _ = list.push(pattern.clone());
+            }
+            children.extend(
+                list.iter_mut()
+                    .filter(|tag| is_matching_compound(pattern, tag.as_view())),
+            );
+        }
+        (segment, tag) => tag_children_mut(tag, segment, children),
+    }
+}
+
+/// Sets the child of `parent` selected by `segment`, returning the number of changed tags.
+fn set_child(parent: NbtTagViewMut, segment: &PathSegment, tag: &NbtTag) -> usize {
+    let replace = |mut old: NbtTagViewMut| {
+        if old == *tag {
+            return 0;
+        }
+        usize::from(old.set(tag.clone()).is_ok())
+    };
+
+    match (segment, parent) {
+        (PathSegment::Key(key), NbtTagViewMut::Compound(compound)) => {
+            let old = compound.insert(key.clone(), tag.clone());
+            usize::from(old.as_ref() != Some(tag))
+        }
+        (PathSegment::AllElements, NbtTagViewMut::List(list)) if list.is_empty() => {
+            usize::from(list.push(tag.clone()).is_ok())
+        }
+        (_, NbtTagViewMut::List(list)) if !list.accepts(tag) => 0,
+        (PathSegment::AllElements, NbtTagViewMut::List(list)) => list.iter_mut().map(replace).sum(),
+        (PathSegment::MatchElements(pattern), NbtTagViewMut::List(list)) => list
+            .iter_mut()
+            .filter(|tag| is_matching_compound(pattern, tag.as_view()))
+            .map(replace)
+            .sum(),
+        (segment, NbtTagViewMut::List(list)) => list_index(segment, list.len())
+            .and_then(|index| list.get_mut(index))
+            .map_or(0, replace),
+        _ => 0,
+    }
+}
+
+/// Removes the children of `parent` selected by `segment`, returning the number of removed tags.
+fn remove_child(parent: NbtTagViewMut, segment: &PathSegment) -> usize {
+    match (segment, parent) {
+        (PathSegment::Key(key), NbtTagViewMut::Compound(compound)) => {
+            usize::from(compound.remove(key).is_some())
+        }
+        (PathSegment::MatchKey(key, pattern), NbtTagViewMut::Compound(compound)) => {
+            let is_match = compound
+                .get(key)
+                .is_some_and(|tag| is_matching_compound(pattern, tag.into()));
+            if is_match {
+                compound.remove(key);
+            }
+            usize::from(is_match)
+        }
+        (PathSegment::AllElements, NbtTagViewMut::List(list)) => mem::take(list).len(),
+        (PathSegment::MatchElements(pattern), NbtTagViewMut::List(list)) => {
+            let len = list.len();
+            list.retain(|tag| !is_matching_compound(pattern, tag));
+            len - list.len()
+        }
+        (segment, NbtTagViewMut::List(list)) => list_index(segment, list.len())
+            .map(|index| list.remove(index))
+            .map_or(0, |_| 1),
+        _ => 0,
+    }
+}
@@ -0,0 +1,979 @@
+use crate::error::Error;
+use bytes::{Buf, Bytes, BytesMut};
+use crab_nbt::nbt::compound::NbtCompound;
+use crab_nbt::nbt::flavor::NbtFlavor;
+use crab_nbt::nbt::list::NbtList;
+use crab_nbt::nbt::options::{ReadContext, ReadOptions};
+use crab_nbt::nbt::tag::NbtTag;
+use crab_nbt::nbt::utils::*;
+use std::fmt::{self, Debug, Formatter};
+use std::io::Read;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Maximum number of elements in a single [NbtEvent::ArrayChunk].
+const ARRAY_CHUNK_LEN: usize = 4096;
+
+/// Maximum number of bytes buffered at once when skipping data from a [Read]er.
+const SKIP_CHUNK_LEN: usize = 8192;
+
+/// Event produced by [NbtReader].
+///
+/// Names are only present for tags in compounds and for the root compound of named NBT.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NbtEvent {
+    /// Start of a compound, followed by events of its tags and an [NbtEvent::End].
+    CompoundStart { name: Option<String> },
+    /// Start of a list, followed by events of its elements and an [NbtEvent::End].
+    ListStart {
+        name: Option<String>,
+        element_type: u8,
+        len: usize,
+    },
+    /// Start of a byte, int or long array, followed by [NbtEvent::ArrayChunk]s
+    /// and an [NbtEvent::End].
+    ArrayStart {
+        name: Option<String>,
+        array_type: u8,
+        len: usize,
+    },
+    /// Number or string.
+    Scalar(Option<String>, NbtTag),
+    /// Part of the elements of an array, so that large arrays don't have to be read at once.
+    ArrayChunk(ArrayChunk),
+    /// End of the innermost compound, list or array.
+    End,
+}
+
+/// Elements of an array read by [NbtReader].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArrayChunk {
+    Bytes(Bytes),
+    Ints(Vec<i32>),
+    Longs(Vec<i64>),
+}
+
+/// Compound, list or array that is being built by [NbtReader::read_tag].
+enum PartialTag {
+    Compound(NbtCompound),
+    List(NbtList),
+    ByteArray(BytesMut),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl PartialTag {
+    fn into_tag(self) -> NbtTag {
+        match self {
+            PartialTag::Compound(compound) => NbtTag::Compound(compound),
+            PartialTag::List(list) => NbtTag::List(list),
+            PartialTag::ByteArray(array) => NbtTag::ByteArray(array.freeze()),
+            PartialTag::IntArray(array) => NbtTag::IntArray(array),
+            PartialTag::LongArray(array) => NbtTag::LongArray(array),
+        }
+    }
+}
+
+/// Tag that is being built from the events of an [NbtReader].
+#[derive(Default)]
+struct TagBuilder {
+    // Values are built without recursion, so that deep nesting can't overflow the stack
+    stack: Vec<(Option<String>, PartialTag)>,
+}
+
+impl TagBuilder {
+    /// Adds an event, returning the tag together with its name once it's complete.
+    fn push(
+        &mut self,
+        event: NbtEvent,
+        context: &ReadContext,
+    ) -> Result<Option<(Option<String>, NbtTag)>, Error> {
+        let (name, tag) = match event {
+            NbtEvent::CompoundStart { name } => {
+                self.stack
+                    .push((name, PartialTag::Compound(NbtCompound::new())));
+                return Ok(None);
+            }
+            NbtEvent::ListStart { name, .. } => {
+                self.stack.push((name, PartialTag::List(NbtList::new())));
+                return Ok(None);
+            }
+            NbtEvent::ArrayStart {
+                name, array_type, ..
+            } => {
+                let array = match array_type {
+                    BYTE_ARRAY_ID => PartialTag::ByteArray(BytesMut::new()),
+                    INT_ARRAY_ID => PartialTag::IntArray(Vec::new()),
+                    _ => PartialTag::LongArray(Vec::new()),
+                };
+                self.stack.push((name, array));
+                return Ok(None);
+            }
+            NbtEvent::ArrayChunk(chunk) => {
+                match (self.stack.last_mut(), chunk) {
+                    (Some((_, PartialTag::ByteArray(array))), ArrayChunk::Bytes(chunk)) => {
+                        array.extend_from_slice(&chunk)
+                    }
+                    (Some((_, PartialTag::IntArray(array))), ArrayChunk::Ints(chunk)) => {
+                        array.extend(chunk)
+                    }
+                    (Some((_, PartialTag::LongArray(array))), ArrayChunk::Longs(chunk)) => {
+                        array.extend(chunk)
+                    }
+                    _ => unreachable!("array chunks are preceded by the start of their array"),
+                }
+                return Ok(None);
+            }
+            NbtEvent::Scalar(name, tag) => (name, tag),
+            NbtEvent::End => match self.stack.pop() {
+                Some((name, PartialTag::List(list))) => {
+                    (name, NbtTag::List(list.unwrap_mixed(context)?))
+                }
+                Some((name, partial)) => (name, partial.into_tag()),
+                None => unreachable!("every end is preceded by a start"),
+            },
+        };
+
+        match self.stack.last_mut() {
+            None => return Ok(Some((name, tag))),
+            Some((_, PartialTag::Compound(compound))) => {
+                compound.put(name.unwrap_or_default(), tag)
+            }
+            Some((_, PartialTag::List(list))) => list.push(tag)?,
+            Some(_) => unreachable!("arrays only contain chunks"),
+        }
+        Ok(None)
+    }
+}
+
+/// Compound, list or array that is being read.
+#[derive(Clone, Copy, Debug)]
+enum Frame {
+    Compound,
+    List { element_type: u8, remaining: usize },
+    Array { element_type: u8, remaining: usize },
+}
+
+/// Pull parser which reads NBT as a sequence of [NbtEvent]s, without building the tags.
+///
+/// Useful for looking at a few values of large files, or for reading data
+/// which doesn't fit in memory. Subtrees can be skipped with [NbtReader::skip_value].
+///
+/// ```
+/// use crab_nbt::{nbt, NbtEvent, NbtReader, NbtTag};
+///
+/// let bytes = nbt!("", {"Data": {"LevelName": "world"}}).write();
+/// let mut reader = NbtReader::new(bytes);
+/// while let Some(event) = reader.next_event().unwrap() {
+///     if let NbtEvent::Scalar(Some(name), NbtTag::String(value)) = event {
+///         println!("{name}: {value}");
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct NbtReader<I> {
+    input: I,
+    context: ReadContext,
+    refill: Refill<I>,
+    stack: Vec<Frame>,
+    root: Root,
+    started: bool,
+}
+
+/// What is at the root of the data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Root {
+    NamedCompound,
+    UnnamedCompound,
+    /// Tag of any type, preceded by its id but without a name.
+    Tag,
+}
+
+/// Input of an [NbtReader], either a [BufInput] or a [ReadInput].
+pub trait NbtInput: Buf + private::Input {}
+
+mod private {
+    use crate::error::Error;
+
+    pub trait Input {
+        /// Whether the input has to be filled before reading from it.
+        const INCREMENTAL: bool;
+
+        /// Makes up to `len` bytes available, returning how many bytes were added.
+        /// Less bytes are available only at the end of the input.
+        fn fill(&mut self, len: usize) -> Result<usize, Error>;
+    }
+}
+
+/// [Buf] containing the whole input of an [NbtReader].
+#[derive(Debug)]
+pub struct BufInput<B> {
+    bytes: B,
+}
+
+impl<B> BufInput<B> {
+    /// Returns the part of the input that wasn't read.
+    pub fn into_inner(self) -> B {
+        self.bytes
+    }
+}
+
+impl<B: Buf> Buf for BufInput<B> {
+    fn remaining(&self) -> usize {
+        self.bytes.remaining()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.bytes.chunk()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.bytes.advance(cnt);
+    }
+}
+
+impl<B: Buf> private::Input for BufInput<B> {
+    const INCREMENTAL: bool = false;
+
+    fn fill(&mut self, _len: usize) -> Result<usize, Error> {
+        Ok(0)
+    }
+}
+
+impl<B: Buf> NbtInput for BufInput<B> {}
+
+/// [Read]er from which an [NbtReader] pulls data as it needs it.
+///
+/// Only the bytes of the NBT are read, in small parts, so unbuffered readers
+/// should be wrapped in a [BufReader](std::io::BufReader).
+#[derive(Debug)]
+pub struct ReadInput<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<R> ReadInput<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        ReadInput {
+            reader,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Returns the reader, which is positioned right after the NBT once it was fully read.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> Buf for ReadInput<R> {
+    fn remaining(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    fn chunk(&self) -> &[u8] {
+        &self.buffer[self.position..]
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.remaining(),
+            "cannot advance past the buffered data"
+        );
+        self.position += cnt;
+    }
+}
+
+impl<R: Read> private::Input for ReadInput<R> {
+    const INCREMENTAL: bool = true;
+
+    fn fill(&mut self, len: usize) -> Result<usize, Error> {
+        let remaining = self.remaining();
+        if remaining >= len {
+            return Ok(0);
+        }
+
+        self.buffer.drain(..self.position);
+        self.position = 0;
+        let added = (&mut self.reader)
+            .take((len - remaining) as u64)
+            .read_to_end(&mut self.buffer)?;
+        Ok(added)
+    }
+}
+
+impl<R: Read> NbtInput for ReadInput<R> {}
+
+/// [AsyncRead]er from which an [NbtReader] receives data between its events.
+///
+/// Filling can't wait for the reader, so it only records how many bytes are missing.
+/// The event is then read again from its start once they were received,
+/// see [NbtReader::next_event_async].
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub(crate) struct AsyncInput<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    position: usize,
+    /// Number of bytes which have to be received before the current event can be read.
+    missing: Option<usize>,
+    ended: bool,
+    /// Whether the data that was read is kept in the buffer.
+    keeps_data: bool,
+}
+
+#[cfg(feature = "tokio")]
+impl<R: AsyncRead + Unpin> AsyncInput<R> {
+    /// Receives `len` more bytes, returning how many bytes were added.
+    async fn receive(&mut self, len: usize) -> Result<usize, Error> {
+        if !self.keeps_data {
+            self.buffer.drain(..self.position);
+            self.position = 0;
+        }
+        let added = (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut self.buffer)
+            .await?;
+        self.ended = added < len;
+        Ok(added)
+    }
+
+    /// Returns the data that was read, when it was kept.
+    #[cfg(feature = "serde")]
+    pub(crate) fn into_data(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R> Buf for AsyncInput<R> {
+    fn remaining(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    fn chunk(&self) -> &[u8] {
+        &self.buffer[self.position..]
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.remaining(),
+            "cannot advance past the buffered data"
+        );
+        self.position += cnt;
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: AsyncRead + Unpin> private::Input for AsyncInput<R> {
+    const INCREMENTAL: bool = true;
+
+    fn fill(&mut self, len: usize) -> Result<usize, Error> {
+        let remaining = self.remaining();
+        if remaining >= len || self.ended {
+            return Ok(0);
+        }
+        self.missing = Some(len - remaining);
+        Err(Error::NoDataRemaining {
+            requested: len,
+            remaining,
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: AsyncRead + Unpin> NbtInput for AsyncInput<R> {}
+
+impl<B: Buf> NbtReader<BufInput<B>> {
+    pub fn new(bytes: B) -> Self {
+        Self::with_options(bytes, &ReadOptions::default())
+    }
+
+    pub fn with_options(bytes: B, options: &ReadOptions) -> Self {
+        NbtReader::from_input(BufInput { bytes }, options)
+    }
+}
+
+impl<R: Read> NbtReader<ReadInput<R>> {
+    pub fn from_reader(reader: R) -> Self {
+        Self::from_reader_with_options(reader, &ReadOptions::default())
+    }
+
+    pub fn from_reader_with_options(reader: R, options: &ReadOptions) -> Self {
+        NbtReader::from_input(ReadInput::new(reader), options)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: AsyncRead + Unpin> NbtReader<AsyncInput<R>> {
+    pub(crate) fn from_async_reader_with_options(reader: R, options: &ReadOptions) -> Self {
+        let input = AsyncInput {
+            reader,
+            buffer: Vec::new(),
+            position: 0,
+            missing: None,
+            ended: false,
+            keeps_data: false,
+        };
+        NbtReader::from_input(input, options)
+    }
+
+    /// Keeps the data that was read, so that it can be taken with [AsyncInput::into_data].
+    #[cfg(feature = "serde")]
+    pub(crate) fn keeping_data(mut self) -> Self {
+        self.input.keeps_data = true;
+        self
+    }
+
+    /// Like [NbtReader::next_event], but waits for the data of the event.
+    ///
+    /// Reading an event consumes its data only partially before it runs out, so the position
+    /// and the innermost frame are restored before the missing data is received.
+    pub(crate) async fn next_event_async(&mut self) -> Result<Option<NbtEvent>, Error> {
+        loop {
+            let position = self.input.position;
+            let frame = self.stack.last().copied();
+            let started = self.started;
+            let error = match self.read_event() {
+                Ok(event) => return Ok(event),
+                Err(error) => error,
+            };
+            let Some(missing) = self.input.missing.take() else {
+                return Err(self.fail(error));
+            };
+
+            self.input.position = position;
+            if let (Some(last), Some(frame)) = (self.stack.last_mut(), frame) {
+                *last = frame;
+            }
+            self.started = started;
+            match self.input.receive(missing).await {
+                Ok(added) => self.context.extend_input(added),
+                Err(error) => return Err(self.fail(error)),
+            }
+        }
+    }
+
+    /// Like [NbtReader::read_tag], but waits for the data of the tag.
+    pub(crate) async fn read_tag_async(
+        &mut self,
+    ) -> Result<Option<(Option<String>, NbtTag)>, Error> {
+        let mut builder = TagBuilder::default();
+        while let Some(event) = self.next_event_async().await? {
+            if let Some(tag) = builder.push(event, &self.context)? {
+                return Ok(Some(tag));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<I: NbtInput> NbtReader<I> {
+    fn from_input(input: I, options: &ReadOptions) -> Self {
+        NbtReader {
+            context: ReadContext::new(*options, &input),
+            input,
+            refill: Refill::for_input(),
+            stack: Vec::new(),
+            root: Root::NamedCompound,
+            started: false,
+        }
+    }
+
+    /// Reads NBT that doesn't contain the name of the root compound.
+    /// Used in [Network NBT](https://wiki.vg/NBT#Network_NBT_(Java_Edition)).
+    pub fn unnamed(self) -> Self {
+        NbtReader {
+            root: Root::UnnamedCompound,
+            ..self
+        }
+    }
+
+    /// Reads a single tag of any type, like [NbtTag::deserialize].
+    pub(crate) fn single_tag(self) -> Self {
+        NbtReader {
+            root: Root::Tag,
+            ..self
+        }
+    }
+
+    /// Number of bytes read so far.
+    pub fn offset(&self) -> usize {
+        self.context.offset(&self.input)
+    }
+
+    /// Returns the input, positioned after the data that was read.
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// Reads the next event, returning `None` after the end of the root compound.
+    ///
+    /// After an error the reader can't continue and only returns `None`.
+    pub fn next_event(&mut self) -> Result<Option<NbtEvent>, Error> {
+        self.read_event().map_err(|error| self.fail(error))
+    }
+
+    /// Reads the next value into a tag, returning it together with its name.
+    pub(crate) fn read_tag(&mut self) -> Result<Option<(Option<String>, NbtTag)>, Error> {
+        let mut builder = TagBuilder::default();
+        while let Some(event) = self.next_event()? {
+            if let Some(tag) = builder.push(event, &self.context)? {
+                return Ok(Some(tag));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Skips the rest of the innermost compound, list or array that is being read,
+    /// including its [NbtEvent::End]. Call it right after a start event to skip the whole value.
+    ///
+    /// Skipped data is checked only as much as needed to find its end, and isn't allocated.
+    /// Does nothing if nothing is being read.
+    pub fn skip_value(&mut self) -> Result<(), Error> {
+        let depth = self.stack.len();
+        while depth > 0 && self.stack.len() >= depth {
+            self.skip_step().map_err(|error| self.fail(error))?;
+        }
+        Ok(())
+    }
+
+    fn fail(&mut self, error: Error) -> Error {
+        self.started = true;
+        self.stack.clear();
+        self.context.locate(&self.input, error)
+    }
+
+    fn read_event(&mut self) -> Result<Option<NbtEvent>, Error> {
+        let Some(frame) = self.stack.last_mut() else {
+            if self.started {
+                return Ok(None);
+            }
+            self.started = true;
+            return self.read_root().map(Some);
+        };
+
+        let event = match frame {
+            Frame::Compound => {
+                self.fill(1)?;
+                let tag_id = self.input.try_get_u8()?;
+                if tag_id == END_ID {
+                    self.close()
+                } else {
+                    let name = self.read_string()?;
+                    self.read_value(tag_id, Some(name))?
+                }
+            }
+            Frame::List { remaining: 0, .. } | Frame::Array { remaining: 0, .. } => self.close(),
+            Frame::List {
+                element_type,
+                remaining,
+            } => {
+                *remaining -= 1;
+                let element_type = *element_type;
+                self.read_value(element_type, None)?
+            }
+            Frame::Array {
+                element_type,
+                remaining,
+            } => {
+                let len = (*remaining).min(ARRAY_CHUNK_LEN);
+                *remaining -= len;
+                let element_type = *element_type;
+                NbtEvent::ArrayChunk(self.read_array_chunk(element_type, len)?)
+            }
+        };
+        Ok(Some(event))
+    }
+
+    fn read_root(&mut self) -> Result<NbtEvent, Error> {
+        self.fill(1)?;
+        let tag_type_id = self.input.try_get_u8()?;
+        if self.root == Root::Tag {
+            return self.read_value(tag_type_id, None);
+        }
+        if tag_type_id != COMPOUND_ID {
+            return Err(Error::NoRootCompound(tag_type_id));
+        }
+
+        let name = if self.root == Root::NamedCompound {
+            Some(self.read_string()?)
+        } else {
+            None
+        };
+        self.read_value(COMPOUND_ID, name)
+    }
+
+    /// Reads the payload of a tag, or only its header for compounds, lists and arrays.
+    fn read_value(&mut self, tag_id: u8, name: Option<String>) -> Result<NbtEvent, Error> {
+        match tag_id {
+            COMPOUND_ID => {
+                self.context.enter()?;
+                self.stack.push(Frame::Compound);
+                Ok(NbtEvent::CompoundStart { name })
+            }
+            LIST_ID => {
+                self.refill
+                    .fill_list_header(&mut self.input, &mut self.context)?;
+                let (element_type, len) = self.context.get_list_header(&mut self.input)?;
+                self.context.enter()?;
+                self.stack.push(Frame::List {
+                    element_type,
+                    remaining: len,
+                });
+                Ok(NbtEvent::ListStart {
+                    name,
+                    element_type,
+                    len,
+                })
+            }
+            BYTE_ARRAY_ID | INT_ARRAY_ID | LONG_ARRAY_ID => {
+                let element_type = array_element_type(tag_id);
+                let size = match element_type {
+                    BYTE_ID => size_of::<i8>(),
+                    INT_ID => size_of::<i32>(),
+                    _ => size_of::<i64>(),
+                };
+                self.fill_int(0, size_of::<i32>())?;
+                let element_size = self.context.array_element_size(size);
+                let len = self.context.get_len(&mut self.input, element_size)?;
+                self.stack.push(Frame::Array {
+                    element_type,
+                    remaining: len,
+                });
+                Ok(NbtEvent::ArrayStart {
+                    name,
+                    array_type: tag_id,
+                    len,
+                })
+            }
+            _ => {
+                self.refill
+                    .fill_payload(&mut self.input, &mut self.context, tag_id)?;
+                let tag = NbtTag::read_primitive_data(&mut self.input, tag_id, &mut self.context)?;
+                Ok(NbtEvent::Scalar(name, tag))
+            }
+        }
+    }
+
+    fn read_array_chunk(&mut self, element_type: u8, len: usize) -> Result<ArrayChunk, Error> {
+        let flavor = self.context.flavor();
+        self.refill
+            .fill_elements(&mut self.input, &mut self.context, 0, element_type, len)?;
+        Ok(match element_type {
+            BYTE_ID => ArrayChunk::Bytes(try_copy_to_bytes(&mut self.input, len)?),
+            INT_ID => ArrayChunk::Ints(flavor.get_i32_array(&mut self.input, len)?),
+            _ => ArrayChunk::Longs(flavor.get_i64_array(&mut self.input, len)?),
+        })
+    }
+
+    fn read_string(&mut self) -> Result<String, Error> {
+        self.refill
+            .fill_string(&mut self.input, &mut self.context)?;
+        self.context.get_string(&mut self.input)
+    }
+
+    /// Ends the innermost compound, list or array.
+    fn close(&mut self) -> NbtEvent {
+        if let Some(Frame::Compound | Frame::List { .. }) = self.stack.pop() {
+            self.context.exit();
+        }
+        NbtEvent::End
+    }
+
+    /// Skips a tag of the innermost compound, or elements of the innermost list or array.
+    fn skip_step(&mut self) -> Result<(), Error> {
+        let flavor = self.context.flavor();
+        match self.stack.last_mut() {
+            None => {}
+            Some(Frame::Compound) => {
+                self.fill(1)?;
+                let tag_id = self.input.try_get_u8()?;
+                if tag_id == END_ID {
+                    self.close();
+                } else {
+                    self.skip_string()?;
+                    self.skip_payload(tag_id)?;
+                }
+            }
+            Some(Frame::List { remaining: 0, .. } | Frame::Array { remaining: 0, .. }) => {
+                self.close();
+            }
+            Some(
+                Frame::List {
+                    element_type,
+                    remaining,
+                }
+                | Frame::Array {
+                    element_type,
+                    remaining,
+                },
+            ) => {
+                let element_type = *element_type;
+                match fixed_size(flavor, element_type) {
+                    Some(size) => {
+                        let len = size.saturating_mul(*remaining);
+                        *remaining = 0;
+                        self.skip(len)?;
+                    }
+                    None => {
+                        *remaining -= 1;
+                        self.skip_payload(element_type)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips the payload of a tag. Compounds, lists and arrays are only entered,
+    /// so that their content is skipped by the following steps.
+    fn skip_payload(&mut self, tag_id: u8) -> Result<(), Error> {
+        if tag_id == STRING_ID {
+            return self.skip_string();
+        }
+        // Numbers and headers don't allocate
+        self.read_value(tag_id, None)?;
+        Ok(())
+    }
+
+    fn skip_string(&mut self) -> Result<(), Error> {
+        let flavor = self.context.flavor();
+        self.fill_int(0, string_len_size(flavor))?;
+        let len = flavor.get_string_len(&mut self.input)?;
+        self.skip(len)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), Error> {
+        self.context.reserve(&self.input, len)?;
+        let mut remaining = len;
+        while remaining > 0 {
+            let chunk_len = remaining.min(SKIP_CHUNK_LEN);
+            self.fill(chunk_len)?;
+            ensure_remaining(&self.input, chunk_len)?;
+            self.input.advance(chunk_len);
+            remaining -= chunk_len;
+        }
+        Ok(())
+    }
+
+    fn fill(&mut self, len: usize) -> Result<(), Error> {
+        self.refill.fill(&mut self.input, &mut self.context, len)
+    }
+
+    fn fill_int(&mut self, start: usize, size: usize) -> Result<usize, Error> {
+        self.refill
+            .fill_int(&mut self.input, &mut self.context, start, size)
+    }
+}
+
+/// Makes data of an input which is read incrementally available before it's decoded
+/// by the shared primitives, which expect it to be buffered. Does nothing for other inputs.
+pub(crate) struct Refill<T> {
+    fill: Option<FillFn<T>>,
+}
+
+type FillFn<T> = fn(&mut T, usize) -> Result<usize, Error>;
+
+impl<T> Clone for Refill<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Refill<T> {}
+
+impl<T> Debug for Refill<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Refill")
+            .field("incremental", &self.fill.is_some())
+            .finish()
+    }
+}
+
+impl<T: NbtInput> Refill<T> {
+    pub(crate) fn for_input() -> Self {
+        Refill {
+            fill: T::INCREMENTAL.then_some(T::fill),
+        }
+    }
+}
+
+impl<T: Buf> Refill<T> {
+    /// Used for inputs which are available at once.
+    #[cfg(feature = "serde")]
+    pub(crate) fn none() -> Self {
+        Refill { fill: None }
+    }
+
+    /// Makes `len` bytes available.
+    pub(crate) fn fill(
+        self,
+        input: &mut T,
+        context: &mut ReadContext,
+        len: usize,
+    ) -> Result<(), Error> {
+        let Some(fill) = self.fill else {
+            return Ok(());
+        };
+        context.reserve(input, len)?;
+        let added = fill(input, len)?;
+        context.extend_input(added);
+        Ok(())
+    }
+
+    /// Makes an integer which starts `start` bytes into the available data and takes `size` bytes
+    /// in fixed-size flavors available, returning the position right after it.
+    pub(crate) fn fill_int(
+        self,
+        input: &mut T,
+        context: &mut ReadContext,
+        start: usize,
+        size: usize,
+    ) -> Result<usize, Error> {
+        if self.fill.is_none() || context.flavor() != NbtFlavor::BedrockNetwork {
+            self.fill(input, context, start + size)?;
+            return Ok(start + size);
+        }
+
+        // Variable-length integers end with the first byte without the continuation bit
+        let max_len = size * 8 / 7 + 1;
+        for end in start + 1..start + max_len {
+            self.fill(input, context, end)?;
+            match input.chunk().get(end - 1) {
+                Some(byte) if byte & 0x80 != 0 => {}
+                _ => return Ok(end),
+            }
+        }
+        self.fill(input, context, start + max_len)?;
+        Ok(start + max_len)
+    }
+
+    /// Makes `len` tags with id `element_type`, starting `start` bytes into the available data,
+    /// available. Only numbers are supported.
+    pub(crate) fn fill_elements(
+        self,
+        input: &mut T,
+        context: &mut ReadContext,
+        start: usize,
+        element_type: u8,
+        len: usize,
+    ) -> Result<(), Error> {
+        if self.fill.is_none() {
+            return Ok(());
+        }
+        if let Some(size) = fixed_size(context.flavor(), element_type) {
+            return self.fill(
+                input,
+                context,
+                start.saturating_add(len.saturating_mul(size)),
+            );
+        }
+
+        let size = match element_type {
+            LONG_ID => size_of::<i64>(),
+            _ => size_of::<i32>(),
+        };
+        let mut end = start;
+        for _ in 0..len {
+            end = self.fill_int(input, context, end, size)?;
+            if input.remaining() < end {
+                // The input ended, which is reported when reading
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn fill_string(self, input: &mut T, context: &mut ReadContext) -> Result<(), Error> {
+        if self.fill.is_none() {
+            return Ok(());
+        }
+        let flavor = context.flavor();
+        self.fill_int(input, context, 0, string_len_size(flavor))?;
+
+        // Peek at the length, so that the string can be read with the shared primitives
+        let mut prefix = input.chunk();
+        let len = flavor.get_string_len(&mut prefix)?;
+        let prefix_len = input.chunk().len() - prefix.len();
+        self.fill(input, context, prefix_len.saturating_add(len))
+    }
+
+    pub(crate) fn fill_list_header(
+        self,
+        input: &mut T,
+        context: &mut ReadContext,
+    ) -> Result<(), Error> {
+        self.fill(input, context, 1)?;
+        self.fill_int(input, context, 1, size_of::<i32>())?;
+        Ok(())
+    }
+
+    /// Makes the payload of a tag which can't contain other tags available.
+    pub(crate) fn fill_payload(
+        self,
+        input: &mut T,
+        context: &mut ReadContext,
+        tag_id: u8,
+    ) -> Result<(), Error> {
+        match tag_id {
+            INT_ID => self.fill_int(input, context, 0, size_of::<i32>()).map(drop),
+            LONG_ID => self.fill_int(input, context, 0, size_of::<i64>()).map(drop),
+            STRING_ID => self.fill_string(input, context),
+            BYTE_ARRAY_ID | INT_ARRAY_ID | LONG_ARRAY_ID if self.fill.is_some() => {
+                let start = self.fill_int(input, context, 0, size_of::<i32>())?;
+                let mut prefix = input.chunk();
+                // Negative lengths are reported when reading
+                let len = usize::try_from(context.flavor().get_i32(&mut prefix)?).unwrap_or(0);
+                let element_type = array_element_type(tag_id);
+                self.fill_elements(input, context, start, element_type, len)
+            }
+            _ => self.fill(
+                input,
+                context,
+                fixed_size(context.flavor(), tag_id).unwrap_or(0),
+            ),
+        }
+    }
+}
+
+/// Id of the elements of an array with the given id.
+pub(crate) fn array_element_type(array_type: u8) -> u8 {
+    match array_type {
+        BYTE_ARRAY_ID => BYTE_ID,
+        INT_ARRAY_ID => INT_ID,
+        _ => LONG_ID,
+    }
+}
+
+/// Size of tags with the given id, if it doesn't depend on their value.
+pub(crate) fn fixed_size(flavor: NbtFlavor, tag_id: u8) -> Option<usize> {
+    let is_variable = flavor == NbtFlavor::BedrockNetwork;
+    match tag_id {
+        BYTE_ID => Some(size_of::<i8>()),
+        SHORT_ID => Some(size_of::<i16>()),
+        INT_ID if !is_variable => Some(size_of::<i32>()),
+        LONG_ID if !is_variable => Some(size_of::<i64>()),
+        FLOAT_ID => Some(size_of::<f32>()),
+        DOUBLE_ID => Some(size_of::<f64>()),
+        _ => None,
+    }
+}
+
+/// Size of the length of a string, before variable-length encoding.
+fn string_len_size(flavor: NbtFlavor) -> usize {
+    match flavor {
+        NbtFlavor::JavaBE | NbtFlavor::BedrockLE => size_of::<u16>(),
+        NbtFlavor::BedrockNetwork => size_of::<u32>(),
+    }
+}
+
+impl<I: NbtInput> Iterator for NbtReader<I> {
+    type Item = Result<NbtEvent, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
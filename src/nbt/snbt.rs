@@ -0,0 +1,745 @@
+//! Parser for the stringified NBT (SNBT) format.
+//!
+//! It accepts everything produced by the [Display](std::fmt::Display) implementations
+//! of [NbtTag], [NbtCompound] and [Nbt](crate::Nbt), as well as the syntax used by vanilla
+//! Minecraft: unquoted strings, single and double quoted strings with escapes,
+//! typed arrays, hexadecimal and binary numbers, numeric suffixes in either case
+//! and the `bool(...)` and `uuid(...)` operations.
+
+use crate::error::Error;
+use bytes::Bytes;
+use crab_nbt::nbt::compound::NbtCompound;
+use crab_nbt::nbt::list::NbtList;
+use crab_nbt::nbt::path::{is_allowed_in_path_key, NbtPath, PathSegment};
+use crab_nbt::nbt::tag::NbtTag;
+
+/// Same nesting limit as the one used by vanilla Minecraft.
+const MAX_DEPTH: usize = 512;
+
+pub(crate) struct SnbtParser<'a> {
+    input: &'a str,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> SnbtParser<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        SnbtParser {
+            input,
+            position: 0,
+            depth: 0,
+        }
+    }
+
+    /// Parses a single tag and makes sure that nothing but whitespace follows it.
+    pub(crate) fn parse_complete(mut self) -> Result<NbtTag, Error> {
+        let tag = self.parse_value()?;
+        self.skip_whitespace();
+        if self.position != self.input.len() {
+            return Err(self.error("Trailing data after the end of the tag"));
+        }
+        Ok(tag)
+    }
+
+    /// Parses a path using the syntax of the `/data` command, like `Items[{Slot:3b}].tag`.
+    pub(crate) fn parse_path(mut self) -> Result<NbtPath, Error> {
+        self.parse_path_segments().map_err(|error| match error {
+            Error::InvalidSnbt { position, message } => Error::InvalidPath { position, message },
+            error => error,
+        })
+    }
+
+    fn parse_path_segments(&mut self) -> Result<NbtPath, Error> {
+        let mut path = NbtPath::new();
+        loop {
+            let segment = match self.peek() {
+                Some('{') if path.is_empty() => PathSegment::MatchRoot(self.parse_pattern()?),
+                Some('[') => self.parse_path_index()?,
+                Some('"' | '\'') => {
+                    let key = self.parse_quoted_string()?;
+                    self.parse_path_key(key)?
+                }
+                Some(c) if is_allowed_in_path_key(c) => {
+                    let rest = &self.input[self.position..];
+                    let len = rest
+                        .find(|c: char| !is_allowed_in_path_key(c))
+                        .unwrap_or(rest.len());
+                    self.position += len;
+                    self.parse_path_key(rest[..len].to_owned())?
+                }
+                Some(c) => return Err(self.error(format!("Unexpected '{c}'"))),
+                None => return Err(self.error("Expected a key or an index")),
+            };
+            path.push(segment);
+
+            match self.peek() {
+                None => return Ok(path),
+                Some('.') => self.position += 1,
+                Some('[') => {}
+                Some(c) => return Err(self.error(format!("Expected '.' or '[', found '{c}'"))),
+            }
+        }
+    }
+
+    /// Parses an optional pattern following the key of a path segment.
+    fn parse_path_key(&mut self, key: String) -> Result<PathSegment, Error> {
+        if self.peek() == Some('{') {
+            return Ok(PathSegment::MatchKey(key, self.parse_pattern()?));
+        }
+        Ok(PathSegment::Key(key))
+    }
+
+    fn parse_path_index(&mut self) -> Result<PathSegment, Error> {
+        self.expect('[')?;
+        let segment = match self.peek() {
+            Some(']') => PathSegment::AllElements,
+            Some('{') => PathSegment::MatchElements(self.parse_pattern()?),
+            _ => {
+                let rest = &self.input[self.position..];
+                let len = rest
+                    .find(|c: char| c != '-' && !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                let token = &rest[..len];
+                let (is_negative, digits) = match token.strip_prefix('-') {
+                    Some(digits) => (true, digits),
+                    None => (false, token),
+                };
+                let index = digits
+                    .parse::<usize>()
+                    .map_err(|_| self.error(format!("Invalid index '{token}'")))?;
+                self.position += len;
+                if is_negative && index > 0 {
+                    PathSegment::IndexFromEnd(index)
+                } else {
+                    PathSegment::Index(index)
+                }
+            }
+        };
+        self.expect(']')?;
+        Ok(segment)
+    }
+
+    fn parse_pattern(&mut self) -> Result<NbtCompound, Error> {
+        self.enter()?;
+        let pattern = self.parse_compound()?;
+        self.depth -= 1;
+        Ok(pattern)
+    }
+
+    fn error(&self, message: impl Into<String>) -> Error {
+        Error::InvalidSnbt {
+            position: self.position,
+            message: message.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("Expected '{expected}', found '{c}'"))),
+            None => Err(self.error(format!("Expected '{expected}', found end of input"))),
+        }
+    }
+
+    /// Consumes `separator` if it is the next character, otherwise expects `end`
+    /// without consuming it. Returns `true` if another element may follow.
+    fn separator_or_end(&mut self, end: char) -> Result<bool, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(',') => {
+                self.position += 1;
+                Ok(true)
+            }
+            Some(c) if c == end => Ok(false),
+            Some(c) => Err(self.error(format!("Expected ',' or '{end}', found '{c}'"))),
+            None => Err(self.error(format!("Expected ',' or '{end}', found end of input"))),
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error(format!("Nesting is deeper than {MAX_DEPTH} levels")));
+        }
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<NbtTag, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.enter()?;
+                let compound = self.parse_compound()?;
+                self.depth -= 1;
+                Ok(NbtTag::Compound(compound))
+            }
+            Some('[') => {
+                self.enter()?;
+                let tag = self.parse_list_or_array()?;
+                self.depth -= 1;
+                Ok(tag)
+            }
+            Some('"' | '\'') => Ok(NbtTag::String(self.parse_quoted_string()?)),
+            Some(_) => {
+                let start = self.position;
+                let token = self.read_unquoted();
+                if token.is_empty() {
+                    return Err(self.error("Expected a value"));
+                }
+
+                self.skip_whitespace();
+                if self.peek() == Some('(') {
+                    self.position = start;
+                    return self.parse_operation(token);
+                }
+
+                parse_literal(token).map_err(|message| Error::InvalidSnbt {
+                    position: start,
+                    message,
+                })
+            }
+            None => Err(self.error("Expected a value, found end of input")),
+        }
+    }
+
+    fn parse_compound(&mut self) -> Result<NbtCompound, Error> {
+        self.expect('{')?;
+        let mut compound = NbtCompound::new();
+
+        self.skip_whitespace();
+        while self.peek() != Some('}') {
+            let key = self.parse_key()?;
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            // Like in Minecraft, the last value of a duplicate key is kept
+            compound.insert(key, value);
+
+            if !self.separator_or_end('}')? {
+                break;
+            }
+            self.skip_whitespace();
+        }
+
+        self.expect('}')?;
+        Ok(compound)
+    }
+
+    fn parse_key(&mut self) -> Result<String, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"' | '\'') => self.parse_quoted_string(),
+            _ => {
+                let key = self.read_unquoted();
+                if key.is_empty() {
+                    return Err(self.error("Expected a key"));
+                }
+                Ok(key.to_owned())
+            }
+        }
+    }
+
+    fn parse_list_or_array(&mut self) -> Result<NbtTag, Error> {
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        let rest = &self.input[self.position..];
+        let array_type = rest
+            .chars()
+            .next()
+            .filter(|c| matches!(c.to_ascii_uppercase(), 'B' | 'I' | 'L'))
+            .filter(|_| rest[1..].trim_start().starts_with(';'));
+        if let Some(array_type) = array_type {
+            self.position += 1;
+            self.expect(';')?;
+            return self.parse_array(array_type.to_ascii_uppercase());
+        }
+
+        let mut list = Vec::new();
+        while self.peek() != Some(']') {
+            list.push(self.parse_value()?);
+            if !self.separator_or_end(']')? {
+                break;
+            }
+            self.skip_whitespace();
+        }
+        self.expect(']')?;
+
+        let list = NbtList::try_from_tags(list).map_err(|error| self.error(error.to_string()))?;
+        Ok(NbtTag::List(list))
+    }
+
+    fn parse_array(&mut self, array_type: char) -> Result<NbtTag, Error> {
+        let (min, max) = match array_type {
+            'B' => (i8::MIN as i64, i8::MAX as i64),
+            'I' => (i32::MIN as i64, i32::MAX as i64),
+            _ => (i64::MIN, i64::MAX),
+        };
+
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        while self.peek() != Some(']') {
+            self.skip_whitespace();
+            let start = self.position;
+            let value = match self.parse_value()? {
+                NbtTag::Byte(value) => value as i64,
+                NbtTag::Short(value) => value as i64,
+                NbtTag::Int(value) => value as i64,
+                NbtTag::Long(value) => value,
+                tag => {
+                    return Err(Error::InvalidSnbt {
+                        position: start,
+                        message: format!("Invalid element {tag} of [{array_type}; ...] array"),
+                    })
+                }
+            };
+            if value < min || value > max {
+                return Err(Error::InvalidSnbt {
+                    position: start,
+                    message: format!("Value {value} is out of range of [{array_type}; ...] array"),
+                });
+            }
+            values.push(value);
+
+            if !self.separator_or_end(']')? {
+                break;
+            }
+            self.skip_whitespace();
+        }
+        self.expect(']')?;
+
+        Ok(match array_type {
+            'B' => NbtTag::ByteArray(Bytes::from_iter(values.into_iter().map(|v| v as u8))),
+            'I' => NbtTag::IntArray(values.into_iter().map(|v| v as i32).collect()),
+            _ => NbtTag::LongArray(values),
+        })
+    }
+
+    fn parse_operation(&mut self, name: &str) -> Result<NbtTag, Error> {
+        let start = self.position;
+        self.position += name.len();
+        self.expect('(')?;
+
+        let mut arguments = Vec::new();
+        self.skip_whitespace();
+        while self.peek() != Some(')') {
+            arguments.push(self.parse_value()?);
+            if !self.separator_or_end(')')? {
+                break;
+            }
+            self.skip_whitespace();
+        }
+        self.expect(')')?;
+
+        let error = |message: String| Error::InvalidSnbt {
+            position: start,
+            message,
+        };
+        match (name, arguments.as_slice()) {
+            ("bool", [argument]) => {
+                let value = match argument {
+                    NbtTag::Byte(value) => *value != 0,
+                    NbtTag::Short(value) => *value != 0,
+                    NbtTag::Int(value) => *value != 0,
+                    NbtTag::Long(value) => *value != 0,
+                    // Like in Java, the fractional part is discarded before the comparison
+                    NbtTag::Float(value) => value.trunc() != 0.0,
+                    NbtTag::Double(value) => value.trunc() != 0.0,
+                    NbtTag::String(value) if value.eq_ignore_ascii_case("true") => true,
+                    NbtTag::String(value) if value.eq_ignore_ascii_case("false") => false,
+                    tag => return Err(error(format!("Can't convert {tag} to a boolean"))),
+                };
+                Ok(NbtTag::Byte(value as i8))
+            }
+            ("uuid", [NbtTag::String(uuid)]) => parse_uuid(uuid)
+                .map(|uuid| NbtTag::IntArray(uuid.to_vec()))
+                .ok_or_else(|| error(format!("Invalid UUID \"{uuid}\""))),
+            _ => Err(error(format!(
+                "Unknown operation {name} with {} argument(s)",
+                arguments.len()
+            ))),
+        }
+    }
+
+    fn read_unquoted(&mut self) -> &'a str {
+        let rest = &self.input[self.position..];
+        let len = rest
+            .find(|c: char| !is_allowed_in_unquoted(c))
+            .unwrap_or(rest.len());
+        self.position += len;
+        &rest[..len]
+    }
+
+    fn parse_quoted_string(&mut self) -> Result<String, Error> {
+        let quote = self.next_char().expect("caller checked for a quote");
+        let mut string = String::new();
+
+        loop {
+            match self.next_char() {
+                Some('\\') => {
+                    let escape_start = self.position - 1;
+                    let c = match self.next_char() {
+                        Some(c) if c == '\\' || c == '"' || c == '\'' => c,
+                        Some('b') => '\x08',
+                        Some('s') => ' ',
+                        Some('t') => '\t',
+                        Some('n') => '\n',
+                        Some('f') => '\x0c',
+                        Some('r') => '\r',
+                        Some('x') => self.parse_hex_escape(2)?,
+                        Some('u') => self.parse_hex_escape(4)?,
+                        Some('U') => self.parse_hex_escape(8)?,
+                        Some('N') => self.parse_named_escape()?,
+                        _ => {
+                            return Err(Error::InvalidSnbt {
+                                position: escape_start,
+                                message: "Invalid escape sequence".to_owned(),
+                            })
+                        }
+                    };
+                    string.push(c);
+                }
+                Some(c) if c == quote => return Ok(string),
+                Some(c) => string.push(c),
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    fn parse_hex_escape(&mut self, digits: usize) -> Result<char, Error> {
+        let hex = self
+            .input
+            .get(self.position..self.position + digits)
+            .filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error(format!("Expected {digits} hexadecimal digits")))?;
+        let c = u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error(format!("Invalid code point {hex}")))?;
+        self.position += digits;
+        Ok(c)
+    }
+
+    fn parse_named_escape(&mut self) -> Result<char, Error> {
+        self.expect('{')?;
+        let rest = &self.input[self.position..];
+        let name = &rest[..rest
+            .find('}')
+            .ok_or_else(|| self.error("Unterminated character name"))?];
+        let c = character_by_name(name).map_err(|message| self.error(message))?;
+        self.position += name.len() + 1;
+        Ok(c)
+    }
+}
+
+/// Looks up a character by its Unicode name, for `\N{...}` escapes.
+#[cfg(feature = "unicode_names")]
+fn character_by_name(name: &str) -> Result<char, String> {
+    unicode_names2::character(name).ok_or_else(|| format!("Unknown character name {name}"))
+}
+
+#[cfg(not(feature = "unicode_names"))]
+fn character_by_name(_name: &str) -> Result<char, String> {
+    Err("Character names require the `unicode_names` feature".to_owned())
+}
+
+fn is_allowed_in_unquoted(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '+'
+}
+
+/// Parses a token that isn't quoted or followed by parenthesis.
+/// Tokens that don't look like numbers are treated as strings, like in older Minecraft versions.
+fn parse_literal(token: &str) -> Result<NbtTag, String> {
+    if token.eq_ignore_ascii_case("true") {
+        return Ok(NbtTag::Byte(1));
+    }
+    if token.eq_ignore_ascii_case("false") {
+        return Ok(NbtTag::Byte(0));
+    }
+
+    match parse_number(token) {
+        Some(result) => result,
+        None => Ok(NbtTag::String(token.to_owned())),
+    }
+}
+
+/// Parses NaN and infinities of floats and doubles, in the form written by [Display](std::fmt::Display).
+fn parse_non_finite(token: &str) -> Option<NbtTag> {
+    let (value, is_float) = match token.strip_suffix(['f', 'F']) {
+        Some(value) => (value, true),
+        None => (token.strip_suffix(['d', 'D'])?, false),
+    };
+    let value = match value {
+        "NaN" => f64::NAN,
+        "inf" | "+inf" => f64::INFINITY,
+        "-inf" => f64::NEG_INFINITY,
+        _ => return None,
+    };
+    Some(if is_float {
+        NbtTag::Float(value as f32)
+    } else {
+        NbtTag::Double(value)
+    })
+}
+
+/// Returns `None` if the token doesn't have the syntax of a number.
+fn parse_number(token: &str) -> Option<Result<NbtTag, String>> {
+    if let Some(tag) = parse_non_finite(token) {
+        return Some(Ok(tag));
+    }
+
+    let (negative, unsigned_token) = match token.as_bytes().first()? {
+        b'-' => (true, &token[1..]),
+        b'+' => (false, &token[1..]),
+        _ => (false, token),
+    };
+
+    let lowercase = unsigned_token.to_ascii_lowercase();
+    let (radix, digits_start) = if lowercase.starts_with("0x") {
+        (16, 2)
+    } else if lowercase.starts_with("0b")
+        && matches!(lowercase.as_bytes().get(2), Some(b'0' | b'1'))
+    {
+        (2, 2)
+    } else {
+        (10, 0)
+    };
+
+    let body = &lowercase[digits_start..];
+    let (digits, suffix) = if radix == 10 {
+        split_decimal(body)?
+    } else {
+        let end = body
+            .find(|c: char| !(c.is_digit(radix) || c == '_'))
+            .unwrap_or(body.len());
+        (&body[..end], &body[end..])
+    };
+
+    if digits.is_empty() || digits.starts_with('_') || digits.ends_with('_') {
+        return None;
+    }
+    let digits = digits.replace('_', "");
+    let is_floating = radix == 10 && digits.contains(['.', 'e']);
+
+    Some(match suffix {
+        "f" | "d" if radix == 10 => {
+            let value = if negative {
+                format!("-{digits}")
+            } else {
+                digits
+            };
+            if suffix == "f" {
+                value.parse().map(NbtTag::Float).map_err(|e| e.to_string())
+            } else {
+                value.parse().map(NbtTag::Double).map_err(|e| e.to_string())
+            }
+        }
+        "" if is_floating => {
+            let value = if negative {
+                format!("-{digits}")
+            } else {
+                digits
+            };
+            value.parse().map(NbtTag::Double).map_err(|e| e.to_string())
+        }
+        _ if is_floating => return None,
+        _ => {
+            let (unsigned, type_suffix) = match suffix.as_bytes() {
+                [b'u', rest @ ..] => (true, rest),
+                [b's', rest @ ..] if !rest.is_empty() => (false, rest),
+                rest => (false, rest),
+            };
+            let bits = match type_suffix {
+                b"b" => 8,
+                b"s" => 16,
+                b"" | b"i" => 32,
+                b"l" => 64,
+                _ => return None,
+            };
+            parse_integer(&digits, radix, negative, unsigned, bits).map(|value| match bits {
+                8 => NbtTag::Byte(value as i8),
+                16 => NbtTag::Short(value as i16),
+                32 => NbtTag::Int(value as i32),
+                _ => NbtTag::Long(value as i64),
+            })
+        }
+    })
+}
+
+/// Splits a decimal number into its digits (including the fraction and the exponent) and its suffix.
+fn split_decimal(body: &str) -> Option<(&str, &str)> {
+    let bytes = body.as_bytes();
+    let mut end = 0;
+    let mut seen_dot = false;
+    let mut seen_digit = false;
+    while end < bytes.len() {
+        match bytes[end] {
+            b'0'..=b'9' => seen_digit = true,
+            b'_' => {}
+            b'.' if !seen_dot => seen_dot = true,
+            _ => break,
+        }
+        end += 1;
+    }
+    if !seen_digit {
+        return None;
+    }
+
+    // The exponent is only a part of the number if it's followed by digits
+    if bytes.get(end) == Some(&b'e') {
+        let mut exponent_end = end + 1;
+        if matches!(bytes.get(exponent_end), Some(b'+' | b'-')) {
+            exponent_end += 1;
+        }
+        if matches!(bytes.get(exponent_end), Some(b'0'..=b'9')) {
+            while matches!(bytes.get(exponent_end), Some(b'0'..=b'9' | b'_')) {
+                exponent_end += 1;
+            }
+            end = exponent_end;
+        }
+    }
+
+    Some((&body[..end], &body[end..]))
+}
+
+fn parse_integer(
+    digits: &str,
+    radix: u32,
+    negative: bool,
+    unsigned: bool,
+    bits: u32,
+) -> Result<i128, String> {
+    let out_of_range = || {
+        let sign = if negative { "-" } else { "" };
+        format!("Number {sign}{digits} is out of range for a {bits}-bit integer")
+    };
+
+    let magnitude = u64::from_str_radix(digits, radix).map_err(|_| out_of_range())? as i128;
+    let value = if negative { -magnitude } else { magnitude };
+
+    let (min, max) = if unsigned {
+        (0, (1i128 << bits) - 1)
+    } else {
+        (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+    };
+    if value < min || value > max {
+        return Err(out_of_range());
+    }
+    Ok(value)
+}
+
+/// Parses UUID in the same format as Java's `UUID.fromString`,
+/// returning it as four integers like Minecraft does.
+fn parse_uuid(uuid: &str) -> Option<[i32; 4]> {
+    let parts: Vec<&str> = uuid.split('-').collect();
+    let [a, b, c, d, e] = parts.as_slice() else {
+        return None;
+    };
+
+    let part = |part: &str, max_len: usize| {
+        if part.is_empty() || part.len() > max_len {
+            return None;
+        }
+        u64::from_str_radix(part, 16).ok()
+    };
+    let most = (part(a, 8)? << 32) | (part(b, 4)? << 16) | part(c, 4)?;
+    let least = (part(d, 4)? << 48) | part(e, 12)?;
+
+    Some([
+        (most >> 32) as i32,
+        most as i32,
+        (least >> 32) as i32,
+        least as i32,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> NbtTag {
+        SnbtParser::new(input).parse_complete().unwrap()
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse("1b"), NbtTag::Byte(1));
+        assert_eq!(parse("-3S"), NbtTag::Short(-3));
+        assert_eq!(parse("7"), NbtTag::Int(7));
+        assert_eq!(parse("7i"), NbtTag::Int(7));
+        assert_eq!(parse("3L"), NbtTag::Long(3));
+        assert_eq!(parse("1.5f"), NbtTag::Float(1.5));
+        assert_eq!(parse("2.0"), NbtTag::Double(2.0));
+        assert_eq!(parse("1e3"), NbtTag::Double(1000.0));
+        assert_eq!(parse("1_000"), NbtTag::Int(1000));
+        assert_eq!(parse("0xFFub"), NbtTag::Byte(-1));
+        assert_eq!(parse("0b101s"), NbtTag::Short(5));
+        assert_eq!(parse("true"), NbtTag::Byte(1));
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert!(SnbtParser::new("128b").parse_complete().is_err());
+        assert!(SnbtParser::new("-1ub").parse_complete().is_err());
+    }
+
+    #[test]
+    fn unquoted_strings() {
+        assert_eq!(parse("hand"), NbtTag::String("hand".to_owned()));
+        assert_eq!(parse("1.2.3"), NbtTag::String("1.2.3".to_owned()));
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            parse(r#"'a\'b"\n\x41é'"#),
+            NbtTag::String("a'b\"\nAé".to_owned())
+        );
+    }
+
+    #[cfg(feature = "unicode_names")]
+    #[test]
+    fn named_escapes() {
+        assert_eq!(parse(r#""\N{Snowman}""#), NbtTag::String("☃".to_owned()));
+        assert!(r#""\N{Not a name}""#.parse::<NbtTag>().is_err());
+    }
+
+    #[cfg(not(feature = "unicode_names"))]
+    #[test]
+    fn named_escapes() {
+        assert!(matches!(
+            r#""\N{Snowman}""#.parse::<NbtTag>(),
+            Err(Error::InvalidSnbt { .. })
+        ));
+    }
+
+    #[test]
+    fn uuid() {
+        assert_eq!(
+            parse("uuid(\"f81d4fae-7dec-11d0-a765-00a0c91e6bf6\")"),
+            NbtTag::IntArray(vec![-132296786, 2112623056, -1486552928, -920753162])
+        );
+    }
+}
@@ -1,11 +1,17 @@
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use crab_nbt::error::Error;
 use crab_nbt::nbt::compound::NbtCompound;
+use crab_nbt::nbt::flavor::NbtFlavor;
+use crab_nbt::nbt::list::{NbtList, NbtTagView};
+use crab_nbt::nbt::options::{ReadContext, ReadOptions};
+use crab_nbt::nbt::reader::NbtReader;
+use crab_nbt::nbt::snbt::SnbtParser;
 use crab_nbt::nbt::utils::*;
 use derive_more::From;
 use std::cmp::Ordering;
 use std::fmt::{self, Display, Formatter};
-use std::io::Cursor;
+use std::io::{Cursor, Read};
+use std::str::FromStr;
 
 /// Enum representing the different types of NBT tags.
 /// Each variant corresponds to a different type of data that can be stored in an NBT tag.
@@ -21,7 +27,7 @@ pub enum NbtTag {
     Double(f64) = DOUBLE_ID,
     ByteArray(Bytes) = BYTE_ARRAY_ID,
     String(String) = STRING_ID,
-    List(Vec<NbtTag>) = LIST_ID,
+    List(NbtList) = LIST_ID,
     Compound(NbtCompound) = COMPOUND_ID,
     IntArray(Vec<i32>) = INT_ARRAY_ID,
     LongArray(Vec<i64>) = LONG_ARRAY_ID,
@@ -29,21 +35,7 @@ pub enum NbtTag {
 
 impl PartialEq for NbtTag {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Self::Byte(a), Self::Byte(b)) => a == b,
-            (Self::Short(a), Self::Short(b)) => a == b,
-            (Self::Int(a), Self::Int(b)) => a == b,
-            (Self::Long(a), Self::Long(b)) => a == b,
-            (Self::Float(a), Self::Float(b)) => a.total_cmp(b) == Ordering::Equal,
-            (Self::Double(a), Self::Double(b)) => a.total_cmp(b) == Ordering::Equal,
-            (Self::ByteArray(a), Self::ByteArray(b)) => a == b,
-            (Self::String(a), Self::String(b)) => a == b,
-            (Self::List(a), Self::List(b)) => a == b,
-            (Self::Compound(a), Self::Compound(b)) => a == b,
-            (Self::IntArray(a), Self::IntArray(b)) => a == b,
-            (Self::LongArray(a), Self::LongArray(b)) => a == b,
-            _ => self.get_type_id() == other.get_type_id(),
-        }
+        NbtTagView::from(self) == NbtTagView::from(other)
     }
 }
 
@@ -57,21 +49,7 @@ impl PartialOrd for NbtTag {
 
 impl Ord for NbtTag {
     fn cmp(&self, other: &Self) -> Ordering {
-        match (self, other) {
-            (Self::Byte(a), Self::Byte(b)) => a.cmp(b),
-            (Self::Short(a), Self::Short(b)) => a.cmp(b),
-            (Self::Int(a), Self::Int(b)) => a.cmp(b),
-            (Self::Long(a), Self::Long(b)) => a.cmp(b),
-            (Self::Float(a), Self::Float(b)) => a.total_cmp(b),
-            (Self::Double(a), Self::Double(b)) => a.total_cmp(b),
-            (Self::ByteArray(a), Self::ByteArray(b)) => a.cmp(b),
-            (Self::String(a), Self::String(b)) => a.cmp(b),
-            (Self::List(a), Self::List(b)) => a.cmp(b),
-            (Self::Compound(a), Self::Compound(b)) => a.cmp(b),
-            (Self::IntArray(a), Self::IntArray(b)) => a.cmp(b),
-            (Self::LongArray(a), Self::LongArray(b)) => a.cmp(b),
-            _ => self.get_type_id().cmp(&other.get_type_id()),
-        }
+        NbtTagView::from(self).cmp(&NbtTagView::from(other))
     }
 }
 
@@ -100,45 +78,59 @@ impl NbtTag {
     }
 
     pub fn serialize_data_into(&self, bytes: &mut BytesMut) {
+        self.serialize_data_into_with_flavor(bytes, NbtFlavor::JavaBE)
+    }
+
+    pub fn serialize_data_into_with_flavor(&self, bytes: &mut BytesMut, flavor: NbtFlavor) {
         match self {
             NbtTag::End => {}
             NbtTag::Byte(byte) => bytes.put_i8(*byte),
-            NbtTag::Short(short) => bytes.put_i16(*short),
-            NbtTag::Int(int) => bytes.put_i32(*int),
-            NbtTag::Long(long) => bytes.put_i64(*long),
-            NbtTag::Float(float) => bytes.put_f32(*float),
-            NbtTag::Double(double) => bytes.put_f64(*double),
-            NbtTag::ByteArray(byte_array) => {
-                bytes.put_i32(byte_array.len() as i32);
-                bytes.put_slice(byte_array);
-            }
-            NbtTag::String(string) => serialize_str_into(string, bytes),
-            NbtTag::List(list) => {
-                bytes.put_u8(list.first().unwrap_or(&NbtTag::End).get_type_id());
-                bytes.put_i32(list.len() as i32);
-                for nbt_tag in list {
-                    nbt_tag.serialize_data_into(bytes);
-                }
-            }
-            NbtTag::Compound(compound) => compound.serialize_content_into(bytes),
-            NbtTag::IntArray(int_array) => {
-                bytes.put_i32(int_array.len() as i32);
-                for int in int_array {
-                    bytes.put_i32(*int)
-                }
-            }
-            NbtTag::LongArray(long_array) => {
-                bytes.put_i32(long_array.len() as i32);
-                for long in long_array {
-                    bytes.put_i64(*long)
-                }
+            NbtTag::Short(short) => flavor.put_i16(bytes, *short),
+            NbtTag::Int(int) => flavor.put_i32(bytes, *int),
+            NbtTag::Long(long) => flavor.put_i64(bytes, *long),
+            NbtTag::Float(float) => flavor.put_f32(bytes, *float),
+            NbtTag::Double(double) => flavor.put_f64(bytes, *double),
+            NbtTag::ByteArray(byte_array) => flavor.put_byte_array(bytes, byte_array),
+            NbtTag::String(string) => flavor.put_string(bytes, string),
+            NbtTag::List(list) => list.serialize_data_into_with_flavor(bytes, flavor),
+            NbtTag::Compound(compound) => {
+                compound.serialize_content_into_with_flavor(bytes, flavor)
             }
+            NbtTag::IntArray(int_array) => flavor.put_i32_array(bytes, int_array),
+            NbtTag::LongArray(long_array) => flavor.put_i64_array(bytes, long_array),
         }
     }
 
     pub fn deserialize(bytes: &mut impl Buf) -> Result<NbtTag, Error> {
-        let tag_id = bytes.try_get_u8()?;
-        Self::deserialize_data(bytes, tag_id)
+        Self::deserialize_with_flavor(bytes, NbtFlavor::JavaBE)
+    }
+
+    pub fn deserialize_with_flavor(
+        bytes: &mut impl Buf,
+        flavor: NbtFlavor,
+    ) -> Result<NbtTag, Error> {
+        let mut context = ReadContext::new(ReadOptions::default().with_flavor(flavor), bytes);
+        bytes
+            .try_get_u8()
+            .map_err(Error::from)
+            .and_then(|tag_id| Self::read_data(bytes, tag_id, &mut context))
+            .map_err(|error| context.locate(bytes, error))
+    }
+
+    /// Reads a tag like [NbtTag::deserialize], pulling the data from a reader as it's needed.
+    pub fn from_reader(reader: impl Read) -> Result<NbtTag, Error> {
+        Self::from_reader_with_options(reader, &ReadOptions::default())
+    }
+
+    pub fn from_reader_with_options(
+        reader: impl Read,
+        options: &ReadOptions,
+    ) -> Result<NbtTag, Error> {
+        let mut reader = NbtReader::from_reader_with_options(reader, options).single_tag();
+        match reader.read_tag()? {
+            Some((_, tag)) => Ok(tag),
+            None => unreachable!("the first event is always a value"),
+        }
     }
 
     pub fn deserialize_from_cursor(cursor: &mut Cursor<&[u8]>) -> Result<NbtTag, Error> {
@@ -146,6 +138,51 @@ impl NbtTag {
     }
 
     pub fn deserialize_data(bytes: &mut impl Buf, tag_id: u8) -> Result<NbtTag, Error> {
+        Self::deserialize_data_with_flavor(bytes, tag_id, NbtFlavor::JavaBE)
+    }
+
+    pub fn deserialize_data_with_flavor(
+        bytes: &mut impl Buf,
+        tag_id: u8,
+        flavor: NbtFlavor,
+    ) -> Result<NbtTag, Error> {
+        Self::deserialize_data_with_options(
+            bytes,
+            tag_id,
+            &ReadOptions::default().with_flavor(flavor),
+        )
+    }
+
+    /// Like [NbtTag::deserialize_data], but enforcing limits from the given [ReadOptions].
+    pub fn deserialize_data_with_options(
+        bytes: &mut impl Buf,
+        tag_id: u8,
+        options: &ReadOptions,
+    ) -> Result<NbtTag, Error> {
+        let mut context = ReadContext::new(*options, bytes);
+        Self::read_data(bytes, tag_id, &mut context).map_err(|error| context.locate(bytes, error))
+    }
+
+    pub(crate) fn read_data(
+        bytes: &mut impl Buf,
+        tag_id: u8,
+        context: &mut ReadContext,
+    ) -> Result<NbtTag, Error> {
+        // Other tags are read separately to keep the stack frames of the recursion small
+        match tag_id {
+            LIST_ID => Ok(NbtTag::List(NbtList::read(bytes, context)?)),
+            COMPOUND_ID => Ok(NbtTag::Compound(NbtCompound::read_content(bytes, context)?)),
+            _ => Self::read_primitive_data(bytes, tag_id, context),
+        }
+    }
+
+    /// Reads the payload of a tag that can't contain other tags.
+    pub(crate) fn read_primitive_data(
+        bytes: &mut impl Buf,
+        tag_id: u8,
+        context: &mut ReadContext,
+    ) -> Result<NbtTag, Error> {
+        let flavor = context.flavor();
         match tag_id {
             END_ID => Ok(NbtTag::End),
             BYTE_ID => {
@@ -153,57 +190,29 @@ impl NbtTag {
                 Ok(NbtTag::Byte(byte))
             }
             SHORT_ID => {
-                let short = bytes.try_get_i16()?;
+                let short = flavor.get_i16(bytes)?;
                 Ok(NbtTag::Short(short))
             }
             INT_ID => {
-                let int = bytes.try_get_i32()?;
+                let int = flavor.get_i32(bytes)?;
                 Ok(NbtTag::Int(int))
             }
             LONG_ID => {
-                let long = bytes.try_get_i64()?;
+                let long = flavor.get_i64(bytes)?;
                 Ok(NbtTag::Long(long))
             }
             FLOAT_ID => {
-                let float = bytes.try_get_f32()?;
+                let float = flavor.get_f32(bytes)?;
                 Ok(NbtTag::Float(float))
             }
             DOUBLE_ID => {
-                let double = bytes.try_get_f64()?;
+                let double = flavor.get_f64(bytes)?;
                 Ok(NbtTag::Double(double))
             }
-            BYTE_ARRAY_ID => {
-                let len = bytes.try_get_i32()? as usize;
-                let byte_array = bytes.copy_to_bytes(len);
-                Ok(NbtTag::ByteArray(byte_array))
-            }
-            STRING_ID => Ok(NbtTag::String(get_nbt_string(bytes).unwrap())),
-            LIST_ID => {
-                let tag_type_id = bytes.try_get_u8()?;
-                let len = bytes.try_get_i32()?;
-                let mut list = Vec::with_capacity(len as usize);
-                for _ in 0..len {
-                    let tag = NbtTag::deserialize_data(bytes, tag_type_id)?;
-                    assert_eq!(tag.get_type_id(), tag_type_id);
-                    list.push(tag);
-                }
-                Ok(NbtTag::List(list))
-            }
-            COMPOUND_ID => Ok(NbtTag::Compound(NbtCompound::deserialize_content(bytes)?)),
-            INT_ARRAY_ID => {
-                const BYTES: usize = size_of::<i32>();
-
-                let len = bytes.try_get_i32()? as usize;
-                let numbers = read_array::<i32, BYTES, _>(bytes, len, i32::from_be_bytes);
-                Ok(NbtTag::IntArray(numbers))
-            }
-            LONG_ARRAY_ID => {
-                const BYTES: usize = size_of::<i64>();
-
-                let len = bytes.try_get_i32()? as usize;
-                let numbers = read_array::<i64, BYTES, _>(bytes, len, i64::from_be_bytes);
-                Ok(NbtTag::LongArray(numbers))
-            }
+            BYTE_ARRAY_ID => Ok(NbtTag::ByteArray(context.get_byte_array(bytes)?)),
+            STRING_ID => Ok(NbtTag::String(context.get_string(bytes)?)),
+            INT_ARRAY_ID => Ok(NbtTag::IntArray(context.get_int_array(bytes)?)),
+            LONG_ARRAY_ID => Ok(NbtTag::LongArray(context.get_long_array(bytes)?)),
             _ => Err(Error::UnknownTagId(tag_id)),
         }
     }
@@ -279,7 +288,7 @@ impl NbtTag {
         }
     }
 
-    pub fn extract_list(&self) -> Option<&Vec<NbtTag>> {
+    pub fn extract_list(&self) -> Option<&NbtList> {
         match self {
             NbtTag::List(list) => Some(list),
             _ => None,
@@ -306,6 +315,90 @@ impl NbtTag {
             _ => None,
         }
     }
+
+    pub fn extract_byte_mut(&mut self) -> Option<&mut i8> {
+        match self {
+            NbtTag::Byte(byte) => Some(byte),
+            _ => None,
+        }
+    }
+
+    pub fn extract_short_mut(&mut self) -> Option<&mut i16> {
+        match self {
+            NbtTag::Short(short) => Some(short),
+            _ => None,
+        }
+    }
+
+    pub fn extract_int_mut(&mut self) -> Option<&mut i32> {
+        match self {
+            NbtTag::Int(int) => Some(int),
+            _ => None,
+        }
+    }
+
+    pub fn extract_long_mut(&mut self) -> Option<&mut i64> {
+        match self {
+            NbtTag::Long(long) => Some(long),
+            _ => None,
+        }
+    }
+
+    pub fn extract_float_mut(&mut self) -> Option<&mut f32> {
+        match self {
+            NbtTag::Float(float) => Some(float),
+            _ => None,
+        }
+    }
+
+    pub fn extract_double_mut(&mut self) -> Option<&mut f64> {
+        match self {
+            NbtTag::Double(double) => Some(double),
+            _ => None,
+        }
+    }
+
+    pub fn extract_byte_array_mut(&mut self) -> Option<&mut Bytes> {
+        match self {
+            NbtTag::ByteArray(byte_array) => Some(byte_array),
+            _ => None,
+        }
+    }
+
+    pub fn extract_string_mut(&mut self) -> Option<&mut String> {
+        match self {
+            NbtTag::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn extract_list_mut(&mut self) -> Option<&mut NbtList> {
+        match self {
+            NbtTag::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn extract_compound_mut(&mut self) -> Option<&mut NbtCompound> {
+        match self {
+            NbtTag::Compound(compound) => Some(compound),
+            _ => None,
+        }
+    }
+
+    pub fn extract_int_array_mut(&mut self) -> Option<&mut Vec<i32>> {
+        match self {
+            NbtTag::IntArray(int_array) => Some(int_array),
+            _ => None,
+        }
+    }
+
+    pub fn extract_long_array_mut(&mut self) -> Option<&mut Vec<i64>> {
+        match self {
+            NbtTag::LongArray(long_array) => Some(long_array),
+            _ => None,
+        }
+    }
 }
 
 impl From<&str> for NbtTag {
@@ -328,37 +421,14 @@ impl From<bool> for NbtTag {
 
 impl Display for NbtTag {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::End => Ok(()),
-            Self::Byte(x) => write!(f, "{x}b"),
-            Self::Short(x) => write!(f, "{x}s"),
-            Self::Int(x) => write!(f, "{x}"),
-            Self::Long(x) => write!(f, "{x}L"),
-            // using debug here matches Minecraft on whole numbers (3.0 instead of 3)
-            Self::Float(x) => write!(f, "{x:?}f"),
-            Self::Double(x) => write!(f, "{x:?}d"),
-            Self::ByteArray(arr) => write_listlike(f, "B; ", "B", arr.iter().map(|b| *b as i8)),
-            Self::String(s) => write!(f, "{}", escape_string_value(s)),
-            Self::List(list) => write_listlike(f, "", "", list),
-            Self::Compound(compound) => write!(f, "{compound}"),
-            Self::IntArray(arr) => write_listlike(f, "I; ", "", arr),
-            Self::LongArray(arr) => write_listlike(f, "L; ", "L", arr),
-        }
+        NbtTagView::from(self).fmt(f)
     }
 }
 
-fn write_listlike<T: Display, I: IntoIterator<Item = T>>(
-    f: &mut Formatter<'_>,
-    prefix: &'static str,
-    affix: &'static str,
-    arr: I,
-) -> fmt::Result {
-    write!(f, "[{prefix}")?;
-    join_formatted(
-        f,
-        ", ",
-        arr.into_iter()
-            .map(|x| move |f: &mut Formatter<'_>| write!(f, "{x}{affix}")),
-    )?;
-    write!(f, "]")
+impl FromStr for NbtTag {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SnbtParser::new(s).parse_complete()
+    }
 }
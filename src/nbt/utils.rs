@@ -1,7 +1,7 @@
 use std::fmt::{self, Display, Formatter};
 
 use crate::error::Error;
-use bytes::{Buf, BufMut, BytesMut};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use simd_cesu8::decode;
 
 pub const END_ID: u8 = 0;
@@ -18,42 +18,76 @@ pub const COMPOUND_ID: u8 = 10;
 pub const INT_ARRAY_ID: u8 = 11;
 pub const LONG_ARRAY_ID: u8 = 12;
 
-pub fn get_nbt_string(bytes: &mut impl Buf) -> Result<String, Error> {
-    let len = bytes.try_get_u16()? as usize;
-    let string_bytes = bytes.copy_to_bytes(len);
-    let string = decode(&string_bytes).map_err(|_| Error::InvalidJavaString)?;
+/// Returns an error if there are less than `len` bytes remaining.
+pub(crate) fn ensure_remaining(bytes: &impl Buf, len: usize) -> Result<(), Error> {
+    if bytes.remaining() < len {
+        return Err(Error::NoDataRemaining {
+            requested: len,
+            remaining: bytes.remaining(),
+        });
+    }
+    Ok(())
+}
+
+/// Like [Buf::copy_to_bytes], but returns an error instead of panicking when there is not enough data.
+pub(crate) fn try_copy_to_bytes(bytes: &mut impl Buf, len: usize) -> Result<Bytes, Error> {
+    ensure_remaining(bytes, len)?;
+    Ok(bytes.copy_to_bytes(len))
+}
+
+pub(crate) fn decode_nbt_string(bytes: &[u8]) -> Result<String, Error> {
+    let string = decode(bytes).map_err(|_| Error::InvalidJavaString)?;
     Ok(string.to_string())
 }
 
+/// Writes a string with its length. Strings taking more than 65535 bytes once encoded are
+/// truncated, since their length can't be written.
 pub fn serialize_str_into(s: &str, bytes: &mut BytesMut) {
     if s.is_empty() {
         bytes.put_u16(0);
         return;
     }
 
+    // Supplementary characters are encoded as two surrogates of 3 bytes
+    let s = truncate_str(s, u16::MAX as usize, |c| match c.len_utf8() {
+        4 => 6,
+        len => len,
+    });
     let java_string = simd_cesu8::encode(s);
     bytes.put_u16(java_string.len() as u16);
     bytes.put_slice(&java_string);
 }
 
+/// Returns the longest prefix of the string taking at most `max_len` bytes,
+/// when every character is encoded with `char_len` bytes.
+pub(crate) fn truncate_str(s: &str, max_len: usize, char_len: impl Fn(char) -> usize) -> &str {
+    let mut len = 0;
+    for (index, c) in s.char_indices() {
+        len += char_len(c);
+        if len > max_len {
+            return &s[..index];
+        }
+    }
+    s
+}
+
 // This can be improved once rust-lang/rust#132980 is resolved:
 // Instead of passing `BYTES` manually, we could use const generics, e.g. `size_of::<T>()`.
 pub(crate) fn read_array<T, const N: usize, F>(
     bytes: &mut impl Buf,
     len: usize,
     from_be: F,
-) -> Vec<T>
+) -> Result<Vec<T>, Error>
 where
     F: Fn([u8; N]) -> T,
 {
-    bytes
-        .copy_to_bytes(len * N)
+    Ok(try_copy_to_bytes(bytes, len.saturating_mul(N))?
         .chunks_exact(N)
         .map(|chunk| {
             let arr: [u8; N] = chunk.try_into().expect("chunk size mismatch");
             from_be(arr)
         })
-        .collect()
+        .collect())
 }
 
 /// like [T]::join, but allowing for formatting
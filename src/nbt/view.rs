@@ -0,0 +1,710 @@
+//! Borrowed, lazily decoded views of NBT data.
+//!
+//! [NbtRef] validates the structure of the data once, without allocating,
+//! and then decodes only the values that are accessed, directly from the original bytes.
+//! Only [NbtFlavor::JavaBE](crate::NbtFlavor::JavaBE) data is supported.
+
+use crate::error::Error;
+use bytes::{Buf, Bytes};
+use crab_nbt::nbt::compound::NbtCompound;
+use crab_nbt::nbt::list::NbtList;
+use crab_nbt::nbt::options::DEFAULT_MAX_DEPTH;
+use crab_nbt::nbt::path::{NbtPath, PathSegment};
+use crab_nbt::nbt::tag::NbtTag;
+use crab_nbt::nbt::utils::*;
+use crab_nbt::nbt::Nbt;
+use simd_cesu8::decode;
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// Borrowed counterpart of [Nbt].
+#[derive(Clone, Copy, Debug)]
+pub struct NbtRef<'a> {
+    name: &'a [u8],
+    root_tag: CompoundRef<'a>,
+    byte_len: usize,
+}
+
+impl<'a> NbtRef<'a> {
+    /// Validates the NBT at the start of `bytes` and creates a view of it.
+    /// Use [NbtRef::byte_len] to get the amount of bytes taken by the NBT.
+    pub fn read(bytes: &'a [u8]) -> Result<NbtRef<'a>, Error> {
+        let mut rest = bytes;
+        let (name, root_tag) =
+            take_root(&mut rest, true).map_err(|error| locate(error, bytes.len() - rest.len()))?;
+        Ok(NbtRef {
+            name,
+            root_tag,
+            byte_len: bytes.len() - rest.len(),
+        })
+    }
+
+    /// Like [NbtRef::read], but for NBT without the name of the root compound.
+    /// Used in [Network NBT](https://wiki.vg/NBT#Network_NBT_(Java_Edition)).
+    pub fn read_unnamed(bytes: &'a [u8]) -> Result<NbtRef<'a>, Error> {
+        let mut rest = bytes;
+        let (name, root_tag) =
+            take_root(&mut rest, false).map_err(|error| locate(error, bytes.len() - rest.len()))?;
+        Ok(NbtRef {
+            name,
+            root_tag,
+            byte_len: bytes.len() - rest.len(),
+        })
+    }
+
+    pub fn name(&self) -> Cow<'a, str> {
+        decode_validated(self.name)
+    }
+
+    pub fn root_tag(&self) -> CompoundRef<'a> {
+        self.root_tag
+    }
+
+    /// Returns the amount of bytes taken by the NBT, including the root tag id and name.
+    pub fn byte_len(&self) -> usize {
+        self.byte_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root_tag.is_empty()
+    }
+
+    /// Decodes the whole NBT into its owned counterpart.
+    pub fn to_nbt(&self) -> Nbt {
+        Nbt::new(self.name().into_owned(), self.root_tag.to_compound())
+    }
+}
+
+impl<'a> Deref for NbtRef<'a> {
+    type Target = CompoundRef<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.root_tag
+    }
+}
+
+impl From<NbtRef<'_>> for Nbt {
+    fn from(value: NbtRef<'_>) -> Self {
+        value.to_nbt()
+    }
+}
+
+/// Borrowed counterpart of [NbtTag].
+#[derive(Clone, Debug)]
+pub enum NbtTagRef<'a> {
+    End,
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(&'a [u8]),
+    String(Cow<'a, str>),
+    List(ListRef<'a>),
+    Compound(CompoundRef<'a>),
+    IntArray(ArrayRef<'a, i32>),
+    LongArray(ArrayRef<'a, i64>),
+}
+
+impl<'a> NbtTagRef<'a> {
+    /// Creates the view from payload of a validated tag.
+    fn from_payload(tag_id: u8, mut payload: &'a [u8]) -> NbtTagRef<'a> {
+        match tag_id {
+            BYTE_ID => NbtTagRef::Byte(payload.get_i8()),
+            SHORT_ID => NbtTagRef::Short(payload.get_i16()),
+            INT_ID => NbtTagRef::Int(payload.get_i32()),
+            LONG_ID => NbtTagRef::Long(payload.get_i64()),
+            FLOAT_ID => NbtTagRef::Float(payload.get_f32()),
+            DOUBLE_ID => NbtTagRef::Double(payload.get_f64()),
+            BYTE_ARRAY_ID => NbtTagRef::ByteArray(&payload[size_of::<i32>()..]),
+            STRING_ID => NbtTagRef::String(decode_validated(&payload[size_of::<u16>()..])),
+            LIST_ID => {
+                let element_type = payload.get_u8();
+                let len = payload.get_i32() as usize;
+                NbtTagRef::List(ListRef {
+                    element_type,
+                    len,
+                    data: payload,
+                })
+            }
+            COMPOUND_ID => NbtTagRef::Compound(CompoundRef { data: payload }),
+            INT_ARRAY_ID => NbtTagRef::IntArray(ArrayRef::new(&payload[size_of::<i32>()..])),
+            LONG_ARRAY_ID => NbtTagRef::LongArray(ArrayRef::new(&payload[size_of::<i32>()..])),
+            _ => NbtTagRef::End,
+        }
+    }
+
+    /// Returns the numeric id associated with the data type.
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            NbtTagRef::End => END_ID,
+            NbtTagRef::Byte(_) => BYTE_ID,
+            NbtTagRef::Short(_) => SHORT_ID,
+            NbtTagRef::Int(_) => INT_ID,
+            NbtTagRef::Long(_) => LONG_ID,
+            NbtTagRef::Float(_) => FLOAT_ID,
+            NbtTagRef::Double(_) => DOUBLE_ID,
+            NbtTagRef::ByteArray(_) => BYTE_ARRAY_ID,
+            NbtTagRef::String(_) => STRING_ID,
+            NbtTagRef::List(_) => LIST_ID,
+            NbtTagRef::Compound(_) => COMPOUND_ID,
+            NbtTagRef::IntArray(_) => INT_ARRAY_ID,
+            NbtTagRef::LongArray(_) => LONG_ARRAY_ID,
+        }
+    }
+
+    /// Decodes the tag into its owned counterpart.
+    pub fn to_tag(&self) -> NbtTag {
+        match self {
+            NbtTagRef::End => NbtTag::End,
+            NbtTagRef::Byte(byte) => NbtTag::Byte(*byte),
+            NbtTagRef::Short(short) => NbtTag::Short(*short),
+            NbtTagRef::Int(int) => NbtTag::Int(*int),
+            NbtTagRef::Long(long) => NbtTag::Long(*long),
+            NbtTagRef::Float(float) => NbtTag::Float(*float),
+            NbtTagRef::Double(double) => NbtTag::Double(*double),
+            NbtTagRef::ByteArray(bytes) => NbtTag::ByteArray(Bytes::copy_from_slice(bytes)),
+            NbtTagRef::String(string) => NbtTag::String(string.clone().into_owned()),
+            NbtTagRef::List(list) => {
+                let tags: Vec<NbtTag> = list.iter().map(|tag| tag.to_tag()).collect();
+                // Elements of the view always have the same type, validated when it was created
+                NbtTag::List(NbtList::try_from(tags).unwrap_or_default())
+            }
+            NbtTagRef::Compound(compound) => NbtTag::Compound(compound.to_compound()),
+            NbtTagRef::IntArray(array) => NbtTag::IntArray(array.to_vec()),
+            NbtTagRef::LongArray(array) => NbtTag::LongArray(array.to_vec()),
+        }
+    }
+
+    pub fn extract_byte(&self) -> Option<i8> {
+        match self {
+            NbtTagRef::Byte(byte) => Some(*byte),
+            _ => None,
+        }
+    }
+
+    pub fn extract_short(&self) -> Option<i16> {
+        match self {
+            NbtTagRef::Short(short) => Some(*short),
+            _ => None,
+        }
+    }
+
+    pub fn extract_int(&self) -> Option<i32> {
+        match self {
+            NbtTagRef::Int(int) => Some(*int),
+            _ => None,
+        }
+    }
+
+    pub fn extract_long(&self) -> Option<i64> {
+        match self {
+            NbtTagRef::Long(long) => Some(*long),
+            _ => None,
+        }
+    }
+
+    pub fn extract_float(&self) -> Option<f32> {
+        match self {
+            NbtTagRef::Float(float) => Some(*float),
+            _ => None,
+        }
+    }
+
+    pub fn extract_double(&self) -> Option<f64> {
+        match self {
+            NbtTagRef::Double(double) => Some(*double),
+            _ => None,
+        }
+    }
+
+    pub fn extract_bool(&self) -> Option<bool> {
+        match self {
+            NbtTagRef::Byte(byte) => Some(*byte != 0),
+            _ => None,
+        }
+    }
+
+    pub fn extract_byte_array(&self) -> Option<&'a [u8]> {
+        match self {
+            NbtTagRef::ByteArray(byte_array) => Some(byte_array),
+            _ => None,
+        }
+    }
+
+    pub fn extract_string(self) -> Option<Cow<'a, str>> {
+        match self {
+            NbtTagRef::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn extract_list(&self) -> Option<ListRef<'a>> {
+        match self {
+            NbtTagRef::List(list) => Some(*list),
+            _ => None,
+        }
+    }
+
+    pub fn extract_compound(&self) -> Option<CompoundRef<'a>> {
+        match self {
+            NbtTagRef::Compound(compound) => Some(*compound),
+            _ => None,
+        }
+    }
+
+    pub fn extract_int_array(&self) -> Option<ArrayRef<'a, i32>> {
+        match self {
+            NbtTagRef::IntArray(int_array) => Some(*int_array),
+            _ => None,
+        }
+    }
+
+    pub fn extract_long_array(&self) -> Option<ArrayRef<'a, i64>> {
+        match self {
+            NbtTagRef::LongArray(long_array) => Some(*long_array),
+            _ => None,
+        }
+    }
+}
+
+impl From<NbtTagRef<'_>> for NbtTag {
+    fn from(value: NbtTagRef<'_>) -> Self {
+        value.to_tag()
+    }
+}
+
+/// Borrowed counterpart of [NbtCompound].
+/// Lookups scan the compound, comparing keys without decoding them.
+#[derive(Clone, Copy)]
+pub struct CompoundRef<'a> {
+    /// Content of the compound, including the end tag.
+    data: &'a [u8],
+}
+
+impl<'a> CompoundRef<'a> {
+    /// Validates the compound at the start of `bytes` and advances past it.
+    fn take(bytes: &mut &'a [u8]) -> Result<CompoundRef<'a>, Error> {
+        let data = *bytes;
+        skip_payload(bytes, COMPOUND_ID, Some(0))?;
+        Ok(CompoundRef {
+            data: &data[..data.len() - bytes.len()],
+        })
+    }
+
+    /// Iterates over the entries, decoding keys on demand.
+    pub fn iter(&self) -> impl Iterator<Item = (Cow<'a, str>, NbtTagRef<'a>)> {
+        self.raw_entries()
+            .map(|(name, tag)| (decode_validated(name), tag))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = Cow<'a, str>> {
+        self.raw_entries().map(|(name, _)| decode_validated(name))
+    }
+
+    pub fn len(&self) -> usize {
+        self.raw_entries().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.first().is_none_or(|id| *id == END_ID)
+    }
+
+    fn raw_entries(&self) -> RawEntries<'a> {
+        RawEntries { data: self.data }
+    }
+
+    pub fn get(&self, name: &str) -> Option<NbtTagRef<'a>> {
+        let encoded = simd_cesu8::encode(name);
+        self.raw_entries()
+            .find(|(key, _)| *key == encoded.as_ref())
+            .map(|(_, tag)| tag)
+    }
+
+    pub fn get_byte(&self, name: &str) -> Option<i8> {
+        self.get(name).and_then(|tag| tag.extract_byte())
+    }
+
+    pub fn get_short(&self, name: &str) -> Option<i16> {
+        self.get(name).and_then(|tag| tag.extract_short())
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<i32> {
+        self.get(name).and_then(|tag| tag.extract_int())
+    }
+
+    pub fn get_long(&self, name: &str) -> Option<i64> {
+        self.get(name).and_then(|tag| tag.extract_long())
+    }
+
+    pub fn get_float(&self, name: &str) -> Option<f32> {
+        self.get(name).and_then(|tag| tag.extract_float())
+    }
+
+    pub fn get_double(&self, name: &str) -> Option<f64> {
+        self.get(name).and_then(|tag| tag.extract_double())
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        self.get(name).and_then(|tag| tag.extract_bool())
+    }
+
+    pub fn get_byte_array(&self, name: &str) -> Option<&'a [u8]> {
+        self.get(name).and_then(|tag| tag.extract_byte_array())
+    }
+
+    pub fn get_string(&self, name: &str) -> Option<Cow<'a, str>> {
+        self.get(name).and_then(|tag| tag.extract_string())
+    }
+
+    pub fn get_list(&self, name: &str) -> Option<ListRef<'a>> {
+        self.get(name).and_then(|tag| tag.extract_list())
+    }
+
+    pub fn get_compound(&self, name: &str) -> Option<CompoundRef<'a>> {
+        self.get(name).and_then(|tag| tag.extract_compound())
+    }
+
+    pub fn get_int_array(&self, name: &str) -> Option<ArrayRef<'a, i32>> {
+        self.get(name).and_then(|tag| tag.extract_int_array())
+    }
+
+    pub fn get_long_array(&self, name: &str) -> Option<ArrayRef<'a, i64>> {
+        self.get(name).and_then(|tag| tag.extract_long_array())
+    }
+
+    /// Decodes the whole compound into its owned counterpart.
+    pub fn to_compound(&self) -> NbtCompound {
+        self.iter()
+            .map(|(name, tag)| (name.into_owned(), tag.to_tag()))
+            .collect()
+    }
+}
+
+impl Debug for CompoundRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl From<CompoundRef<'_>> for NbtCompound {
+    fn from(value: CompoundRef<'_>) -> Self {
+        value.to_compound()
+    }
+}
+
+/// Iterator over undecoded names and values of a validated compound.
+struct RawEntries<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for RawEntries<'a> {
+    type Item = (&'a [u8], NbtTagRef<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tag_id = *self.data.first()?;
+        if tag_id == END_ID {
+            return None;
+        }
+        self.data = &self.data[1..];
+
+        let name_len = self.data.get_u16() as usize;
+        let (name, rest) = self.data.split_at(name_len);
+        self.data = rest;
+
+        let tag = take_tag(&mut self.data, tag_id);
+        Some((name, tag))
+    }
+}
+
+/// Borrowed counterpart of a list tag.
+#[derive(Clone, Copy)]
+pub struct ListRef<'a> {
+    element_type: u8,
+    len: usize,
+    /// Elements of the list, without the element type and length.
+    data: &'a [u8],
+}
+
+impl<'a> ListRef<'a> {
+    pub fn element_type(&self) -> u8 {
+        self.element_type
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = NbtTagRef<'a>> {
+        let element_type = self.element_type;
+        let mut data = self.data;
+        (0..self.len).map(move |_| take_tag(&mut data, element_type))
+    }
+
+    pub fn get(&self, index: usize) -> Option<NbtTagRef<'a>> {
+        if index >= self.len {
+            return None;
+        }
+        if let Some(size) = fixed_size(self.element_type) {
+            let payload = &self.data[index * size..(index + 1) * size];
+            return Some(NbtTagRef::from_payload(self.element_type, payload));
+        }
+        self.iter().nth(index)
+    }
+}
+
+impl Debug for ListRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Borrowed view of an int or long array, decoding the numbers on access.
+#[derive(Clone, Copy)]
+pub struct ArrayRef<'a, T> {
+    data: &'a [u8],
+    _type: PhantomData<T>,
+}
+
+impl<'a, T: ArrayElement> ArrayRef<'a, T> {
+    fn new(data: &'a [u8]) -> Self {
+        ArrayRef {
+            data,
+            _type: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len() / T::SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(T::from_be_slice(
+            &self.data[index * T::SIZE..(index + 1) * T::SIZE],
+        ))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        self.data.chunks_exact(T::SIZE).map(T::from_be_slice)
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
+impl<T: ArrayElement + Debug> Debug for ArrayRef<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Numbers that can be stored in [ArrayRef].
+pub trait ArrayElement: Copy + 'static {
+    const SIZE: usize;
+
+    fn from_be_slice(bytes: &[u8]) -> Self;
+}
+
+impl ArrayElement for i32 {
+    const SIZE: usize = size_of::<i32>();
+
+    fn from_be_slice(bytes: &[u8]) -> Self {
+        i32::from_be_bytes(bytes.try_into().expect("chunk size mismatch"))
+    }
+}
+
+impl ArrayElement for i64 {
+    const SIZE: usize = size_of::<i64>();
+
+    fn from_be_slice(bytes: &[u8]) -> Self {
+        i64::from_be_bytes(bytes.try_into().expect("chunk size mismatch"))
+    }
+}
+
+/// Adds the offset to an error, which is known only after returning to the start of the data.
+fn locate(error: Error, offset: usize) -> Error {
+    match error {
+        Error::At { path, source, .. } => Error::At {
+            offset,
+            path,
+            source,
+        },
+        error => Error::At {
+            offset,
+            path: NbtPath::new(),
+            source: Box::new(error),
+        },
+    }
+}
+
+/// Adds a segment to the path of an error that occurred while validating a child tag.
+fn locate_in(error: Error, segment: PathSegment) -> Error {
+    // The offset is set later by `locate`
+    let mut error = locate(error, 0);
+    if let Error::At { path, .. } = &mut error {
+        path.push_front(segment);
+    }
+    error
+}
+
+/// Takes the name and the content of the root compound.
+fn take_root<'a>(
+    bytes: &mut &'a [u8],
+    is_named: bool,
+) -> Result<(&'a [u8], CompoundRef<'a>), Error> {
+    let tag_type_id = bytes.try_get_u8()?;
+    if tag_type_id != COMPOUND_ID {
+        return Err(Error::NoRootCompound(tag_type_id));
+    }
+    let name = if is_named {
+        take_string(bytes, true)?
+    } else {
+        &[]
+    };
+    Ok((name, CompoundRef::take(bytes)?))
+}
+
+/// Takes a validated tag from the start of `bytes`.
+fn take_tag<'a>(bytes: &mut &'a [u8], tag_id: u8) -> NbtTagRef<'a> {
+    let data = *bytes;
+    // The data was already validated, so skipping can't fail and only has to follow lengths
+    let _ = skip_payload(bytes, tag_id, None);
+    NbtTagRef::from_payload(tag_id, &data[..data.len() - bytes.len()])
+}
+
+fn decode_validated(bytes: &[u8]) -> Cow<'_, str> {
+    decode(bytes).expect("strings are validated when the view is created")
+}
+
+/// Size of payloads of tags that don't contain any length.
+fn fixed_size(tag_id: u8) -> Option<usize> {
+    match tag_id {
+        END_ID => Some(0),
+        BYTE_ID => Some(1),
+        SHORT_ID => Some(2),
+        INT_ID | FLOAT_ID => Some(4),
+        LONG_ID | DOUBLE_ID => Some(8),
+        _ => None,
+    }
+}
+
+pub(crate) fn take_slice<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if len > bytes.len() {
+        return Err(Error::NoDataRemaining {
+            requested: len,
+            remaining: bytes.len(),
+        });
+    }
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(taken)
+}
+
+fn take_string<'a>(bytes: &mut &'a [u8], validate: bool) -> Result<&'a [u8], Error> {
+    let len = bytes.try_get_u16()? as usize;
+    let string = take_slice(bytes, len)?;
+    if validate {
+        decode(string).map_err(|_| Error::InvalidJavaString)?;
+    }
+    Ok(string)
+}
+
+/// Reads length of a list or an array.
+fn take_len(bytes: &mut &[u8]) -> Result<usize, Error> {
+    let len = bytes.try_get_i32()?;
+    usize::try_from(len).map_err(|_| Error::NegativeLength(len))
+}
+
+/// Advances past the payload of a tag. When `depth` is given, the payload is validated
+/// as the content of that many compounds and lists, otherwise it was validated before.
+fn skip_payload(bytes: &mut &[u8], tag_id: u8, depth: Option<usize>) -> Result<(), Error> {
+    // Other tags are skipped separately to keep the stack frames of the recursion small
+    match tag_id {
+        LIST_ID => skip_list(bytes, depth),
+        COMPOUND_ID => skip_compound(bytes, depth),
+        _ => skip_primitive(bytes, tag_id, depth.is_some()),
+    }
+}
+
+fn skip_primitive(bytes: &mut &[u8], tag_id: u8, validate: bool) -> Result<(), Error> {
+    if let Some(size) = fixed_size(tag_id) {
+        take_slice(bytes, size)?;
+        return Ok(());
+    }
+
+    match tag_id {
+        BYTE_ARRAY_ID => {
+            let len = take_len(bytes)?;
+            take_slice(bytes, len)?;
+        }
+        INT_ARRAY_ID => {
+            let len = take_len(bytes)?;
+            take_slice(bytes, len.saturating_mul(size_of::<i32>()))?;
+        }
+        LONG_ARRAY_ID => {
+            let len = take_len(bytes)?;
+            take_slice(bytes, len.saturating_mul(size_of::<i64>()))?;
+        }
+        STRING_ID => {
+            take_string(bytes, validate)?;
+        }
+        _ => return Err(Error::UnknownTagId(tag_id)),
+    }
+    Ok(())
+}
+
+fn skip_list(bytes: &mut &[u8], depth: Option<usize>) -> Result<(), Error> {
+    if depth.is_some_and(|depth| depth >= DEFAULT_MAX_DEPTH) {
+        return Err(Error::DepthLimitExceeded(DEFAULT_MAX_DEPTH));
+    }
+
+    let element_type = bytes.try_get_u8()?;
+    let len = take_len(bytes)?;
+    if element_type == END_ID && len > 0 {
+        return Err(Error::MissingListType(len));
+    }
+    match fixed_size(element_type) {
+        Some(size) => {
+            take_slice(bytes, len.saturating_mul(size))?;
+        }
+        None => {
+            for index in 0..len {
+                skip_payload(bytes, element_type, depth.map(|depth| depth + 1))
+                    .map_err(|error| locate_in(error, PathSegment::Index(index)))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn skip_compound(bytes: &mut &[u8], depth: Option<usize>) -> Result<(), Error> {
+    if depth.is_some_and(|depth| depth >= DEFAULT_MAX_DEPTH) {
+        return Err(Error::DepthLimitExceeded(DEFAULT_MAX_DEPTH));
+    }
+
+    loop {
+        let tag_id = bytes.try_get_u8()?;
+        if tag_id == END_ID {
+            return Ok(());
+        }
+        let name = take_string(bytes, depth.is_some())?;
+        skip_payload(bytes, tag_id, depth.map(|depth| depth + 1)).map_err(|error| {
+            locate_in(error, PathSegment::Key(decode_validated(name).into_owned()))
+        })?;
+    }
+}
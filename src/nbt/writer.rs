@@ -0,0 +1,313 @@
+use crate::error::Error;
+use bytes::{BufMut, BytesMut};
+use crab_nbt::nbt::compound::NbtCompound;
+use crab_nbt::nbt::flavor::NbtFlavor;
+use crab_nbt::nbt::list::NbtList;
+use crab_nbt::nbt::tag::NbtTag;
+use crab_nbt::nbt::utils::*;
+use std::io::Write;
+
+/// Number of buffered bytes after which they are written to the sink.
+const FLUSH_LEN: usize = 8192;
+
+/// Compound or list that is being written.
+#[derive(Debug)]
+enum Frame {
+    Compound,
+    List { element_type: u8, remaining: usize },
+}
+
+/// Push writer which writes NBT directly to a [Write]r, without building the tags in memory.
+///
+/// Calls have to form valid NBT, which is checked at runtime: the root has to be a compound,
+/// every compound and list has to be ended, and lists have to contain the declared number
+/// of elements of the declared type. Names are ignored for elements of lists.
+///
+/// Data is written in parts of a few kilobytes and flushed when the root compound ends.
+///
+/// ```
+/// use crab_nbt::{Nbt, NbtWriter};
+///
+/// let mut writer = NbtWriter::new(Vec::new());
+/// writer.begin_compound("").unwrap();
+/// writer.begin_list("Pos", 6, 3).unwrap();
+/// for coordinate in [1.5, 64.0, -3.5] {
+///     writer.write_double("", coordinate).unwrap();
+/// }
+/// writer.end().unwrap();
+/// writer.write_int("XpLevel", 3).unwrap();
+/// writer.end().unwrap();
+///
+/// let bytes = writer.finish().unwrap();
+/// let nbt = Nbt::read(&mut bytes.as_slice()).unwrap();
+/// assert_eq!(nbt.get_int("XpLevel"), Some(3));
+/// ```
+#[derive(Debug)]
+pub struct NbtWriter<W: Write> {
+    writer: W,
+    flavor: NbtFlavor,
+    is_named: bool,
+    buffer: BytesMut,
+    stack: Vec<Frame>,
+    started: bool,
+}
+
+impl<W: Write> NbtWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self::with_flavor(writer, NbtFlavor::JavaBE)
+    }
+
+    pub fn with_flavor(writer: W, flavor: NbtFlavor) -> Self {
+        NbtWriter {
+            writer,
+            flavor,
+            is_named: true,
+            buffer: BytesMut::new(),
+            stack: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Writes NBT without the name of the root compound.
+    /// Used in [Network NBT](https://wiki.vg/NBT#Network_NBT_(Java_Edition)).
+    pub fn unnamed(self) -> Self {
+        NbtWriter {
+            is_named: false,
+            ..self
+        }
+    }
+
+    /// Returns the writer after checking that the root compound was ended.
+    pub fn finish(mut self) -> Result<W, Error> {
+        if !self.started || !self.stack.is_empty() {
+            return Err(Error::InvalidWrite(
+                "the root compound wasn't ended".to_owned(),
+            ));
+        }
+        self.flush_buffer()?;
+        Ok(self.writer)
+    }
+
+    /// Returns the writer, to which data is written in parts of a few kilobytes.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Starts a compound, which has to be ended with [NbtWriter::end].
+    pub fn begin_compound(&mut self, name: &str) -> Result<(), Error> {
+        self.put_header(name, COMPOUND_ID)?;
+        self.stack.push(Frame::Compound);
+        Ok(())
+    }
+
+    /// Starts a list of `len` tags with id `element_type`, which has to be ended
+    /// with [NbtWriter::end] after writing all of them.
+    pub fn begin_list(&mut self, name: &str, element_type: u8, len: usize) -> Result<(), Error> {
+        if element_type > LONG_ARRAY_ID {
+            return Err(Error::UnknownTagId(element_type));
+        }
+        if element_type == END_ID && len > 0 {
+            return Err(Error::MissingListType(len));
+        }
+        let Ok(header_len) = i32::try_from(len) else {
+            return Err(Error::InvalidWrite(format!(
+                "list with {len} elements is longer than the maximum of {}",
+                i32::MAX
+            )));
+        };
+
+        self.put_header(name, LIST_ID)?;
+        self.buffer.put_u8(element_type);
+        self.flavor.put_i32(&mut self.buffer, header_len);
+        self.stack.push(Frame::List {
+            element_type,
+            remaining: len,
+        });
+        Ok(())
+    }
+
+    /// Ends the innermost compound or list.
+    pub fn end(&mut self) -> Result<(), Error> {
+        match self.stack.last() {
+            None => {
+                return Err(Error::InvalidWrite(
+                    "there is no compound or list to end".to_owned(),
+                ))
+            }
+            Some(Frame::List { remaining, .. }) if *remaining > 0 => {
+                return Err(Error::InvalidWrite(format!(
+                    "the list is missing {remaining} elements"
+                )))
+            }
+            Some(Frame::List { .. }) => {}
+            Some(Frame::Compound) => self.buffer.put_u8(END_ID),
+        }
+        self.stack.pop();
+
+        if self.stack.is_empty() {
+            self.flush_buffer()
+        } else {
+            self.flush_if_full()
+        }
+    }
+
+    pub fn write_byte(&mut self, name: &str, value: i8) -> Result<(), Error> {
+        self.put_header(name, BYTE_ID)?;
+        self.buffer.put_i8(value);
+        self.flush_if_full()
+    }
+
+    pub fn write_bool(&mut self, name: &str, value: bool) -> Result<(), Error> {
+        self.write_byte(name, value as i8)
+    }
+
+    pub fn write_short(&mut self, name: &str, value: i16) -> Result<(), Error> {
+        self.put_header(name, SHORT_ID)?;
+        self.flavor.put_i16(&mut self.buffer, value);
+        self.flush_if_full()
+    }
+
+    pub fn write_int(&mut self, name: &str, value: i32) -> Result<(), Error> {
+        self.put_header(name, INT_ID)?;
+        self.flavor.put_i32(&mut self.buffer, value);
+        self.flush_if_full()
+    }
+
+    pub fn write_long(&mut self, name: &str, value: i64) -> Result<(), Error> {
+        self.put_header(name, LONG_ID)?;
+        self.flavor.put_i64(&mut self.buffer, value);
+        self.flush_if_full()
+    }
+
+    pub fn write_float(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        self.put_header(name, FLOAT_ID)?;
+        self.flavor.put_f32(&mut self.buffer, value);
+        self.flush_if_full()
+    }
+
+    pub fn write_double(&mut self, name: &str, value: f64) -> Result<(), Error> {
+        self.put_header(name, DOUBLE_ID)?;
+        self.flavor.put_f64(&mut self.buffer, value);
+        self.flush_if_full()
+    }
+
+    pub fn write_string(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        self.put_header(name, STRING_ID)?;
+        self.flavor.put_string(&mut self.buffer, value);
+        self.flush_if_full()
+    }
+
+    pub fn write_byte_array(&mut self, name: &str, values: &[u8]) -> Result<(), Error> {
+        self.put_header(name, BYTE_ARRAY_ID)?;
+        self.flavor.put_byte_array(&mut self.buffer, values);
+        self.flush_if_full()
+    }
+
+    pub fn write_int_array(&mut self, name: &str, values: &[i32]) -> Result<(), Error> {
+        self.put_header(name, INT_ARRAY_ID)?;
+        self.flavor.put_i32_array(&mut self.buffer, values);
+        self.flush_if_full()
+    }
+
+    pub fn write_long_array(&mut self, name: &str, values: &[i64]) -> Result<(), Error> {
+        self.put_header(name, LONG_ARRAY_ID)?;
+        self.flavor.put_i64_array(&mut self.buffer, values);
+        self.flush_if_full()
+    }
+
+    /// Writes a tag built in memory. Compounds and lists of compounds or lists are written
+    /// part by part, so that they don't have to be buffered at once.
+    pub fn write_tag(&mut self, name: &str, tag: &NbtTag) -> Result<(), Error> {
+        match tag {
+            NbtTag::Compound(compound) => self.write_compound(name, compound),
+            NbtTag::List(list) => self.write_list(name, list),
+            tag => {
+                self.put_header(name, tag.get_type_id())?;
+                tag.serialize_data_into_with_flavor(&mut self.buffer, self.flavor);
+                self.flush_if_full()
+            }
+        }
+    }
+
+    pub fn write_compound(&mut self, name: &str, compound: &NbtCompound) -> Result<(), Error> {
+        self.begin_compound(name)?;
+        for (name, tag) in compound {
+            self.write_tag(name, tag)?;
+        }
+        self.end()
+    }
+
+    pub fn write_list(&mut self, name: &str, list: &NbtList) -> Result<(), Error> {
+        match list {
+            NbtList::Compounds(compounds) => {
+                self.begin_list(name, COMPOUND_ID, compounds.len())?;
+                for compound in compounds {
+                    self.write_compound("", compound)?;
+                }
+                self.end()
+            }
+            NbtList::Lists(lists) => {
+                self.begin_list(name, LIST_ID, lists.len())?;
+                for list in lists {
+                    self.write_list("", list)?;
+                }
+                self.end()
+            }
+            list => {
+                self.put_header(name, LIST_ID)?;
+                list.serialize_data_into_with_flavor(&mut self.buffer, self.flavor);
+                self.flush_if_full()
+            }
+        }
+    }
+
+    /// Writes the id and the name of a tag, or checks that it can be an element of the list.
+    fn put_header(&mut self, name: &str, tag_id: u8) -> Result<(), Error> {
+        match self.stack.last_mut() {
+            None if self.started => {
+                return Err(Error::InvalidWrite(
+                    "the root compound was already ended".to_owned(),
+                ))
+            }
+            None if tag_id != COMPOUND_ID => return Err(Error::NoRootCompound(tag_id)),
+            None => {
+                self.started = true;
+                self.buffer.put_u8(COMPOUND_ID);
+                if self.is_named {
+                    self.flavor.put_string(&mut self.buffer, name);
+                }
+            }
+            Some(Frame::Compound) => {
+                self.buffer.put_u8(tag_id);
+                self.flavor.put_string(&mut self.buffer, name);
+            }
+            Some(Frame::List { element_type, .. }) if *element_type != tag_id => {
+                return Err(Error::ListTypeMismatch {
+                    list_type: *element_type,
+                    tag_type: tag_id,
+                })
+            }
+            Some(Frame::List { remaining: 0, .. }) => {
+                return Err(Error::InvalidWrite(
+                    "the list already contains all of its elements".to_owned(),
+                ))
+            }
+            Some(Frame::List { remaining, .. }) => *remaining -= 1,
+        }
+        Ok(())
+    }
+
+    fn flush_if_full(&mut self) -> Result<(), Error> {
+        if self.buffer.len() >= FLUSH_LEN {
+            self.flush_buffer()?;
+        }
+        Ok(())
+    }
+
+    fn flush_buffer(&mut self) -> Result<(), Error> {
+        self.writer.write_all(&self.buffer)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+//! Reading and writing of [Anvil](https://minecraft.wiki/w/Anvil_file_format)
+//! and [McRegion](https://minecraft.wiki/w/Region_file_format) region files,
+//! which store NBT of 32×32 chunks.
+
+use crate::error::Error;
+use crate::{Compression, Nbt, ReadOptions};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const SECTOR_SIZE: usize = 4096;
+/// Amount of chunks stored in a single region file.
+pub const CHUNK_COUNT: usize = 32 * 32;
+const HEADER_SECTORS: u32 = 2;
+/// Chunks that don't fit in this many sectors are stored in an external `.mcc` file.
+const MAX_CHUNK_SECTORS: usize = u8::MAX as usize;
+/// Flag set on the compression type of chunks stored in an external file.
+const EXTERNAL_FLAG: u8 = 0x80;
+
+/// Region file opened from any seekable stream.
+///
+/// Chunk coordinates can be given either relative to the region (`0..32`)
+/// or as absolute chunk coordinates, as only their lowest 5 bits are used.
+pub struct RegionFile<S> {
+    stream: S,
+    locations: Vec<u32>,
+    timestamps: Vec<u32>,
+    external: Option<ExternalChunks>,
+    read_options: ReadOptions,
+}
+
+struct ExternalChunks {
+    directory: PathBuf,
+    region_x: i32,
+    region_z: i32,
+}
+
+impl<S: Read + Seek> RegionFile<S> {
+    /// Opens an existing region file by reading its header.
+    pub fn open(mut stream: S) -> Result<Self, Error> {
+        stream.seek(SeekFrom::Start(0))?;
+        let mut header = vec![0; SECTOR_SIZE * HEADER_SECTORS as usize];
+        stream.read_exact(&mut header)?;
+
+        let (locations, timestamps) = header.split_at(SECTOR_SIZE);
+        Ok(RegionFile {
+            stream,
+            locations: read_header_table(locations),
+            timestamps: read_header_table(timestamps),
+            external: None,
+            read_options: ReadOptions::default(),
+        })
+    }
+
+    /// Enables support for chunks stored in external `c.<x>.<z>.mcc` files,
+    /// which are located in `directory` next to the region file.
+    /// Coordinates of the region are required to know names of these files.
+    pub fn with_external_chunks(
+        mut self,
+        directory: impl Into<PathBuf>,
+        region_x: i32,
+        region_z: i32,
+    ) -> Self {
+        self.external = Some(ExternalChunks {
+            directory: directory.into(),
+            region_x,
+            region_z,
+        });
+        self
+    }
+
+    /// Sets the options used when reading chunks, for example to limit the size
+    /// of their decompressed data with [ReadOptions::max_total_bytes].
+    pub fn with_read_options(mut self, options: &ReadOptions) -> Self {
+        self.read_options = *options;
+        self
+    }
+
+    /// Returns relative coordinates of all chunks present in this region.
+    pub fn chunks(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.locations
+            .iter()
+            .enumerate()
+            .filter(|(_, location)| **location != 0)
+            .map(|(index, _)| ((index % 32) as i32, (index / 32) as i32))
+    }
+
+    pub fn has_chunk(&self, x: i32, z: i32) -> bool {
+        self.locations[chunk_index(x, z)] != 0
+    }
+
+    /// Returns the last modification time of the chunk in seconds since the Unix epoch.
+    pub fn timestamp(&self, x: i32, z: i32) -> Option<u32> {
+        self.has_chunk(x, z)
+            .then(|| self.timestamps[chunk_index(x, z)])
+    }
+
+    /// Reads the chunk, returning `None` if it's not present in this region.
+    pub fn read_chunk(&mut self, x: i32, z: i32) -> Result<Option<Nbt>, Error> {
+        let location = self.locations[chunk_index(x, z)];
+        if location == 0 {
+            return Ok(None);
+        }
+
+        let (offset, sectors) = (location >> 8, location & 0xff);
+        if offset < HEADER_SECTORS {
+            return Err(Error::InvalidRegion(format!(
+                "Chunk at {x}, {z} starts at sector {offset}, inside the header"
+            )));
+        }
+        self.stream
+            .seek(SeekFrom::Start(offset as u64 * SECTOR_SIZE as u64))?;
+
+        let mut header = [0; 5];
+        self.stream.read_exact(&mut header)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let compression_id = header[4];
+        let fits = length
+            .checked_add(4)
+            .is_some_and(|end| end <= sectors as usize * SECTOR_SIZE);
+        if length == 0 || !fits {
+            return Err(Error::InvalidRegion(format!(
+                "Chunk at {x}, {z} has invalid length {length}"
+            )));
+        }
+
+        let data = if compression_id & EXTERNAL_FLAG != 0 {
+            fs::read(self.external_chunk_path(x, z)?)?
+        } else {
+            let mut data = vec![0; length - 1];
+            self.stream.read_exact(&mut data)?;
+            data
+        };
+
+        let compression = compression_from_id(compression_id & !EXTERNAL_FLAG)?;
+        Nbt::read_compressed_with_options(&mut data.as_slice(), compression, &self.read_options)
+            .map(Some)
+    }
+
+    fn external_chunk_path(&self, x: i32, z: i32) -> Result<PathBuf, Error> {
+        let external = self.external.as_ref().ok_or_else(|| {
+            Error::InvalidRegion(format!(
+                "Chunk at {x}, {z} is stored in an external file, but its directory is unknown"
+            ))
+        })?;
+        let chunk_x = external.region_x * 32 + (x & 31);
+        let chunk_z = external.region_z * 32 + (z & 31);
+        Ok(external
+            .directory
+            .join(format!("c.{chunk_x}.{chunk_z}.mcc")))
+    }
+}
+
+impl<S: Read + Write + Seek> RegionFile<S> {
+    /// Creates a new, empty region file, overwriting the header of the stream.
+    pub fn create(mut stream: S) -> Result<Self, Error> {
+        stream.seek(SeekFrom::Start(0))?;
+        stream.write_all(&[0; SECTOR_SIZE * HEADER_SECTORS as usize])?;
+        Ok(RegionFile {
+            stream,
+            locations: vec![0; CHUNK_COUNT],
+            timestamps: vec![0; CHUNK_COUNT],
+            external: None,
+            read_options: ReadOptions::default(),
+        })
+    }
+
+    /// Writes the chunk, replacing the previous one if it exists.
+    /// Chunks are moved to a new place in the file when they no longer fit in their sectors,
+    /// and stored in external files when they are bigger than 1 MiB.
+    pub fn write_chunk(
+        &mut self,
+        x: i32,
+        z: i32,
+        nbt: &Nbt,
+        compression: Compression,
+    ) -> Result<(), Error> {
+        let data = nbt.write_compressed(compression)?;
+        let compression_id = compression_to_id(compression);
+        let index = chunk_index(x, z);
+
+        let mut sectors = (data.len() + 5).div_ceil(SECTOR_SIZE);
+        let external = sectors > MAX_CHUNK_SECTORS;
+        let mut payload = Vec::with_capacity(sectors * SECTOR_SIZE);
+        if external {
+            fs::write(self.external_chunk_path(x, z)?, &data)?;
+            sectors = 1;
+            payload.extend_from_slice(&1u32.to_be_bytes());
+            payload.push(compression_id | EXTERNAL_FLAG);
+        } else {
+            payload.extend_from_slice(&(data.len() as u32 + 1).to_be_bytes());
+            payload.push(compression_id);
+            payload.extend_from_slice(&data);
+            if self.external.is_some() {
+                let path = self.external_chunk_path(x, z)?;
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+        payload.resize(sectors * SECTOR_SIZE, 0);
+
+        let location = self.locations[index];
+        let offset = if location != 0 && (location & 0xff) as usize >= sectors {
+            location >> 8
+        } else {
+            self.allocate(index, sectors as u32)
+        };
+
+        self.stream
+            .seek(SeekFrom::Start(offset as u64 * SECTOR_SIZE as u64))?;
+        self.stream.write_all(&payload)?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as u32)
+            .unwrap_or_default();
+        self.write_header_entry(index, (offset << 8) | sectors as u32, timestamp)
+    }
+
+    /// Removes the chunk from the region. Its sectors will be reused by later writes.
+    pub fn remove_chunk(&mut self, x: i32, z: i32) -> Result<(), Error> {
+        self.write_header_entry(chunk_index(x, z), 0, 0)
+    }
+
+    /// Finds the first free range of sectors big enough to hold the chunk,
+    /// ignoring the sectors currently used by the chunk itself.
+    fn allocate(&self, index: usize, sectors: u32) -> u32 {
+        let mut used: Vec<(u32, u32)> = self
+            .locations
+            .iter()
+            .enumerate()
+            .filter(|(i, location)| *i != index && **location != 0)
+            .map(|(_, location)| (location >> 8, location & 0xff))
+            .collect();
+        used.sort_unstable();
+
+        let mut offset = HEADER_SECTORS;
+        for (start, count) in used {
+            if start >= offset + sectors {
+                break;
+            }
+            offset = offset.max(start + count);
+        }
+        offset
+    }
+
+    fn write_header_entry(
+        &mut self,
+        index: usize,
+        location: u32,
+        timestamp: u32,
+    ) -> Result<(), Error> {
+        self.locations[index] = location;
+        self.timestamps[index] = timestamp;
+
+        self.stream.seek(SeekFrom::Start(index as u64 * 4))?;
+        self.stream.write_all(&location.to_be_bytes())?;
+        self.stream
+            .seek(SeekFrom::Start((SECTOR_SIZE + index * 4) as u64))?;
+        self.stream.write_all(&timestamp.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// Returns the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+fn chunk_index(x: i32, z: i32) -> usize {
+    (x & 31) as usize + (z & 31) as usize * 32
+}
+
+fn read_header_table(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|entry| u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]))
+        .collect()
+}
+
+fn compression_from_id(id: u8) -> Result<Compression, Error> {
+    match id {
+        1 => Ok(Compression::Gzip),
+        2 => Ok(Compression::Zlib),
+        3 => Ok(Compression::None),
+        _ => Err(Error::UnsupportedCompression(id)),
+    }
+}
+
+fn compression_to_id(compression: Compression) -> u8 {
+    match compression {
+        Compression::Gzip => 1,
+        Compression::Zlib => 2,
+        Compression::None => 3,
+    }
+}
@@ -1,51 +1,174 @@
 use crate::error::{Error, Result};
-use crate::nbt::utils::get_nbt_string;
-use crate::nbt::utils::{BYTE_ID, COMPOUND_ID, END_ID, LIST_ID};
-use crate::NbtTag;
+#[cfg(feature = "tokio")]
+use crate::nbt::async_io::read_nbt_bytes;
+use crate::nbt::options::ReadContext;
+use crate::nbt::path::PathSegment;
+use crate::nbt::reader::{NbtInput, ReadInput, Refill};
+use crate::nbt::utils::{
+    ensure_remaining, BYTE_ARRAY_ID, BYTE_ID, COMPOUND_ID, END_ID, INT_ID, LIST_ID, LONG_ID,
+    SHORT_ID, STRING_ID,
+};
+use crate::nbt::view::take_slice;
+use crate::{NbtFlavor, NbtTag, ReadOptions};
 use bytes::Buf;
 use serde::de::value::SeqDeserializer;
-use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::de::DeserializeOwned;
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
 use serde::{forward_to_deserialize_any, Deserialize};
-use std::io::Cursor;
-use std::vec::IntoIter;
+use std::borrow::Cow;
+use std::io::{Cursor, Read};
+use std::mem;
+#[cfg(feature = "tokio")]
+use tokio::io::AsyncRead;
 
 #[derive(Debug)]
 pub struct Deserializer<'de, T: Buf> {
-    input: &'de mut T,
+    input: T,
+    refill: Refill<T>,
+    /// Takes bytes out of inputs which outlive the deserialized value, so they can be borrowed
+    borrow: Option<BorrowFn<'de, T>>,
     tag_to_deserialize: Option<u8>,
     is_named: bool,
+    context: ReadContext,
     // Average serde experience, sometimes when you deserialize a struct in a struct
     // It doesn't call `deserialize_identifier` but `deserialize_string`
     // So we need to know if we are currently deserializing a key or not
     is_deserializing_key: bool,
+    /// Last deserialized key, used to create paths of errors
+    last_key: String,
 }
 
-impl<'de, T: Buf> Deserializer<'de, T> {
+type BorrowFn<'de, T> = fn(&mut T, usize) -> Result<&'de [u8]>;
+
+impl<'de, T: Buf> Deserializer<'de, &'de mut T> {
     pub fn new(input: &'de mut T, is_named: bool) -> Self {
+        Self::with_flavor(input, is_named, NbtFlavor::JavaBE)
+    }
+
+    pub fn with_flavor(input: &'de mut T, is_named: bool, flavor: NbtFlavor) -> Self {
+        Self::with_options(input, is_named, &ReadOptions::default().with_flavor(flavor))
+    }
+
+    /// Creates a deserializer enforcing limits from the given [ReadOptions].
+    pub fn with_options(input: &'de mut T, is_named: bool, options: &ReadOptions) -> Self {
+        Self::from_buf(input, is_named, options)
+    }
+}
+
+impl<'de> Deserializer<'de, &'de [u8]> {
+    /// Creates a deserializer which borrows strings and byte arrays from the input
+    /// when they don't have to be decoded, see [from_slice].
+    pub fn from_slice(input: &'de [u8], is_named: bool, options: &ReadOptions) -> Self {
+        Deserializer {
+            borrow: Some(take_slice),
+            ..Self::from_buf(input, is_named, options)
+        }
+    }
+}
+
+impl<T: Buf> Deserializer<'_, T> {
+    fn from_buf(input: T, is_named: bool, options: &ReadOptions) -> Self {
         Deserializer {
+            context: ReadContext::new(*options, &input),
             input,
+            refill: Refill::none(),
+            borrow: None,
             tag_to_deserialize: None,
             is_named,
             is_deserializing_key: true,
+            last_key: String::new(),
+        }
+    }
+}
+
+impl<T: NbtInput> Deserializer<'_, T> {
+    /// Creates a deserializer for an input which may be read incrementally.
+    fn from_input(input: T, is_named: bool, options: &ReadOptions) -> Self {
+        Deserializer {
+            refill: Refill::for_input(),
+            ..Self::from_buf(input, is_named, options)
         }
     }
 }
 
+/// Deserializes a value, adding the location to errors.
+fn deserialize<'a, T, B>(mut deserializer: Deserializer<'a, B>) -> Result<T>
+where
+    T: Deserialize<'a>,
+    B: Buf,
+{
+    T::deserialize(&mut deserializer)
+        .map_err(|error| deserializer.context.locate(&deserializer.input, error))
+}
+
 /// Deserializes struct using Serde Deserializer from unnamed (network) NBT
 pub fn from_bytes<'a, T>(s: &'a mut impl Buf) -> Result<T>
 where
     T: Deserialize<'a>,
 {
-    let mut deserializer = Deserializer::new(s, true);
-    T::deserialize(&mut deserializer)
+    deserialize(Deserializer::new(s, true))
+}
+
+pub fn from_bytes_with_flavor<'a, T>(s: &'a mut impl Buf, flavor: NbtFlavor) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    deserialize(Deserializer::with_flavor(s, true, flavor))
+}
+
+pub fn from_bytes_with_options<'a, T>(s: &'a mut impl Buf, options: &ReadOptions) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    deserialize(Deserializer::with_options(s, true, options))
+}
+
+/// Deserializes struct from normal NBT, borrowing strings and byte arrays from the slice,
+/// so that types like `&'de str` and `&'de [u8]` can be deserialized without copying.
+///
+/// Strings of Java Edition are borrowed only when their modified UTF-8 is regular UTF-8 too,
+/// which isn't the case for strings containing null characters or characters outside the BMP.
+pub fn from_slice<'de, T>(s: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    from_slice_with_options(s, &ReadOptions::default())
+}
+
+pub fn from_slice_with_options<'de, T>(s: &'de [u8], options: &ReadOptions) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    deserialize(Deserializer::from_slice(s, true, options))
+}
+
+/// Deserializes struct from normal NBT, pulling the data from a reader as it's needed.
+/// See [Nbt::from_reader](crate::Nbt::from_reader).
+pub fn from_reader<T>(reader: impl Read) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    from_reader_with_options(reader, &ReadOptions::default())
+}
+
+pub fn from_reader_with_options<T>(reader: impl Read, options: &ReadOptions) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    deserialize(Deserializer::from_input(
+        ReadInput::new(reader),
+        true,
+        options,
+    ))
 }
 
 pub fn from_cursor<'a, T>(cursor: &'a mut Cursor<&[u8]>) -> Result<T>
 where
     T: Deserialize<'a>,
 {
-    let mut deserializer = Deserializer::new(cursor, true);
-    T::deserialize(&mut deserializer)
+    deserialize(Deserializer::new(cursor, true))
 }
 
 /// Deserializes struct using Serde Deserializer from normal NBT
@@ -53,84 +176,195 @@ pub fn from_bytes_unnamed<'a, T>(s: &'a mut impl Buf) -> Result<T>
 where
     T: Deserialize<'a>,
 {
-    let mut deserializer = Deserializer::new(s, false);
-    T::deserialize(&mut deserializer)
+    deserialize(Deserializer::new(s, false))
+}
+
+pub fn from_bytes_unnamed_with_flavor<'a, T>(s: &'a mut impl Buf, flavor: NbtFlavor) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    deserialize(Deserializer::with_flavor(s, false, flavor))
+}
+
+pub fn from_bytes_unnamed_with_options<'a, T>(
+    s: &'a mut impl Buf,
+    options: &ReadOptions,
+) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    deserialize(Deserializer::with_options(s, false, options))
+}
+
+/// Like [from_slice], but for NBT that doesn't contain the name of the root compound.
+pub fn from_slice_unnamed<'de, T>(s: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    from_slice_unnamed_with_options(s, &ReadOptions::default())
+}
+
+pub fn from_slice_unnamed_with_options<'de, T>(s: &'de [u8], options: &ReadOptions) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    deserialize(Deserializer::from_slice(s, false, options))
+}
+
+/// Like [from_reader], but for NBT that doesn't contain the name of the root compound.
+pub fn from_reader_unnamed<T>(reader: impl Read) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    from_reader_unnamed_with_options(reader, &ReadOptions::default())
+}
+
+pub fn from_reader_unnamed_with_options<T>(reader: impl Read, options: &ReadOptions) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    deserialize(Deserializer::from_input(
+        ReadInput::new(reader),
+        false,
+        options,
+    ))
+}
+
+/// Deserializes struct from normal NBT read from an [AsyncRead]er.
+/// See [Nbt::read_async](crate::Nbt::read_async).
+#[cfg(feature = "tokio")]
+pub async fn from_async_reader<T>(reader: impl AsyncRead + Unpin) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    from_async_reader_with_options(reader, &ReadOptions::default()).await
+}
+
+#[cfg(feature = "tokio")]
+pub async fn from_async_reader_with_options<T>(
+    reader: impl AsyncRead + Unpin,
+    options: &ReadOptions,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let bytes = read_nbt_bytes(reader, options, true).await?;
+    from_bytes_with_options(&mut bytes.as_slice(), options)
+}
+
+/// Like [from_async_reader], but for NBT that doesn't contain the name of the root compound.
+#[cfg(feature = "tokio")]
+pub async fn from_async_reader_unnamed<T>(reader: impl AsyncRead + Unpin) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    from_async_reader_unnamed_with_options(reader, &ReadOptions::default()).await
+}
+
+#[cfg(feature = "tokio")]
+pub async fn from_async_reader_unnamed_with_options<T>(
+    reader: impl AsyncRead + Unpin,
+    options: &ReadOptions,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let bytes = read_nbt_bytes(reader, options, false).await?;
+    from_bytes_unnamed_with_options(&mut bytes.as_slice(), options)
 }
 
 pub fn from_cursor_unnamed<'a, T>(cursor: &'a mut Cursor<&[u8]>) -> Result<T>
 where
     T: Deserialize<'a>,
 {
-    let mut deserializer = Deserializer::new(cursor, false);
-    T::deserialize(&mut deserializer)
+    deserialize(Deserializer::new(cursor, false))
+}
+
+/// Deserializes an unsigned integer, reinterpreting the bits of the signed tag of the same width.
+/// Values of other tags, like the wider ones, are checked by the visitor.
+macro_rules! deserialize_unsigned {
+    ($method:ident, $visit:ident, $tag_id:ident, $variant:ident, $unsigned:ty) => {
+        fn $method<V>(self, visitor: V) -> Result<V::Value>
+        where
+            V: Visitor<'de>,
+        {
+            if self.is_deserializing_key || self.tag_to_deserialize != Some($tag_id) {
+                return self.deserialize_any(visitor);
+            }
+            match self.read_scalar($tag_id)? {
+                NbtTag::$variant(value) => visitor.$visit(value as $unsigned),
+                tag => unreachable!("{:?} should be handled differently", tag),
+            }
+        }
+    };
 }
 
 impl<'de, T: Buf> de::Deserializer<'de> for &mut Deserializer<'de, T> {
     type Error = Error;
 
-    forward_to_deserialize_any!(i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 seq char str string bytes byte_buf tuple tuple_struct enum ignored_any unit unit_struct newtype_struct);
+    deserialize_unsigned!(deserialize_u8, visit_u8, BYTE_ID, Byte, u8);
+    deserialize_unsigned!(deserialize_u16, visit_u16, SHORT_ID, Short, u16);
+    deserialize_unsigned!(deserialize_u32, visit_u32, INT_ID, Int, u32);
+    deserialize_unsigned!(deserialize_u64, visit_u64, LONG_ID, Long, u64);
+
+    forward_to_deserialize_any!(i8 i16 i32 i64 f32 f64 seq char str string ignored_any unit unit_struct);
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        // Used when buffering the root compound, for example by internally tagged enums
+        if self.tag_to_deserialize.is_none() {
+            return self.deserialize_map(visitor);
+        }
+
         // Sometimes `deserialize_string` is called instead of `deserialize_identifier`
         if self.is_deserializing_key {
             return self.deserialize_identifier(visitor);
         }
 
-        let tag_to_deserialize = self.tag_to_deserialize.unwrap();
+        let tag_to_deserialize = self.current_tag()?;
         match tag_to_deserialize {
-            LIST_ID => {
-                let list_type = self.input.try_get_u8()?;
-                let remaining_values = self.input.try_get_u32()?;
-                return visitor.visit_seq(ListAccess {
-                    de: self,
-                    list_type,
-                    remaining_values,
-                });
+            STRING_ID => {
+                let string = self.read_string()?;
+                self.tag_to_deserialize = None;
+                return match string {
+                    Cow::Borrowed(string) => visitor.visit_borrowed_str(string),
+                    Cow::Owned(string) => visitor.visit_string(string),
+                };
             }
+            LIST_ID => return self.deserialize_list(visitor, false),
             COMPOUND_ID => return self.deserialize_map(visitor),
             _ => {}
         };
 
-        let result: Result<V::Value> = Ok(
-            match NbtTag::deserialize_data(self.input, tag_to_deserialize)? {
-                NbtTag::Byte(value) => visitor.visit_i8::<Error>(value)?,
-                NbtTag::Short(value) => visitor.visit_i16::<Error>(value)?,
-                NbtTag::Int(value) => visitor.visit_i32::<Error>(value)?,
-                NbtTag::Long(value) => visitor.visit_i64::<Error>(value)?,
-                NbtTag::Float(value) => visitor.visit_f32::<Error>(value)?,
-                NbtTag::Double(value) => visitor.visit_f64::<Error>(value)?,
-                NbtTag::String(value) => visitor.visit_string::<Error>(value)?,
-                NbtTag::LongArray(value) => visitor
-                    .visit_seq::<SeqDeserializer<IntoIter<i64>, Error>>(
-                        value.into_deserializer(),
-                    )?,
-                NbtTag::IntArray(value) => visitor
-                    .visit_seq::<SeqDeserializer<IntoIter<i32>, Error>>(
-                        value.into_deserializer(),
-                    )?,
-                NbtTag::ByteArray(value) => {
-                    // For compatibility, we serialize byte arrays as Vec<i8>
-                    // It could be probably changed in the future
-                    let array: Vec<_> = value.iter().map(|&byte| byte as i8).collect();
-                    visitor.visit_seq::<SeqDeserializer<IntoIter<i8>, Error>>(
-                        array.into_deserializer(),
-                    )?
-                }
-                tag => unreachable!("{:?} should be handled differently", tag),
-            },
-        );
-        self.tag_to_deserialize = None;
-        result
+        Ok(match self.read_scalar(tag_to_deserialize)? {
+            NbtTag::Byte(value) => visitor.visit_i8::<Error>(value)?,
+            NbtTag::Short(value) => visitor.visit_i16::<Error>(value)?,
+            NbtTag::Int(value) => visitor.visit_i32::<Error>(value)?,
+            NbtTag::Long(value) => visitor.visit_i64::<Error>(value)?,
+            NbtTag::Float(value) => visitor.visit_f32::<Error>(value)?,
+            NbtTag::Double(value) => visitor.visit_f64::<Error>(value)?,
+            NbtTag::String(value) => visitor.visit_string::<Error>(value)?,
+            NbtTag::LongArray(value) => visitor.visit_seq(array_access(value))?,
+            NbtTag::IntArray(value) => visitor.visit_seq(array_access(value))?,
+            NbtTag::ByteArray(value) => {
+                // For compatibility, we serialize byte arrays as Vec<i8>
+                // It could be probably changed in the future
+                let array: Vec<_> = value.iter().map(|&byte| byte as i8).collect();
+                visitor.visit_seq(array_access(array))?
+            }
+            NbtTag::End => visitor.visit_unit::<Error>()?,
+            tag => unreachable!("{:?} should be handled differently", tag),
+        })
     }
 
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        if self.tag_to_deserialize.unwrap() == BYTE_ID {
+        if self.current_tag()? == BYTE_ID {
+            self.refill.fill(&mut self.input, &mut self.context, 1)?;
             let value = self.input.try_get_u8()?;
             if value != 0 {
                 return visitor.visit_bool(true);
@@ -139,6 +373,36 @@ impl<'de, T: Buf> de::Deserializer<'de> for &mut Deserializer<'de, T> {
         visitor.visit_bool(false)
     }
 
+    /// Byte arrays are borrowed when deserializing [from_slice].
+    /// Other tags are deserialized like by [deserialize_any](Self::deserialize_any).
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.is_deserializing_key || self.tag_to_deserialize != Some(BYTE_ARRAY_ID) {
+            return self.deserialize_any(visitor);
+        }
+        self.refill
+            .fill_payload(&mut self.input, &mut self.context, BYTE_ARRAY_ID)?;
+        self.tag_to_deserialize = None;
+        match self.borrow {
+            Some(borrow) => {
+                let len = self.context.get_len(&mut self.input, 1)?;
+                visitor.visit_borrowed_bytes(borrow(&mut self.input, len)?)
+            }
+            None => visitor.visit_bytes(&self.context.get_byte_array(&mut self.input)?),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    // NBT has no null value, so every present tag is `Some`.
+    // `None` fields are omitted, and serde deserializes missing keys of options as `None`
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
@@ -151,20 +415,86 @@ impl<'de, T: Buf> de::Deserializer<'de> for &mut Deserializer<'de, T> {
         V: Visitor<'de>,
     {
         if self.tag_to_deserialize.is_none() {
-            let next_byte = self.input.try_get_u8()?;
-            if next_byte != COMPOUND_ID {
-                return Err(Error::NoRootCompound(next_byte));
+            self.read_root_header()?;
+        }
+
+        self.nested(|de| {
+            visitor.visit_map(CompoundAccess {
+                de,
+                key: String::new(),
+            })
+        })
+    }
+
+    /// Enums are deserialized from the name of a unit variant, or from a compound
+    /// with a single key, which is the name of the variant containing data.
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let is_key = self.is_deserializing_key && self.tag_to_deserialize.is_some();
+        if is_key || self.tag_to_deserialize == Some(STRING_ID) {
+            self.refill
+                .fill_string(&mut self.input, &mut self.context)?;
+            let variant = self.context.get_string(&mut self.input)?;
+            if is_key {
+                self.last_key.clone_from(&variant);
             }
+            self.tag_to_deserialize = None;
+            return visitor.visit_enum(variant.into_deserializer());
+        }
 
-            if self.is_named {
-                // Compound name is never used, so we can skip it
-                let length = self.input.try_get_u16()? as usize;
-                self.input.advance(length);
+        match self.tag_to_deserialize {
+            None => self.read_root_header()?,
+            Some(COMPOUND_ID) => {}
+            Some(tag) => {
+                return Err(Error::SerdeError(format!(
+                    "Enum can't be deserialized from a tag with id {tag}"
+                )))
             }
         }
+        self.nested(|de| {
+            visitor.visit_enum(VariantAccess {
+                de,
+                key: String::new(),
+            })
+        })
+    }
 
-        let value = visitor.visit_map(CompoundAccess { de: self })?;
-        Ok(value)
+    // Tuples with elements of different types are stored as lists of wrapped elements
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.is_deserializing_key || self.tag_to_deserialize != Some(LIST_ID) {
+            return self.deserialize_any(visitor);
+        }
+        self.deserialize_list(visitor, true)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    // Newtypes are transparent
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
     }
 
     fn deserialize_struct<V>(
@@ -183,8 +513,13 @@ impl<'de, T: Buf> de::Deserializer<'de> for &mut Deserializer<'de, T> {
     where
         V: Visitor<'de>,
     {
-        let str = get_nbt_string(&mut self.input)?;
-        visitor.visit_string(str)
+        let key = self.read_string()?;
+        let value = match key {
+            Cow::Borrowed(key) => visitor.visit_borrowed_str(key),
+            Cow::Owned(ref key) => visitor.visit_str(key),
+        };
+        self.last_key = key.into_owned();
+        value
     }
 
     fn is_human_readable(&self) -> bool {
@@ -192,8 +527,152 @@ impl<'de, T: Buf> de::Deserializer<'de> for &mut Deserializer<'de, T> {
     }
 }
 
+impl<'de, T: Buf> Deserializer<'de, T> {
+    /// Reads a string, borrowing it from the input when possible.
+    fn read_string(&mut self) -> Result<Cow<'de, str>> {
+        self.refill
+            .fill_string(&mut self.input, &mut self.context)?;
+        let Some(borrow) = self.borrow else {
+            return self.context.get_string(&mut self.input).map(Cow::Owned);
+        };
+        let len = self.context.flavor().get_string_len(&mut self.input)?;
+        self.context.reserve(&self.input, len)?;
+        self.context
+            .flavor()
+            .decode_str(borrow(&mut self.input, len)?)
+    }
+
+    /// Reads the payload of a tag which can't contain other tags.
+    fn read_scalar(&mut self, tag_id: u8) -> Result<NbtTag> {
+        self.refill
+            .fill_payload(&mut self.input, &mut self.context, tag_id)?;
+        let tag = NbtTag::read_data(&mut self.input, tag_id, &mut self.context)?;
+        self.tag_to_deserialize = None;
+        Ok(tag)
+    }
+
+    fn read_root_header(&mut self) -> Result<()> {
+        self.refill.fill(&mut self.input, &mut self.context, 1)?;
+        let next_byte = self.input.try_get_u8()?;
+        if next_byte != COMPOUND_ID {
+            return Err(Error::NoRootCompound(next_byte));
+        }
+
+        if self.is_named {
+            // Compound name is never used, so we can skip it
+            self.refill
+                .fill_string(&mut self.input, &mut self.context)?;
+            let length = self.context.flavor().get_string_len(&mut self.input)?;
+            self.context.reserve(&self.input, length)?;
+            ensure_remaining(&self.input, length)?;
+            self.input.advance(length);
+        }
+        Ok(())
+    }
+
+    fn deserialize_list<V>(&mut self, visitor: V, unwraps_elements: bool) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.refill
+            .fill_list_header(&mut self.input, &mut self.context)?;
+        let (list_type, remaining_values) = self.context.get_list_header(&mut self.input)?;
+        self.nested(|de| {
+            visitor.visit_seq(ListAccess {
+                de,
+                list_type,
+                remaining_values,
+                index: 0,
+                unwraps_elements: unwraps_elements && list_type == COMPOUND_ID,
+            })
+        })
+    }
+
+    /// Reads the header of a compound wrapping an element of a list with elements
+    /// of different types, returning the id of the wrapped tag.
+    /// Nothing is read if the compound isn't a wrapper, whose only key is empty.
+    fn read_wrapper_header(&mut self) -> Result<Option<u8>> {
+        let name_len_size = match self.context.flavor() {
+            NbtFlavor::JavaBE | NbtFlavor::BedrockLE => 2,
+            NbtFlavor::BedrockNetwork => 1,
+        };
+        self.refill
+            .fill(&mut self.input, &mut self.context, 1 + name_len_size)?;
+        match self.input.chunk().get(..1 + name_len_size) {
+            Some([tag, name_len @ ..]) if *tag != END_ID && name_len.iter().all(|b| *b == 0) => {
+                let tag = *tag;
+                self.input.advance(1 + name_len_size);
+                Ok(Some(tag))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Id of the tag that is being deserialized.
+    /// Only compounds can be deserialized at the root, where there is no such tag.
+    fn current_tag(&self) -> Result<u8> {
+        self.tag_to_deserialize.ok_or_else(|| {
+            Error::SerdeError("Only compounds can be deserialized at the root".to_owned())
+        })
+    }
+
+    /// Like [ReadContext::nested], but giving access to the whole deserializer.
+    fn nested<R>(&mut self, read: impl FnOnce(&mut Self) -> Result<R>) -> Result<R> {
+        self.context.enter()?;
+        let result = read(self);
+        self.context.exit();
+        result
+    }
+}
+
+/// Element of an array, which can be deserialized into an unsigned integer of the same width too.
+struct ArrayElement<N>(N);
+
+fn array_access<N>(array: Vec<N>) -> SeqDeserializer<impl Iterator<Item = ArrayElement<N>>, Error> {
+    SeqDeserializer::new(array.into_iter().map(ArrayElement))
+}
+
+macro_rules! array_element {
+    ($signed:ty, $visit_signed:ident, $method:ident, $visit_unsigned:ident, $unsigned:ty, [$($forward:ident)*]) => {
+        impl<'de> de::Deserializer<'de> for ArrayElement<$signed> {
+            type Error = Error;
+
+            forward_to_deserialize_any!($($forward)* bool i8 i16 i32 i64 i128 u128 f32 f64 char str
+                string bytes byte_buf option unit unit_struct newtype_struct seq tuple
+                tuple_struct map struct enum identifier ignored_any);
+
+            fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                visitor.$visit_signed(self.0)
+            }
+
+            fn $method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                visitor.$visit_unsigned(self.0 as $unsigned)
+            }
+        }
+
+        impl IntoDeserializer<'_, Error> for ArrayElement<$signed> {
+            type Deserializer = Self;
+
+            fn into_deserializer(self) -> Self {
+                self
+            }
+        }
+    };
+}
+
+array_element!(i8, visit_i8, deserialize_u8, visit_u8, u8, [u16 u32 u64]);
+array_element!(i32, visit_i32, deserialize_u32, visit_u32, u32, [u8 u16 u64]);
+array_element!(i64, visit_i64, deserialize_u64, visit_u64, u64, [u8 u16 u32]);
+
 struct CompoundAccess<'a, 'de: 'a, T: Buf> {
     de: &'a mut Deserializer<'de, T>,
+    key: String,
 }
 
 impl<'de, T: Buf> MapAccess<'de> for CompoundAccess<'_, 'de, T> {
@@ -203,7 +682,11 @@ impl<'de, T: Buf> MapAccess<'de> for CompoundAccess<'_, 'de, T> {
     where
         K: DeserializeSeed<'de>,
     {
+        self.de
+            .refill
+            .fill(&mut self.de.input, &mut self.de.context, 1)?;
         let tag = self.de.input.try_get_u8()?;
+        self.de.context.reserve(&self.de.input, 0)?;
         self.de.tag_to_deserialize = Some(tag);
 
         if tag == END_ID {
@@ -211,7 +694,9 @@ impl<'de, T: Buf> MapAccess<'de> for CompoundAccess<'_, 'de, T> {
         }
 
         self.de.is_deserializing_key = true;
-        seed.deserialize(&mut *self.de).map(Some)
+        let key = seed.deserialize(&mut *self.de)?;
+        self.key = mem::take(&mut self.de.last_key);
+        Ok(Some(key))
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
@@ -219,14 +704,22 @@ impl<'de, T: Buf> MapAccess<'de> for CompoundAccess<'_, 'de, T> {
         V: DeserializeSeed<'de>,
     {
         self.de.is_deserializing_key = false;
-        seed.deserialize(&mut *self.de)
+        seed.deserialize(&mut *self.de).map_err(|error| {
+            let key = mem::take(&mut self.key);
+            self.de
+                .context
+                .locate_in(&self.de.input, error, PathSegment::Key(key))
+        })
     }
 }
 
 struct ListAccess<'a, 'de: 'a, T: Buf> {
     de: &'a mut Deserializer<'de, T>,
-    remaining_values: u32,
+    remaining_values: usize,
     list_type: u8,
+    index: usize,
+    /// Whether compounds wrapping elements of different types are unwrapped
+    unwraps_elements: bool,
 }
 
 impl<'de, T: Buf> SeqAccess<'de> for ListAccess<'_, 'de, T> {
@@ -241,11 +734,125 @@ impl<'de, T: Buf> SeqAccess<'de> for ListAccess<'_, 'de, T> {
         }
 
         self.remaining_values -= 1;
-        self.de.tag_to_deserialize = Some(self.list_type);
-        seed.deserialize(&mut *self.de).map(Some)
+        let index = self.index;
+        self.index += 1;
+        let wrapped_tag = if self.unwraps_elements {
+            self.de.read_wrapper_header()?
+        } else {
+            None
+        };
+        let Some(wrapped_tag) = wrapped_tag else {
+            self.de.tag_to_deserialize = Some(self.list_type);
+            return seed.deserialize(&mut *self.de).map(Some).map_err(|error| {
+                self.de
+                    .context
+                    .locate_in(&self.de.input, error, PathSegment::Index(index))
+            });
+        };
+
+        self.de.tag_to_deserialize = Some(wrapped_tag);
+        let value = self.de.nested(|de| {
+            let value = seed.deserialize(&mut *de)?;
+            de.refill.fill(&mut de.input, &mut de.context, 1)?;
+            if de.input.try_get_u8()? != END_ID {
+                return Err(Error::SerdeError(
+                    "Compound wrapping a list element has to contain only one key".to_owned(),
+                ));
+            }
+            Ok(value)
+        });
+        value.map(Some).map_err(|error| {
+            self.de
+                .context
+                .locate_in(&self.de.input, error, PathSegment::Index(index))
+        })
     }
 
     fn size_hint(&self) -> Option<usize> {
-        Some(self.remaining_values as usize)
+        Some(self.remaining_values)
+    }
+}
+
+/// Compound containing a single enum variant.
+struct VariantAccess<'a, 'de: 'a, T: Buf> {
+    de: &'a mut Deserializer<'de, T>,
+    /// Name of the variant, used to create paths of errors
+    key: String,
+}
+
+impl<'de, T: Buf> EnumAccess<'de> for VariantAccess<'_, 'de, T> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(mut self, seed: V) -> Result<(V::Value, Self)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.de
+            .refill
+            .fill(&mut self.de.input, &mut self.de.context, 1)?;
+        let tag = self.de.input.try_get_u8()?;
+        if tag == END_ID {
+            return Err(Error::SerdeError(
+                "Compound of an enum variant is empty".to_owned(),
+            ));
+        }
+        self.de.tag_to_deserialize = Some(tag);
+
+        self.de.is_deserializing_key = true;
+        let variant = seed.deserialize(&mut *self.de)?;
+        self.de.is_deserializing_key = false;
+        self.key = mem::take(&mut self.de.last_key);
+        Ok((variant, self))
+    }
+}
+
+impl<'de, T: Buf> de::VariantAccess<'de> for VariantAccess<'_, 'de, T> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Err(Error::SerdeError(
+            "Unit variants have to be deserialized from strings".to_owned(),
+        ))
+    }
+
+    fn newtype_variant_seed<S>(self, seed: S) -> Result<S::Value>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        self.read_value(|de| seed.deserialize(de))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.read_value(|de| de::Deserializer::deserialize_any(de, visitor))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.read_value(|de| de::Deserializer::deserialize_any(de, visitor))
+    }
+}
+
+impl<'de, T: Buf> VariantAccess<'_, 'de, T> {
+    /// Reads the content of the variant, followed by the end of the compound.
+    fn read_value<R>(self, read: impl FnOnce(&mut Deserializer<'de, T>) -> Result<R>) -> Result<R> {
+        let de = self.de;
+        let value = read(&mut *de).map_err(|error| {
+            de.context
+                .locate_in(&de.input, error, PathSegment::Key(self.key))
+        })?;
+
+        de.refill.fill(&mut de.input, &mut de.context, 1)?;
+        if de.input.try_get_u8()? != END_ID {
+            return Err(Error::SerdeError(
+                "Compound of an enum variant has to contain only one key".to_owned(),
+            ));
+        }
+        Ok(value)
     }
 }
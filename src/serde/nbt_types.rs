@@ -1,4 +1,4 @@
-use crate::{NbtCompound, NbtTag};
+use crate::{NbtCompound, NbtList, NbtTag, NbtTagView};
 use serde::de::value::MapAccessDeserializer;
 use serde::{Deserialize, Serialize};
 
@@ -7,15 +7,24 @@ impl Serialize for NbtTag {
     where
         S: serde::Serializer,
     {
-        match self {
-            NbtTag::End => serializer.serialize_unit(),
-            NbtTag::Byte(byte_val) => serializer.serialize_i8(*byte_val),
-            NbtTag::Short(short_val) => serializer.serialize_i16(*short_val),
-            NbtTag::Int(int_val) => serializer.serialize_i32(*int_val),
-            NbtTag::Long(long_val) => serializer.serialize_i64(*long_val),
-            NbtTag::Float(float_val) => serializer.serialize_f32(*float_val),
-            NbtTag::Double(double_val) => serializer.serialize_f64(*double_val),
-            NbtTag::ByteArray(byte_array) => {
+        NbtTagView::from(self).serialize(serializer)
+    }
+}
+
+impl Serialize for NbtTagView<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match *self {
+            NbtTagView::End => serializer.serialize_unit(),
+            NbtTagView::Byte(byte_val) => serializer.serialize_i8(byte_val),
+            NbtTagView::Short(short_val) => serializer.serialize_i16(short_val),
+            NbtTagView::Int(int_val) => serializer.serialize_i32(int_val),
+            NbtTagView::Long(long_val) => serializer.serialize_i64(long_val),
+            NbtTagView::Float(float_val) => serializer.serialize_f32(float_val),
+            NbtTagView::Double(double_val) => serializer.serialize_f64(double_val),
+            NbtTagView::ByteArray(byte_array) => {
                 use serde::ser::SerializeSeq;
                 let mut seq = serializer.serialize_seq(Some(byte_array.len()))?;
                 for byte in byte_array.iter() {
@@ -23,17 +32,10 @@ impl Serialize for NbtTag {
                 }
                 seq.end()
             }
-            NbtTag::String(string_val) => serializer.serialize_str(string_val),
-            NbtTag::List(list_items) => {
-                use serde::ser::SerializeSeq;
-                let mut seq = serializer.serialize_seq(Some(list_items.len()))?;
-                for item in list_items.iter() {
-                    seq.serialize_element(item)?;
-                }
-                seq.end()
-            }
-            NbtTag::Compound(compound) => compound.serialize(serializer),
-            NbtTag::IntArray(int_array) => {
+            NbtTagView::String(string_val) => serializer.serialize_str(string_val),
+            NbtTagView::List(list) => list.serialize(serializer),
+            NbtTagView::Compound(compound) => compound.serialize(serializer),
+            NbtTagView::IntArray(int_array) => {
                 use serde::ser::SerializeSeq;
                 let mut sequence = serializer.serialize_seq(Some(int_array.len()))?;
                 for int in int_array.iter() {
@@ -41,7 +43,7 @@ impl Serialize for NbtTag {
                 }
                 sequence.end()
             }
-            NbtTag::LongArray(long_array) => {
+            NbtTagView::LongArray(long_array) => {
                 use serde::ser::SerializeSeq;
                 let mut sequence = serializer.serialize_seq(Some(long_array.len()))?;
                 for long in long_array.iter() {
@@ -53,6 +55,20 @@ impl Serialize for NbtTag {
     }
 }
 
+impl Serialize for NbtList {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for item in self {
+            seq.serialize_element(&item)?;
+        }
+        seq.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for NbtTag {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -110,7 +126,8 @@ impl<'de> Deserialize<'de> for NbtTag {
                 while let Some(tag) = seq.next_element()? {
                     items.push(tag);
                 }
-                Ok(NbtTag::List(items))
+                let list = NbtList::try_from_tags(items).map_err(serde::de::Error::custom)?;
+                Ok(NbtTag::List(list))
             }
 
             fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
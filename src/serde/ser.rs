@@ -1,15 +1,33 @@
 use crate::error::Error::UnsupportedType;
 use crate::error::{Error, Result};
 use crate::nbt::utils::*;
+use crate::NbtFlavor;
 use bytes::{BufMut, BytesMut};
 use crab_nbt::nbt::utils::END_ID;
-use serde::ser::Impossible;
 use serde::{ser, Serialize};
 use std::io::Write;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 pub struct Serializer {
     output: BytesMut,
     state: State,
+    flavor: NbtFlavor,
+    unsigned_policy: UnsignedPolicy,
+}
+
+/// How unsigned integers are serialized, since NBT only has signed ones.
+/// Deserializer accepts values written with any of the policies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum UnsignedPolicy {
+    /// Unsigned integers can't be serialized.
+    #[default]
+    Error,
+    /// Bits are stored in the signed tag of the same width, so `u32::MAX` becomes an int with value -1.
+    Reinterpret,
+    /// Values are stored in the next wider signed tag, for example `u8` as a short.
+    /// `u64` values which don't fit in a long can't be serialized.
+    Widen,
 }
 
 // NBT uses a different order of tag type, tag name and value than most formats.
@@ -23,19 +41,43 @@ enum State {
     MapKey,
     FirstListElement { len: i32 },
     ListElement,
+    // Elements of tuples are buffered with their tag id, but without a name
+    TupleElement,
     Array { name: String, array_type: String },
 }
 
 impl Serializer {
+    /// Creates a serializer of NBT with the given root name, or of unnamed NBT.
+    pub fn new(root_name: Option<String>, flavor: NbtFlavor) -> Self {
+        Serializer {
+            output: BytesMut::new(),
+            state: State::Root(root_name),
+            flavor,
+            unsigned_policy: UnsignedPolicy::default(),
+        }
+    }
+
+    pub fn with_unsigned_policy(self, unsigned_policy: UnsignedPolicy) -> Self {
+        Serializer {
+            unsigned_policy,
+            ..self
+        }
+    }
+
+    /// Returns the serialized data.
+    pub fn into_inner(self) -> BytesMut {
+        self.output
+    }
+
     fn parse_state(&mut self, tag: u8) -> Result<()> {
         match &mut self.state {
             State::Named(name) | State::Array { name, .. } => {
                 self.output.put_u8(tag);
-                serialize_str_into(name, &mut self.output);
+                self.flavor.put_string(&mut self.output, name);
             }
             State::FirstListElement { len } => {
                 self.output.put_u8(tag);
-                self.output.put_i32(*len);
+                self.flavor.put_i32(&mut self.output, *len);
             }
             State::MapKey => {
                 if tag != STRING_ID {
@@ -45,6 +87,7 @@ impl Serializer {
                 }
             }
             State::ListElement => {}
+            State::TupleElement => self.output.put_u8(tag),
             _ => return Err(Error::SerdeError("Invalid Serializer state!".to_string())),
         };
         Ok(())
@@ -56,10 +99,14 @@ pub fn to_bytes_unnamed<T>(value: &T) -> Result<BytesMut>
 where
     T: Serialize,
 {
-    let mut serializer = Serializer {
-        output: BytesMut::new(),
-        state: State::Root(None),
-    };
+    to_bytes_unnamed_with_flavor(value, NbtFlavor::JavaBE)
+}
+
+pub fn to_bytes_unnamed_with_flavor<T>(value: &T, flavor: NbtFlavor) -> Result<BytesMut>
+where
+    T: Serialize,
+{
+    let mut serializer = Serializer::new(None, flavor);
     value.serialize(&mut serializer)?;
     Ok(serializer.output)
 }
@@ -78,10 +125,14 @@ pub fn to_bytes<T>(value: &T, name: String) -> Result<BytesMut>
 where
     T: Serialize,
 {
-    let mut serializer = Serializer {
-        output: BytesMut::new(),
-        state: State::Root(Some(name)),
-    };
+    to_bytes_with_flavor(value, name, NbtFlavor::JavaBE)
+}
+
+pub fn to_bytes_with_flavor<T>(value: &T, name: String, flavor: NbtFlavor) -> Result<BytesMut>
+where
+    T: Serialize,
+{
+    let mut serializer = Serializer::new(Some(name), flavor);
     value.serialize(&mut serializer)?;
     Ok(serializer.output)
 }
@@ -95,17 +146,37 @@ where
     Ok(())
 }
 
-impl ser::Serializer for &mut Serializer {
+#[cfg(feature = "tokio")]
+pub async fn to_async_writer<T, W>(value: &T, name: String, mut writer: W) -> Result<()>
+where
+    T: Serialize,
+    W: AsyncWrite + Unpin,
+{
+    writer.write_all(&to_bytes(value, name)?).await?;
+    Ok(())
+}
+
+#[cfg(feature = "tokio")]
+pub async fn to_async_writer_unnamed<T, W>(value: &T, mut writer: W) -> Result<()>
+where
+    T: Serialize,
+    W: AsyncWrite + Unpin,
+{
+    writer.write_all(&to_bytes_unnamed(value)?).await?;
+    Ok(())
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
     type Ok = ();
     type Error = Error;
 
     type SerializeSeq = Self;
-    type SerializeTuple = Impossible<(), Error>;
-    type SerializeTupleStruct = Impossible<(), Error>;
-    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeTuple = TupleSerializer<'a>;
+    type SerializeTupleStruct = TupleSerializer<'a>;
+    type SerializeTupleVariant = TupleSerializer<'a>;
     type SerializeMap = Self;
     type SerializeStruct = Self;
-    type SerializeStructVariant = Impossible<(), Error>;
+    type SerializeStructVariant = Self;
 
     // NBT doesn't have a bool type, but it's most commonly represented as a byte
     fn serialize_bool(self, v: bool) -> Result<()> {
@@ -121,52 +192,73 @@ impl ser::Serializer for &mut Serializer {
 
     fn serialize_i16(self, v: i16) -> Result<()> {
         self.parse_state(SHORT_ID)?;
-        self.output.put_i16(v);
+        self.flavor.put_i16(&mut self.output, v);
         Ok(())
     }
 
     fn serialize_i32(self, v: i32) -> Result<()> {
         self.parse_state(INT_ID)?;
-        self.output.put_i32(v);
+        self.flavor.put_i32(&mut self.output, v);
         Ok(())
     }
 
     fn serialize_i64(self, v: i64) -> Result<()> {
         self.parse_state(LONG_ID)?;
-        self.output.put_i64(v);
+        self.flavor.put_i64(&mut self.output, v);
         Ok(())
     }
 
-    fn serialize_u8(self, _v: u8) -> Result<()> {
-        Err(UnsupportedType("u8".to_string()))
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        match self.unsigned_policy {
+            UnsignedPolicy::Error => Err(UnsupportedType("u8".to_string())),
+            UnsignedPolicy::Reinterpret => self.serialize_i8(v as i8),
+            UnsignedPolicy::Widen => self.serialize_i16(v.into()),
+        }
     }
 
-    fn serialize_u16(self, _v: u16) -> Result<()> {
-        Err(UnsupportedType("u16".to_string()))
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        match self.unsigned_policy {
+            UnsignedPolicy::Error => Err(UnsupportedType("u16".to_string())),
+            UnsignedPolicy::Reinterpret => self.serialize_i16(v as i16),
+            UnsignedPolicy::Widen => self.serialize_i32(v.into()),
+        }
     }
 
-    fn serialize_u32(self, _v: u32) -> Result<()> {
-        Err(UnsupportedType("u32".to_string()))
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        match self.unsigned_policy {
+            UnsignedPolicy::Error => Err(UnsupportedType("u32".to_string())),
+            UnsignedPolicy::Reinterpret => self.serialize_i32(v as i32),
+            UnsignedPolicy::Widen => self.serialize_i64(v.into()),
+        }
     }
 
-    fn serialize_u64(self, _v: u64) -> Result<()> {
-        Err(UnsupportedType("u64".to_string()))
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        match self.unsigned_policy {
+            UnsignedPolicy::Error => Err(UnsupportedType("u64".to_string())),
+            UnsignedPolicy::Reinterpret => self.serialize_i64(v as i64),
+            UnsignedPolicy::Widen => {
+                let v = i64::try_from(v).map_err(|_| {
+                    Error::SerdeError(format!("u64 value {v} doesn't fit in a long"))
+                })?;
+                self.serialize_i64(v)
+            }
+        }
     }
 
     fn serialize_f32(self, v: f32) -> Result<()> {
         self.parse_state(FLOAT_ID)?;
-        self.output.put_f32(v);
+        self.flavor.put_f32(&mut self.output, v);
         Ok(())
     }
 
     fn serialize_f64(self, v: f64) -> Result<()> {
         self.parse_state(DOUBLE_ID)?;
-        self.output.put_f64(v);
+        self.flavor.put_f64(&mut self.output, v);
         Ok(())
     }
 
-    fn serialize_char(self, _v: char) -> Result<()> {
-        Err(UnsupportedType("char".to_string()))
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
     }
 
     fn serialize_str(self, v: &str) -> Result<()> {
@@ -176,7 +268,7 @@ impl ser::Serializer for &mut Serializer {
             return Ok(());
         }
 
-        serialize_str_into(v, &mut self.output);
+        self.flavor.put_string(&mut self.output, v);
         Ok(())
     }
 
@@ -184,9 +276,18 @@ impl ser::Serializer for &mut Serializer {
         Err(UnsupportedType("bytes".to_string()))
     }
 
-    // Just skip serializing if the value is none
+    // NBT has no null value, so fields and map values which are None are omitted from compounds
     fn serialize_none(self) -> Result<()> {
-        Ok(())
+        match self.state {
+            State::Named(_) => Ok(()),
+            State::FirstListElement { .. } | State::ListElement | State::TupleElement => Err(
+                Error::SerdeError("None can't be an element of a list or a tuple".to_string()),
+            ),
+            State::MapKey => Err(Error::SerdeError("Map key can't be None".to_string())),
+            State::Root(_) | State::Array { .. } => Err(Error::SerdeError(
+                "None can only be a value of a compound".to_string(),
+            )),
+        }
     }
 
     fn serialize_some<T>(self, value: &T) -> Result<()>
@@ -213,11 +314,12 @@ impl ser::Serializer for &mut Serializer {
         self.serialize_str(variant)
     }
 
-    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<()>
+    // Newtypes are transparent
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        Err(UnsupportedType("newtype struct".to_string()))
+        value.serialize(self)
     }
 
     fn serialize_newtype_variant<T>(
@@ -230,10 +332,11 @@ impl ser::Serializer for &mut Serializer {
     where
         T: ?Sized + Serialize,
     {
+        // Enum variants are serialized as compounds with a single key, the name of the variant
         if name != "nbt_array" {
-            return Err(Error::SerdeError(
-                "new_type variant supports only nbt_array".to_string(),
-            ));
+            let mut compound = self.serialize_struct(name, 1)?;
+            ser::SerializeStruct::serialize_field(&mut compound, variant, value)?;
+            return ser::SerializeStruct::end(compound);
         }
 
         let name = match self.state {
@@ -271,7 +374,7 @@ impl ser::Serializer for &mut Serializer {
                     }
                 };
                 self.parse_state(id)?;
-                self.output.put_i32(len.unwrap() as i32);
+                self.flavor.put_i32(&mut self.output, len.unwrap() as i32);
                 self.state = State::ListElement;
             }
             _ => {
@@ -280,7 +383,7 @@ impl ser::Serializer for &mut Serializer {
                 // If the list is empty, FirstListElement is never parsed
                 if len.unwrap() == 0 {
                     self.output.put_u8(END_ID);
-                    self.output.put_i32(0);
+                    self.flavor.put_i32(&mut self.output, 0);
                 }
 
                 self.state = State::FirstListElement {
@@ -292,26 +395,35 @@ impl ser::Serializer for &mut Serializer {
         Ok(self)
     }
 
-    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
-        Err(UnsupportedType("tuple".to_string()))
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        Ok(TupleSerializer {
+            serializer: self,
+            elements: Vec::with_capacity(len),
+            is_variant: false,
+        })
     }
 
     fn serialize_tuple_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
-        Err(UnsupportedType("tuple struct".to_string()))
+        self.serialize_tuple(len)
     }
 
     fn serialize_tuple_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _len: usize,
+        variant: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        Err(UnsupportedType("tuple variant".to_string()))
+        let compound = self.serialize_struct(name, 1)?;
+        compound.state = State::Named(variant.to_string());
+        Ok(TupleSerializer {
+            is_variant: true,
+            ..compound.serialize_tuple(len)?
+        })
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
@@ -319,9 +431,20 @@ impl ser::Serializer for &mut Serializer {
     }
 
     fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
-        // Don't write tag id for next elements inside list
-        if let State::ListElement = self.state {
-            return Ok(self);
+        match self.state {
+            // Don't write tag id for next elements inside list
+            State::ListElement => return Ok(self),
+            State::MapKey => {
+                return Err(Error::SerdeError(
+                    "Map key can only be string, not a compound".to_string(),
+                ))
+            }
+            State::Array { .. } => {
+                return Err(Error::SerdeError(
+                    "Array supports only byte, int, long".to_string(),
+                ))
+            }
+            _ => {}
         }
 
         self.output.put_u8(COMPOUND_ID);
@@ -329,18 +452,16 @@ impl ser::Serializer for &mut Serializer {
         match &mut self.state {
             State::Root(root_name) => {
                 if let Some(root_name) = root_name {
-                    serialize_str_into(root_name, &mut self.output);
+                    self.flavor.put_string(&mut self.output, root_name);
                 }
             }
             State::Named(string) => {
-                serialize_str_into(string, &mut self.output);
+                self.flavor.put_string(&mut self.output, string);
             }
             State::FirstListElement { len } => {
-                self.output.put_i32(*len);
-            }
-            _ => {
-                unimplemented!()
+                self.flavor.put_i32(&mut self.output, *len);
             }
+            State::TupleElement | State::ListElement | State::MapKey | State::Array { .. } => {}
         }
 
         Ok(self)
@@ -348,12 +469,14 @@ impl ser::Serializer for &mut Serializer {
 
     fn serialize_struct_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _len: usize,
+        variant: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        Err(UnsupportedType("struct variant".to_string()))
+        let compound = self.serialize_struct(name, 1)?;
+        compound.state = State::Named(variant.to_string());
+        compound.serialize_struct(variant, len)
     }
 
     fn is_human_readable(&self) -> bool {
@@ -379,6 +502,114 @@ impl ser::SerializeSeq for &mut Serializer {
     }
 }
 
+/// Serializes tuples, which are written as int or long arrays if all of their elements
+/// are ints or longs, and as lists otherwise. Elements of different types are written
+/// as a list of compounds wrapping them, like [NbtList::Mixed](crate::NbtList::Mixed).
+/// Elements are buffered until the end, since the type of the tag isn't known before that.
+pub struct TupleSerializer<'a> {
+    serializer: &'a mut Serializer,
+    /// Ids and payloads of the elements
+    elements: Vec<(u8, BytesMut)>,
+    /// Whether the tuple is inside the compound of an enum variant, which has to be ended
+    is_variant: bool,
+}
+
+impl ser::SerializeTuple for TupleSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut element = Serializer {
+            output: BytesMut::new(),
+            state: State::TupleElement,
+            flavor: self.serializer.flavor,
+            unsigned_policy: self.serializer.unsigned_policy,
+        };
+        value.serialize(&mut element)?;
+        if element.output.is_empty() {
+            return Err(Error::SerdeError(
+                "Tuple elements can't be skipped".to_string(),
+            ));
+        }
+        let payload = element.output.split_off(1);
+        self.elements.push((element.output[0], payload));
+        Ok(())
+    }
+
+    fn end(self) -> Result<()> {
+        let serializer = self.serializer;
+        let element_tag = self.elements.first().map_or(END_ID, |(tag, _)| *tag);
+        let is_mixed = self.elements.iter().any(|(tag, _)| *tag != element_tag);
+
+        match element_tag {
+            _ if is_mixed => {
+                serializer.parse_state(LIST_ID)?;
+                serializer.output.put_u8(COMPOUND_ID);
+            }
+            INT_ID => serializer.parse_state(INT_ARRAY_ID)?,
+            LONG_ID => serializer.parse_state(LONG_ARRAY_ID)?,
+            _ => {
+                serializer.parse_state(LIST_ID)?;
+                serializer.output.put_u8(element_tag);
+            }
+        }
+        let len = self.elements.len() as i32;
+        serializer.flavor.put_i32(&mut serializer.output, len);
+        for (tag, payload) in self.elements {
+            if !is_mixed {
+                serializer.output.put_slice(&payload);
+                continue;
+            }
+            // Like lists with elements of different types, see `NbtList::Mixed`,
+            // elements are wrapped in compounds with an empty key
+            serializer.output.put_u8(tag);
+            serializer.flavor.put_string(&mut serializer.output, "");
+            serializer.output.put_slice(&payload);
+            serializer.output.put_u8(END_ID);
+        }
+
+        if self.is_variant {
+            serializer.output.put_u8(END_ID);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for TupleSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeTuple::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeTuple::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for TupleSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeTuple::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeTuple::end(self)
+    }
+}
+
 impl ser::SerializeStruct for &mut Serializer {
     type Ok = ();
     type Error = Error;
@@ -397,6 +628,25 @@ impl ser::SerializeStruct for &mut Serializer {
     }
 }
 
+impl ser::SerializeStructVariant for &mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    // Ends both the compound of the fields and the one containing it
+    fn end(self) -> Result<()> {
+        self.output.put_u8(END_ID);
+        self.output.put_u8(END_ID);
+        Ok(())
+    }
+}
+
 impl ser::SerializeMap for &mut Serializer {
     type Ok = ();
     type Error = Error;
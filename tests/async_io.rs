@@ -0,0 +1,153 @@
+#![cfg(feature = "tokio")]
+
+use crab_nbt::error::Error;
+use crab_nbt::{nbt, Nbt, NbtCompound, NbtFlavor, ReadOptions};
+use tokio::io::{duplex, AsyncWriteExt};
+
+#[tokio::test]
+async fn read_async() {
+    let bytes = include_bytes!("data/bigtest.nbt");
+    let nbt = Nbt::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(Nbt::read_async(bytes.as_slice()).await.unwrap(), nbt);
+
+    let options = ReadOptions::default().with_flavor(NbtFlavor::BedrockNetwork);
+    let network = nbt.write_with_flavor(NbtFlavor::BedrockNetwork);
+    assert_eq!(
+        Nbt::read_async_with_options(network.as_ref(), &options)
+            .await
+            .unwrap(),
+        nbt
+    );
+}
+
+#[tokio::test]
+async fn read_from_slow_stream() {
+    let nbt = nbt!("", {
+        "name": "Steve",
+        "pos": [1.5, 64.0, -3.5],
+        "longs": [L; 1, 2, 3],
+        "inventory": [{"id": "minecraft:stone", "Count": 64i8}, {"id": "minecraft:dirt"}]
+    });
+    let bytes = nbt.write_unnamed();
+    let (mut client, mut server) = duplex(4);
+
+    let send = async {
+        for byte in bytes.iter() {
+            client.write_u8(*byte).await.unwrap();
+        }
+        nbt.write_unnamed_async(&mut client).await.unwrap();
+    };
+    let receive = async {
+        let first = Nbt::read_unnamed_async(&mut server).await.unwrap();
+        let second = Nbt::read_unnamed_async(&mut server).await.unwrap();
+        (first, second)
+    };
+    let ((), (first, second)) = tokio::join!(send, receive);
+    assert_eq!(first, nbt);
+    assert_eq!(second, nbt);
+}
+
+#[tokio::test]
+async fn write_in_parts() {
+    let mut nbt = Nbt::new("chunk".to_owned(), NbtCompound::new());
+    for index in 0..1000 {
+        let name = format!("minecraft:block_{index}");
+        nbt.put(
+            index.to_string(),
+            nbt!("", {"Name": name, "Data": [I; 1, 2, 3]}).root_tag,
+        );
+    }
+    let mut written = Vec::new();
+    nbt.write_async(&mut written).await.unwrap();
+    assert_eq!(written, nbt.write());
+
+    // The NBT is received while it's being written, so it doesn't have to fit in the stream
+    let (mut client, mut server) = duplex(1024);
+    let send = async { nbt.write_async(&mut client).await.unwrap() };
+    let receive = async {
+        Nbt::read_async_with_options(&mut server, &ReadOptions::network())
+            .await
+            .unwrap()
+    };
+    let ((), read) = tokio::join!(send, receive);
+    assert_eq!(read, nbt);
+}
+
+#[tokio::test]
+async fn reader_stops_after_nbt() {
+    let first = nbt!("first", {"list": [[1, 2], [3]], "ints": [I; 1, 2]});
+    let second = nbt!("second", {"text": "text"});
+    let mut bytes = Vec::new();
+    first.write_async(&mut bytes).await.unwrap();
+    second.write_async(&mut bytes).await.unwrap();
+
+    let mut reader = bytes.as_slice();
+    assert_eq!(Nbt::read_async(&mut reader).await.unwrap(), first);
+    assert_eq!(Nbt::read_async(&mut reader).await.unwrap(), second);
+    assert!(reader.is_empty());
+}
+
+#[tokio::test]
+async fn limits() {
+    let options = ReadOptions::network().with_max_total_bytes(100);
+
+    // An array claiming a million longs is rejected before they are received
+    let (mut client, mut server) = duplex(64);
+    client
+        .write_all(b"\x0a\x0c\x00\x01a\x00\x0f\x42\x40")
+        .await
+        .unwrap();
+    let result = Nbt::read_unnamed_async_with_options(&mut server, &options).await;
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::SizeLimitExceeded(100))
+    ));
+
+    let options = ReadOptions::network().with_max_depth(2);
+    let bytes = nbt!("", {"a": {"b": {}}}).write_unnamed();
+    let result = Nbt::read_unnamed_async_with_options(bytes.as_ref(), &options).await;
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::DepthLimitExceeded(2))
+    ));
+
+    let bytes = nbt!("", {"a": 1, "b": "text"}).write_unnamed();
+    let result = Nbt::read_unnamed_async(&bytes[..bytes.len() - 3]).await;
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::NoDataRemaining { .. })
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn serde() {
+    use crab_nbt::serde::de::{from_async_reader, from_async_reader_unnamed};
+    use crab_nbt::serde::ser::{to_async_writer, to_async_writer_unnamed};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Player {
+        name: String,
+        health: f32,
+        inventory: Vec<String>,
+    }
+
+    let player = Player {
+        name: "Steve".to_owned(),
+        health: 20.0,
+        inventory: vec!["minecraft:stone".to_owned()],
+    };
+
+    let mut bytes = Vec::new();
+    to_async_writer(&player, "player".to_owned(), &mut bytes)
+        .await
+        .unwrap();
+    let read: Player = from_async_reader(bytes.as_slice()).await.unwrap();
+    assert_eq!(read, player);
+
+    let mut bytes = Vec::new();
+    to_async_writer_unnamed(&player, &mut bytes).await.unwrap();
+    let read: Player = from_async_reader_unnamed(bytes.as_slice()).await.unwrap();
+    assert_eq!(read, player);
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "codec")]
+
+use bytes::BytesMut;
+use crab_nbt::error::Error;
+use crab_nbt::{nbt, NbtCodec, NbtFlavor, ReadOptions};
+use futures::{SinkExt, StreamExt};
+use tokio::io::duplex;
+use tokio_util::codec::{Decoder, Encoder, Framed};
+
+#[tokio::test]
+async fn framed() {
+    let messages = [
+        nbt!("", {"text": "hello", "bold": true}),
+        nbt!("", {"pos": [1.5, 64.0], "ids": [I; 1, 2]}),
+        nbt!("", {}),
+    ];
+    let (client, server) = duplex(16);
+    let mut client = Framed::new(client, NbtCodec::new());
+    let mut server = Framed::new(server, NbtCodec::new());
+
+    let send = async {
+        for message in &messages {
+            client.send(message.clone()).await.unwrap();
+        }
+    };
+    let receive = async {
+        let mut received = Vec::new();
+        for _ in 0..messages.len() {
+            received.push(server.next().await.unwrap().unwrap());
+        }
+        received
+    };
+    let ((), received) = tokio::join!(send, receive);
+    assert_eq!(received, messages);
+}
+
+#[test]
+fn incomplete_input() {
+    let first = nbt!("", {"list": [{"a": "text"}, {"b": [L; 1, 2]}], "int": 5});
+    let second = nbt!("", {"second": 2});
+    let mut codec = NbtCodec::new();
+    let mut bytes = BytesMut::new();
+    codec.encode(first.clone(), &mut bytes).unwrap();
+    codec.encode(second.clone(), &mut bytes).unwrap();
+
+    let mut buffer = BytesMut::new();
+    let mut decoded = Vec::new();
+    for byte in bytes {
+        buffer.extend_from_slice(&[byte]);
+        if let Some(nbt) = codec.decode(&mut buffer).unwrap() {
+            decoded.push(nbt);
+        }
+    }
+    assert_eq!(decoded, [first, second]);
+    assert!(buffer.is_empty());
+    assert!(codec.decode(&mut buffer).unwrap().is_none());
+}
+
+#[test]
+fn length_prefix() {
+    let text = "a".repeat(200);
+    let nbt = nbt!("", {"text": text});
+    let payload = nbt.write_unnamed();
+    let mut buffer = BytesMut::new();
+    NbtCodec::new().encode(nbt.clone(), &mut buffer).unwrap();
+
+    // 211 bytes take two bytes as a VarInt
+    assert_eq!(payload.len(), 211);
+    assert_eq!(&buffer[..2], b"\xd3\x01");
+    assert_eq!(&buffer[2..], &payload[..]);
+    assert_eq!(NbtCodec::new().decode(&mut buffer).unwrap(), Some(nbt));
+}
+
+#[test]
+fn named_and_flavors() {
+    let nbt = nbt!("root", {"int": 5, "longs": [L; 1, 2], "text": "text"});
+    for flavor in [
+        NbtFlavor::JavaBE,
+        NbtFlavor::BedrockLE,
+        NbtFlavor::BedrockNetwork,
+    ] {
+        let options = ReadOptions::network().with_flavor(flavor);
+        let mut codec = NbtCodec::with_options(&options).named();
+        let mut buffer = BytesMut::new();
+        codec.encode(nbt.clone(), &mut buffer).unwrap();
+        codec.encode(nbt.clone(), &mut buffer).unwrap();
+        let payload = nbt.write_with_flavor(flavor);
+        let mut frame = vec![payload.len() as u8];
+        frame.extend_from_slice(&payload);
+        assert_eq!(buffer, frame.repeat(2));
+
+        assert_eq!(codec.decode(&mut buffer).unwrap(), Some(nbt.clone()));
+        assert_eq!(codec.decode(&mut buffer).unwrap(), Some(nbt.clone()));
+    }
+}
+
+#[test]
+fn errors() {
+    let options = ReadOptions::network().with_max_total_bytes(100);
+    // The length is rejected before the frame is received
+    let mut codec = NbtCodec::with_options(&options);
+    let mut buffer = BytesMut::from(&b"\x80\x01\x0a"[..]);
+    let error = codec.decode(&mut buffer).unwrap_err();
+    assert_eq!(error.offset(), Some(2));
+    assert!(matches!(error.into_inner(), Error::SizeLimitExceeded(100)));
+
+    let mut codec = NbtCodec::new();
+    let mut buffer = BytesMut::from(&b"\xff\xff\xff\xff\xff\x01"[..]);
+    assert!(matches!(
+        codec.decode(&mut buffer).map_err(Error::into_inner),
+        Err(Error::VarIntTooBig(5))
+    ));
+
+    let mut codec = NbtCodec::new();
+    let mut buffer = BytesMut::from(&b"\x03\x08\x00\x00"[..]);
+    let error = codec.decode(&mut buffer).unwrap_err();
+    assert_eq!(error.offset(), Some(2));
+    assert!(matches!(error.into_inner(), Error::NoRootCompound(8)));
+
+    let mut codec = NbtCodec::new();
+    let mut buffer = BytesMut::from(&b"\x03\x0a\x00\x00"[..]);
+    let error = codec.decode(&mut buffer).unwrap_err();
+    assert_eq!(error.offset(), Some(3));
+    assert!(matches!(error.into_inner(), Error::InvalidFrame(_)));
+
+    let mut codec = NbtCodec::new();
+    let mut buffer = BytesMut::from(&b"\x06\x0a\x03\x00\x01a"[..]);
+    assert!(codec.decode(&mut buffer).unwrap().is_none());
+    assert!(matches!(codec.decode_eof(&mut buffer), Err(Error::Io(_))));
+}
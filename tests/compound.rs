@@ -0,0 +1,127 @@
+#![cfg(feature = "macro")]
+
+use crab_nbt::{nbt, Nbt, NbtCompound, NbtTag};
+
+#[test]
+fn put_keeps_first_value() {
+    let mut compound = NbtCompound::new();
+    compound.put("a".to_owned(), 1);
+    compound.put("b".to_owned(), 2);
+    compound.put("a".to_owned(), 3);
+
+    assert_eq!(compound.get_int("a"), Some(1));
+    assert_eq!(compound.get_int("b"), Some(2));
+    assert_eq!(compound.child_tags.len(), 2);
+}
+
+#[test]
+fn remove_keeps_order() {
+    let mut compound = nbt!("", {"a": 1, "b": 2, "c": 3, "d": 4}).root_tag;
+
+    assert_eq!(compound.remove("b"), Some(NbtTag::Int(2)));
+    assert_eq!(compound.remove("b"), None);
+    assert_eq!(compound.get("b"), None);
+
+    let keys: Vec<String> = compound.into_iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, ["a", "c", "d"]);
+}
+
+#[test]
+fn equality_depends_on_order() {
+    let compound = nbt!("", {"a": 1, "b": 2}).root_tag;
+    let reversed = nbt!("", {"b": 2, "a": 1}).root_tag;
+    assert_ne!(compound, reversed);
+    assert_eq!(compound, nbt!("", {"a": 1, "b": 2}).root_tag);
+}
+
+#[test]
+fn large_compound() {
+    let compound: NbtCompound = (0..10_000)
+        .map(|i| (format!("entry_{i}"), NbtTag::Int(i)))
+        .collect();
+    assert_eq!(compound.get_int("entry_9999"), Some(9999));
+
+    let nbt = Nbt::new("registry".to_owned(), compound);
+    let read = Nbt::read(&mut nbt.write()).unwrap();
+    assert_eq!(read, nbt);
+
+    let keys: Vec<&String> = read.child_tags.keys().take(3).collect();
+    assert_eq!(keys, ["entry_0", "entry_1", "entry_2"]);
+}
+
+#[test]
+fn edit_nested_values() {
+    let mut nbt = nbt!("", {
+        "Inventory": [
+            {"id": "minecraft:stone", "Count": 1i8},
+            {"id": "minecraft:dirt", "Count": 5i8}
+        ],
+        "abilities": {"flying": false, "walkSpeed": 0.1f32}
+    });
+
+    let inventory = nbt.get_list_mut("Inventory").unwrap();
+    let stone = inventory
+        .get_mut(0)
+        .unwrap()
+        .extract_compound_mut()
+        .unwrap();
+    *stone.get_mut("Count").unwrap().extract_byte_mut().unwrap() += 63;
+    stone.get_string_mut("id").unwrap().push_str("_bricks");
+    inventory.pop();
+
+    let abilities = nbt.get_compound_mut("abilities").unwrap();
+    abilities.insert("flying".to_owned(), true);
+
+    let expected = nbt!("", {
+        "Inventory": [{"id": "minecraft:stone_bricks", "Count": 64i8}],
+        "abilities": {"flying": true, "walkSpeed": 0.1f32}
+    });
+    assert_eq!(nbt, expected);
+
+    assert!(nbt.get_compound_mut("Inventory").is_none());
+    assert!(nbt.get_list_mut("missing").is_none());
+}
+
+#[test]
+fn insert_replaces() {
+    let mut compound = nbt!("", {"a": 1, "b": 2}).root_tag;
+    assert_eq!(
+        compound.insert("a".to_owned(), "text"),
+        Some(NbtTag::Int(1))
+    );
+    assert_eq!(compound.insert("c".to_owned(), 3), None);
+    assert_eq!(compound, nbt!("", {"a": "text", "b": 2, "c": 3}).root_tag);
+}
+
+#[test]
+fn retain_and_queries() {
+    let mut compound = nbt!("", {"a": 1, "b": "text", "c": 3, "d": 4}).root_tag;
+    assert_eq!(compound.len(), 4);
+    assert!(compound.contains_key("b"));
+
+    compound.retain(|name, tag| name != "c" && tag.extract_string().is_none());
+    assert_eq!(compound.keys().collect::<Vec<_>>(), ["a", "d"]);
+    assert!(!compound.contains_key("b"));
+
+    for value in compound.values_mut() {
+        *value.extract_int_mut().unwrap() *= 10;
+    }
+    for (_, value) in &mut compound {
+        *value.extract_int_mut().unwrap() += 1;
+    }
+    let values: Vec<_> = compound.values().filter_map(NbtTag::extract_int).collect();
+    assert_eq!(values, [11, 41]);
+
+    compound.retain(|_, _| false);
+    assert!(compound.is_empty());
+}
+
+#[test]
+fn entry() {
+    let mut compound = NbtCompound::new();
+    for name in ["a", "b", "a", "a"] {
+        let count = compound.entry(name.to_owned()).or_insert(NbtTag::Int(0));
+        *count.extract_int_mut().unwrap() += 1;
+    }
+    assert_eq!(compound, nbt!("", {"a": 3, "b": 1}).root_tag);
+}
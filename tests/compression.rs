@@ -0,0 +1,56 @@
+#![cfg(feature = "compression")]
+
+use crab_nbt::error::Error;
+use crab_nbt::{nbt, Compression, Nbt, ReadOptions};
+
+#[test]
+fn read_gzip() {
+    let bytes = include_bytes!("data/complex_player.dat");
+    let nbt = Nbt::read_compressed(&mut bytes.as_slice(), Compression::Gzip).unwrap();
+    assert!(nbt.get_int("SelectedItemSlot").is_some());
+}
+
+#[test]
+fn detect_compression() {
+    let nbt = nbt!("root", {"key": "value", "int": 5});
+    for compression in [Compression::Gzip, Compression::Zlib, Compression::None] {
+        let bytes = nbt.write_compressed(compression).unwrap();
+        assert_eq!(Compression::detect(&bytes), compression);
+
+        let read = Nbt::read_compressed_auto(&mut bytes.clone()).unwrap();
+        assert_eq!(read, nbt);
+    }
+}
+
+#[test]
+fn detect_uncompressed() {
+    let bytes = include_bytes!("data/bigtest.nbt");
+    assert_eq!(Compression::detect(bytes), Compression::None);
+}
+
+#[test]
+fn from_reader_gzip() {
+    let bytes = include_bytes!("data/complex_player.dat");
+    let nbt = Nbt::read_compressed(&mut bytes.as_slice(), Compression::Gzip).unwrap();
+    let decoder = flate2::read::GzDecoder::new(bytes.as_slice());
+    assert_eq!(Nbt::from_reader(decoder).unwrap(), nbt);
+}
+
+#[test]
+fn decompressed_size_limit() {
+    let nbt = nbt!("root", {"data": [B; 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]});
+    let len = nbt.write().len();
+    for compression in [Compression::Gzip, Compression::Zlib, Compression::None] {
+        let bytes = nbt.write_compressed(compression).unwrap();
+        let options = ReadOptions::default().with_max_total_bytes(len);
+        let read = Nbt::read_compressed_with_options(&mut bytes.clone(), compression, &options);
+        assert_eq!(read.unwrap(), nbt);
+
+        let options = ReadOptions::default().with_max_total_bytes(len - 1);
+        let read = Nbt::read_compressed_with_options(&mut bytes.clone(), compression, &options);
+        assert!(matches!(
+            read.map_err(Error::into_inner),
+            Err(Error::SizeLimitExceeded(_))
+        ));
+    }
+}
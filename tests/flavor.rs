@@ -0,0 +1,112 @@
+#![cfg(feature = "macro")]
+
+use crab_nbt::error::Error;
+use crab_nbt::{nbt, Nbt, NbtFlavor, NbtTag};
+
+const FLAVORS: [NbtFlavor; 3] = [
+    NbtFlavor::JavaBE,
+    NbtFlavor::BedrockLE,
+    NbtFlavor::BedrockNetwork,
+];
+
+#[test]
+fn bigtest_roundtrip() {
+    let nbt = Nbt::read(&mut include_bytes!("data/bigtest.nbt").as_slice()).unwrap();
+
+    for flavor in FLAVORS {
+        let bytes = nbt.write_with_flavor(flavor);
+        let read = Nbt::read_with_flavor(&mut bytes.clone(), flavor).unwrap();
+        assert_eq!(read, nbt, "{flavor:?}");
+
+        let bytes = nbt.write_unnamed_with_flavor(flavor);
+        let read = Nbt::read_unnamed_with_flavor(&mut bytes.clone(), flavor).unwrap();
+        assert_eq!(read.root_tag, nbt.root_tag, "{flavor:?}");
+    }
+}
+
+#[test]
+fn little_endian() {
+    let nbt = nbt!("a", {"i": 1, "s": "é"});
+    let expected: &[u8] = b"\x0a\x01\0a\x03\x01\0i\x01\0\0\0\x08\x01\0s\x02\0\xc3\xa9\0";
+    assert_eq!(
+        nbt.write_with_flavor(NbtFlavor::BedrockLE).as_ref(),
+        expected
+    );
+}
+
+#[test]
+fn network() {
+    let negative = -2;
+    let nbt = nbt!("", {"i": negative, "l": 300i64, "list": [1, 2]});
+    let expected: &[u8] = b"\x0a\0\x03\x01i\x03\x04\x01l\xd8\x04\x09\x04list\x03\x04\x02\x04\0";
+    assert_eq!(
+        nbt.write_with_flavor(NbtFlavor::BedrockNetwork).as_ref(),
+        expected
+    );
+}
+
+#[test]
+fn tag_roundtrip() {
+    let tag = NbtTag::LongArray(vec![i64::MIN, -1, 0, 1, i64::MAX]);
+    for flavor in FLAVORS {
+        let mut bytes = bytes::BytesMut::new();
+        tag.serialize_data_into_with_flavor(&mut bytes, flavor);
+        let read = NbtTag::deserialize_data_with_flavor(&mut bytes, tag.get_type_id(), flavor);
+        assert_eq!(read.unwrap(), tag);
+    }
+}
+
+#[test]
+fn long_strings_are_truncated() {
+    let fits = "a".repeat(65534);
+    let too_long = format!("{fits}\u{1F600}");
+    for flavor in FLAVORS {
+        let tag = NbtTag::String(too_long.clone());
+        let mut bytes = bytes::BytesMut::new();
+        tag.serialize_data_into_with_flavor(&mut bytes, flavor);
+        let read = NbtTag::deserialize_data_with_flavor(&mut bytes, tag.get_type_id(), flavor);
+        let expected = match flavor {
+            NbtFlavor::BedrockNetwork => &too_long,
+            NbtFlavor::JavaBE | NbtFlavor::BedrockLE => &fits,
+        };
+        assert_eq!(read.unwrap().extract_string(), Some(expected));
+    }
+}
+
+#[test]
+fn bedrock_level_dat() {
+    let nbt = nbt!("", {"LevelName": "My World", "RandomSeed": 42i64});
+    let bytes = nbt.write_bedrock_level_dat(10);
+    assert_eq!(&bytes[..4], &10i32.to_le_bytes());
+    assert_eq!(&bytes[4..8], &(bytes.len() as i32 - 8).to_le_bytes());
+
+    let (storage_version, read) = Nbt::read_bedrock_level_dat(&mut bytes.clone()).unwrap();
+    assert_eq!(storage_version, 10);
+    assert_eq!(read, nbt);
+}
+
+#[test]
+fn bedrock_level_dat_invalid_length() {
+    let nbt = nbt!("", {"LevelName": "My World"});
+    let bytes = nbt.write_bedrock_level_dat(10);
+
+    let mut too_long = bytes.to_vec();
+    too_long[4] += 1;
+    let error = Nbt::read_bedrock_level_dat(&mut too_long.as_slice()).unwrap_err();
+    assert_eq!(error.offset(), Some(8));
+    assert!(matches!(error.inner(), Error::InvalidBedrockHeader(_)));
+
+    // The payload is missing the end tag of the root compound
+    let mut too_short = bytes.to_vec();
+    too_short[4] -= 1;
+    let error = Nbt::read_bedrock_level_dat(&mut too_short.as_slice()).unwrap_err();
+    assert_eq!(error.offset(), Some(bytes.len() - 1));
+    assert!(matches!(error.inner(), Error::NoDataRemaining { .. }));
+
+    let mut trailing = bytes.to_vec();
+    trailing[4] += 1;
+    trailing.push(0);
+    let error = Nbt::read_bedrock_level_dat(&mut trailing.as_slice()).unwrap_err();
+    assert_eq!(error.offset(), Some(bytes.len()));
+    assert!(matches!(error.inner(), Error::InvalidBedrockHeader(_)));
+}
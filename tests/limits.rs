@@ -0,0 +1,107 @@
+#![cfg(feature = "macro")]
+
+use bytes::{BufMut, BytesMut};
+use crab_nbt::error::Error;
+use crab_nbt::{nbt, Nbt, NbtCompound, NbtRef, ReadOptions};
+
+/// Unnamed root compound, containing `depth - 1` nested lists.
+fn nested_lists(depth: usize) -> Vec<u8> {
+    let mut bytes = BytesMut::new();
+    bytes.put_slice(b"\x0a\x09\x00\x01a");
+    for _ in 0..depth - 2 {
+        bytes.put_u8(9);
+        bytes.put_i32(1);
+    }
+    bytes.put_u8(1);
+    bytes.put_i32(0);
+    for _ in 0..depth - 1 {
+        bytes.put_u8(0);
+    }
+    bytes.to_vec()
+}
+
+#[test]
+fn depth_limit() {
+    let bytes = nested_lists(512);
+    assert!(Nbt::read_unnamed(&mut bytes.as_slice()).is_ok());
+
+    let bytes = nested_lists(513);
+    assert!(matches!(
+        Nbt::read_unnamed(&mut bytes.as_slice()).map_err(Error::into_inner),
+        Err(Error::DepthLimitExceeded(512))
+    ));
+
+    let options = ReadOptions::default().with_max_depth(4);
+    let bytes = nested_lists(5);
+    assert!(matches!(
+        Nbt::read_unnamed_with_options(&mut bytes.as_slice(), &options).map_err(Error::into_inner),
+        Err(Error::DepthLimitExceeded(4))
+    ));
+}
+
+#[test]
+fn deep_nesting_does_not_overflow_stack() {
+    let bytes = nested_lists(100_000);
+    assert!(matches!(
+        Nbt::read_unnamed(&mut bytes.as_slice()).map_err(Error::into_inner),
+        Err(Error::DepthLimitExceeded(_))
+    ));
+    assert!(matches!(
+        NbtRef::read_unnamed(&bytes).map_err(Error::into_inner),
+        Err(Error::DepthLimitExceeded(_))
+    ));
+}
+
+#[test]
+fn size_limit() {
+    let data = "a".repeat(1000);
+    let nbt = nbt!("", {"data": data});
+    let bytes = nbt.write();
+
+    let options = ReadOptions::default().with_max_total_bytes(bytes.len());
+    assert_eq!(
+        Nbt::read_with_options(&mut bytes.clone(), &options).unwrap(),
+        nbt
+    );
+
+    let options = ReadOptions::default().with_max_total_bytes(bytes.len() - 1);
+    assert!(matches!(
+        Nbt::read_with_options(&mut bytes.clone(), &options).map_err(Error::into_inner),
+        Err(Error::SizeLimitExceeded(_))
+    ));
+}
+
+#[test]
+fn huge_length_prefix() {
+    // A list claiming to contain `i32::MAX` compounds
+    let bytes = b"\x0a\x09\x00\x01a\x0a\x7f\xff\xff\xff\x00";
+    assert!(matches!(
+        Nbt::read_unnamed_with_options(&mut bytes.as_slice(), &ReadOptions::network())
+            .map_err(Error::into_inner),
+        Err(Error::SizeLimitExceeded(_))
+    ));
+
+    // A byte array claiming to have `i32::MAX` bytes
+    let bytes = b"\x0a\x07\x00\x01a\x7f\xff\xff\xff\x00";
+    assert!(matches!(
+        NbtCompound::deserialize_content_with_options(&mut &bytes[1..], &ReadOptions::network())
+            .map_err(Error::into_inner),
+        Err(Error::SizeLimitExceeded(_))
+    ));
+}
+
+#[test]
+fn array_len_limit() {
+    let nbt = nbt!("", {"list": [1, 2, 3], "array": [I; 1, 2]});
+    let options = ReadOptions::default().with_max_array_len(2);
+    assert!(matches!(
+        Nbt::read_with_options(&mut nbt.write(), &options).map_err(Error::into_inner),
+        Err(Error::ArrayTooLong { len: 3, max: 2 })
+    ));
+
+    let options = ReadOptions::default().with_max_array_len(3);
+    assert_eq!(
+        Nbt::read_with_options(&mut nbt.write(), &options).unwrap(),
+        nbt
+    );
+}
@@ -0,0 +1,152 @@
+#![cfg(feature = "macro")]
+
+use crab_nbt::error::Error;
+use crab_nbt::{nbt, Nbt, NbtCompound, NbtList, NbtTag, NbtTagView, ReadOptions};
+
+#[test]
+fn typed_storage() {
+    let nbt = nbt!("", {
+        "bytes": [1i8, 2i8],
+        "strings": ["a", "b"],
+        "compounds": [{"a": 1}, {"b": 2}],
+        "lists": [[1i16], [], [2i16, 3i16]],
+        "empty": []
+    });
+    let read = Nbt::read(&mut nbt.write()).unwrap();
+    assert_eq!(read, nbt);
+
+    assert_eq!(read.get_list("bytes"), Some(&NbtList::Bytes(vec![1, 2])));
+    assert!(matches!(
+        read.get_list("strings"),
+        Some(NbtList::Strings(_))
+    ));
+    assert!(matches!(
+        read.get_list("compounds"),
+        Some(NbtList::Compounds(_))
+    ));
+    assert!(matches!(read.get_list("lists"), Some(NbtList::Lists(_))));
+    assert_eq!(read.get_list("empty"), Some(&NbtList::Empty));
+}
+
+#[test]
+fn homogeneity() {
+    let mut list = NbtList::new();
+    list.push(1).unwrap();
+    list.push(2).unwrap();
+    assert!(matches!(
+        list.push("text"),
+        Err(Error::ListTypeMismatch {
+            list_type: 3,
+            tag_type: 8
+        })
+    ));
+    assert!(list.push(NbtTag::End).is_err());
+    assert_eq!(list, NbtList::Ints(vec![1, 2]));
+
+    assert!(list.replace(0, 1.5).is_err());
+    assert_eq!(list.replace(0, 5).unwrap(), NbtTag::Int(1));
+
+    let mixed = vec![NbtTag::Byte(1), NbtTag::Int(2)];
+    assert!(NbtList::try_from(mixed).is_err());
+
+    // Empty lists accept tags of any type
+    list.clear();
+    list.push("text").unwrap();
+    assert_eq!(list.element_type_id(), 8);
+}
+
+#[test]
+fn empty_lists_are_equal() {
+    let mut ints = NbtList::Ints(vec![1]);
+    ints.pop();
+    assert_eq!(ints, NbtList::Empty);
+    assert_eq!(ints, NbtList::Strings(Vec::new()));
+    assert_eq!(ints.element_type_id(), 0);
+    assert_eq!(
+        NbtTag::List(ints).serialize(),
+        NbtTag::List(NbtList::new()).serialize()
+    );
+}
+
+#[test]
+fn views() {
+    let mut list = NbtList::from(vec![
+        nbt!("", {"id": "minecraft:stone"}).root_tag,
+        nbt!("", {"id": "minecraft:dirt"}).root_tag,
+    ]);
+
+    let ids: Vec<&String> = list
+        .iter()
+        .filter_map(|tag| tag.extract_compound())
+        .filter_map(|compound| compound.get_string("id"))
+        .collect();
+    assert_eq!(ids, ["minecraft:stone", "minecraft:dirt"]);
+    assert_eq!(list.iter().rev().len(), 2);
+
+    for tag in &mut list {
+        let compound = tag.extract_compound_mut().unwrap();
+        compound.insert("Count".to_owned(), 1i8);
+    }
+    list.retain(|tag| {
+        tag.extract_compound().unwrap().get_string("id").unwrap() != "minecraft:dirt"
+    });
+    assert_eq!(
+        list.get(0),
+        Some(NbtTagView::Compound(
+            &nbt!("", {"id": "minecraft:stone", "Count": 1i8}).root_tag
+        ))
+    );
+
+    let mut doubles = NbtList::from(vec![1.0, 2.0]);
+    *doubles.get_mut(1).unwrap().extract_double_mut().unwrap() += 1.0;
+    let tags: Vec<NbtTag> = doubles.into_iter().collect();
+    assert_eq!(tags, [NbtTag::Double(1.0), NbtTag::Double(3.0)]);
+}
+
+#[test]
+fn display() {
+    let compound: NbtCompound = "{a: [1b, 2b], b: [[I; 1], [I; 2]], c: []}".parse().unwrap();
+    assert_eq!(
+        compound.to_string(),
+        "{a: [1b, 2b], b: [[I; 1], [I; 2]], c: []}"
+    );
+}
+
+#[test]
+fn mixed() {
+    let list = NbtList::try_from_tags(vec![
+        NbtTag::Int(1),
+        NbtTag::from("text"),
+        NbtTag::Compound(nbt!("", {"a": 1}).root_tag),
+        NbtTag::Compound(nbt!("", {"": 2}).root_tag),
+    ])
+    .unwrap();
+    assert!(matches!(list, NbtList::Mixed(_)));
+    assert_eq!(list.element_type_id(), 10);
+    assert_eq!(list.to_string(), "[1, \"text\", {a: 1}, {\"\": 2}]");
+    let parsed: NbtTag = "[1, 'text', {a: 1}, {'': 2}]".parse().unwrap();
+    assert_eq!(parsed, NbtTag::List(list.clone()));
+
+    // Elements are wrapped in compounds, unless they are compounds which can't be mistaken for a wrapper
+    let compound = NbtCompound::from_iter([("list".to_owned(), NbtTag::List(list.clone()))]);
+    let nbt = Nbt::new(String::new(), compound);
+    let wrapped = nbt!("", {
+        "list": [{"": 1}, {"": "text"}, {"a": 1}, {"": {"": 2}}]
+    });
+    assert_eq!(Nbt::read(&mut nbt.write()).unwrap(), wrapped);
+
+    let options = ReadOptions::default().with_unwrap_mixed_lists(true);
+    let read = Nbt::read_with_options(&mut nbt.write(), &options).unwrap();
+    assert_eq!(read.get_list("list"), Some(&list));
+
+    // Tags of a single type don't need a mixed list
+    let ints = NbtList::try_from_tags(vec![NbtTag::Int(1), NbtTag::Int(2)]).unwrap();
+    assert_eq!(ints, NbtList::Ints(vec![1, 2]));
+    assert!(NbtList::try_from_tags(vec![NbtTag::Int(1), NbtTag::End]).is_err());
+
+    let mut mixed = NbtList::try_from_tags(vec![NbtTag::Int(1), NbtTag::from("text")]).unwrap();
+    mixed.push(2).unwrap();
+    mixed.replace(0, 1.5).unwrap();
+    assert!(mixed.push(NbtTag::End).is_err());
+    assert_eq!(mixed.to_string(), "[1.5d, \"text\", 2]");
+}
@@ -0,0 +1,300 @@
+#![cfg(feature = "macro")]
+
+use crab_nbt::error::Error;
+use crab_nbt::{
+    nbt, Nbt, NbtCompound, NbtFlavor, NbtList, NbtPath, NbtRef, NbtTag, PathSegment, ReadOptions,
+};
+
+const FLAVORS: [NbtFlavor; 3] = [
+    NbtFlavor::JavaBE,
+    NbtFlavor::BedrockLE,
+    NbtFlavor::BedrockNetwork,
+];
+
+/// Runs every reader on the data, none of them should panic.
+fn read_all(data: &[u8]) {
+    for flavor in FLAVORS {
+        let _ = Nbt::read_with_flavor(&mut &data[..], flavor);
+        let _ = Nbt::read_unnamed_with_flavor(&mut &data[..], flavor);
+        let _ = NbtTag::deserialize_with_flavor(&mut &data[..], flavor);
+        let _ = NbtCompound::deserialize_content_with_flavor(&mut &data[..], flavor);
+        let options = ReadOptions::default().with_flavor(flavor);
+        let _ = Nbt::read_lenient(&mut &data[..], &options);
+        let _ = Nbt::read_unnamed_lenient(&mut &data[..], &options);
+    }
+    let _ = NbtRef::read(data);
+    let _ = NbtRef::read_unnamed(data);
+    #[cfg(feature = "serde")]
+    {
+        let _ = crab_nbt::serde::de::from_bytes::<NbtCompound>(&mut &data[..]);
+        let _ = crab_nbt::serde::de::from_bytes_unnamed::<NbtCompound>(&mut &data[..]);
+    }
+}
+
+#[test]
+fn truncated() {
+    let data = include_bytes!("data/bigtest.nbt");
+    for len in 0..data.len() {
+        read_all(&data[..len]);
+    }
+}
+
+#[test]
+fn corrupted() {
+    let data = include_bytes!("data/bigtest.nbt");
+    for i in 0..data.len() {
+        for value in [0x00, 0x7f, 0x80, 0xff] {
+            let mut corrupted = data.to_vec();
+            corrupted[i] = value;
+            read_all(&corrupted);
+        }
+    }
+}
+
+#[test]
+fn negative_length() {
+    // Byte array with length -1
+    let data = b"\x0a\x00\x00\x07\x00\x01a\xff\xff\xff\xff\x00";
+    assert!(matches!(
+        NbtTag::deserialize_data(&mut &data[7..], 7).map_err(Error::into_inner),
+        Err(Error::NegativeLength(-1))
+    ));
+    assert!(matches!(
+        NbtRef::read(data).map_err(Error::into_inner),
+        Err(Error::NegativeLength(-1))
+    ));
+}
+
+#[test]
+fn missing_list_type() {
+    let data = b"\x00\x7f\xff\xff\xff";
+    assert!(matches!(
+        NbtTag::deserialize_data(&mut &data[..], 9).map_err(Error::into_inner),
+        Err(Error::MissingListType(_))
+    ));
+
+    let empty = b"\x00\x00\x00\x00\x00";
+    assert_eq!(
+        NbtTag::deserialize_data(&mut &empty[..], 9).unwrap(),
+        NbtTag::List(NbtList::new())
+    );
+}
+
+#[test]
+fn not_enough_data() {
+    // String with length 5, but only 2 bytes
+    let data = b"\x00\x05ab";
+    assert!(matches!(
+        NbtTag::deserialize_data(&mut &data[..], 8).map_err(Error::into_inner),
+        Err(Error::NoDataRemaining {
+            requested: 5,
+            remaining: 2
+        })
+    ));
+
+    // Int array with 2 elements, but only 4 bytes
+    let data = b"\x00\x00\x00\x02\x00\x00\x00\x01";
+    assert!(matches!(
+        NbtTag::deserialize_data(&mut &data[..], 11).map_err(Error::into_inner),
+        Err(Error::NoDataRemaining {
+            requested: 8,
+            remaining: 4
+        })
+    ));
+}
+
+fn chunk_with_block_states() -> Nbt {
+    nbt!("", {
+        "Level": {
+            "Sections": [
+                {"Y": 0i8},
+                {"Y": 1i8},
+                {"Y": 2i8},
+                {"Y": 3i8, "BlockStates": [L; 1, 2, 3, 4, 5]}
+            ]
+        }
+    })
+}
+
+/// Offset of the length prefix of the `BlockStates` array, which is right after its name.
+fn block_states_start(bytes: &[u8]) -> usize {
+    let name = b"BlockStates";
+    let position = bytes.windows(name.len()).position(|window| window == name);
+    position.unwrap() + name.len()
+}
+
+#[test]
+fn error_location() {
+    let bytes = chunk_with_block_states().write();
+    let array_start = block_states_start(&bytes);
+
+    let options = ReadOptions::default().with_max_array_len(4);
+    let error = Nbt::read_with_options(&mut bytes.clone(), &options).unwrap_err();
+    assert_eq!(error.offset(), Some(array_start + 4));
+    assert_eq!(
+        error.path().unwrap().to_string(),
+        "Level.Sections[3].BlockStates"
+    );
+    assert!(matches!(
+        error.inner(),
+        Error::ArrayTooLong { len: 5, max: 4 }
+    ));
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "List or array with 5 elements exceeds the limit of 4 elements. \
+            (at byte {}, in `Level.Sections[3].BlockStates`)",
+            array_start + 4
+        )
+    );
+
+    let mut corrupted = bytes.to_vec();
+    corrupted[array_start..array_start + 4].copy_from_slice(&(-5i32).to_be_bytes());
+    let error = NbtRef::read(&corrupted).unwrap_err();
+    assert_eq!(error.offset(), Some(array_start + 4));
+    assert_eq!(
+        error.path().unwrap().segments(),
+        [
+            PathSegment::Key("Level".to_owned()),
+            PathSegment::Key("Sections".to_owned()),
+            PathSegment::Index(3),
+            PathSegment::Key("BlockStates".to_owned()),
+        ]
+    );
+    assert!(matches!(error.inner(), Error::NegativeLength(-5)));
+}
+
+#[test]
+fn error_location_root() {
+    let error = Nbt::read(&mut &b"\x0a\x00\x00\x63"[..]).unwrap_err();
+    assert_eq!(error.offset(), Some(4));
+    assert!(error.path().unwrap().is_empty());
+
+    let error = Nbt::read(&mut &b"\x08"[..]).unwrap_err();
+    assert_eq!(error.offset(), Some(1));
+    assert!(matches!(error.inner(), Error::NoRootCompound(8)));
+    assert_eq!(
+        error.to_string(),
+        "The root tag of the NBT file is not a compound tag. Received tag id: 8 (at byte 1)"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_error_location() {
+    let bytes = chunk_with_block_states().write();
+    let array_start = block_states_start(&bytes);
+    let truncated = &bytes[..bytes.len() - 12];
+
+    // Reading fails right after the length prefix, when there is not enough data for the array
+    let error = crab_nbt::serde::de::from_bytes::<NbtCompound>(&mut &truncated[..]).unwrap_err();
+    assert_eq!(error.offset(), Some(array_start + 4));
+    assert_eq!(
+        error.path().unwrap().to_string(),
+        "Level.Sections[3].BlockStates"
+    );
+    assert!(matches!(error.inner(), Error::NoDataRemaining { .. }));
+}
+
+#[test]
+fn strict_compound() {
+    let nbt = nbt!("", {"a": 1, "b": "text", "c": 3});
+    let mut bytes = nbt.write().to_vec();
+
+    // Replaces the type of `b` with an unknown one
+    let b_start = bytes
+        .windows(3)
+        .position(|window| window == b"\x00\x01b")
+        .unwrap()
+        - 1;
+    bytes[b_start] = 0x63;
+    let error = Nbt::read(&mut bytes.as_slice()).unwrap_err();
+    assert!(matches!(error.inner(), Error::UnknownTagId(0x63)));
+
+    // Missing end tag
+    let bytes = nbt.write();
+    let error = Nbt::read(&mut &bytes[..bytes.len() - 1]).unwrap_err();
+    assert_eq!(error.offset(), Some(bytes.len() - 1));
+    assert!(matches!(error.inner(), Error::NoDataRemaining { .. }));
+}
+
+#[test]
+fn lenient_truncated() {
+    let bytes = chunk_with_block_states().write();
+    let truncated = &bytes[..bytes.len() - 12];
+
+    let options = ReadOptions::default();
+    let (nbt, diagnostics) = Nbt::read_lenient(&mut &truncated[..], &options).unwrap();
+    let expected = nbt!("", {
+        "Level": {
+            "Sections": [{"Y": 0i8}, {"Y": 1i8}, {"Y": 2i8}, {"Y": 3i8}]
+        }
+    });
+    assert_eq!(nbt, expected);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].path().unwrap().to_string(),
+        "Level.Sections[3].BlockStates"
+    );
+    assert!(matches!(
+        diagnostics[0].inner(),
+        Error::NoDataRemaining { .. }
+    ));
+
+    let (nbt, diagnostics) = Nbt::read_lenient(&mut &bytes[..], &options).unwrap();
+    assert_eq!(nbt, chunk_with_block_states());
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn lenient_recoverable() {
+    let data = [
+        &b"\x0a\x00\x00"[..],
+        // String `a` with invalid UTF-8
+        b"\x08\x00\x01a\x00\x02\xffb",
+        // List `b` containing 3 `END`s
+        b"\x09\x00\x01b\x00\x00\x00\x00\x03",
+        b"\x03\x00\x01c\x00\x00\x00\x07",
+        b"\x00",
+    ]
+    .concat();
+    assert!(Nbt::read(&mut data.as_slice()).is_err());
+
+    let (nbt, diagnostics) =
+        Nbt::read_lenient(&mut data.as_slice(), &ReadOptions::default()).unwrap();
+    assert_eq!(nbt, nbt!("", {"a": "\u{fffd}b", "b": [], "c": 7}));
+
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].path().unwrap().to_string(), "a");
+    assert!(matches!(diagnostics[0].inner(), Error::InvalidJavaString));
+    assert_eq!(diagnostics[1].path().unwrap().to_string(), "b");
+    assert!(matches!(diagnostics[1].inner(), Error::MissingListType(3)));
+}
+
+#[test]
+fn lenient_limits() {
+    let bytes = chunk_with_block_states().write();
+    let options = ReadOptions::default().with_max_array_len(4);
+    let error = Nbt::read_lenient(&mut &bytes[..], &options).unwrap_err();
+    assert!(matches!(error.inner(), Error::ArrayTooLong { .. }));
+
+    // Errors before the root compound can't be recovered from
+    assert!(Nbt::read_lenient(&mut &b"\x08"[..], &options).is_err());
+}
+
+#[test]
+fn path_display() {
+    let path = NbtPath::from(vec![
+        PathSegment::Key("Items".to_owned()),
+        PathSegment::Index(0),
+        PathSegment::Key("minecraft:custom_name".to_owned()),
+        PathSegment::Key("with space".to_owned()),
+        PathSegment::Key("dots.in.name".to_owned()),
+    ]);
+    assert_eq!(
+        path.to_string(),
+        "Items[0].minecraft:custom_name.\"with space\".\"dots.in.name\""
+    );
+    assert_eq!(NbtPath::new().to_string(), "");
+}
@@ -0,0 +1,214 @@
+#![cfg(feature = "macro")]
+
+use crab_nbt::error::Error;
+use crab_nbt::{nbt, Nbt, NbtCompound, NbtPath, NbtTag, PathSegment};
+
+fn path(path: &str) -> NbtPath {
+    path.parse().unwrap()
+}
+
+fn player() -> Nbt {
+    nbt!("", {
+        "Inventory": [
+            {"Slot": 0i8, "id": "minecraft:stone", "Count": 64i8},
+            {"Slot": 3i8, "id": "minecraft:diamond_sword", "Count": 1i8, "tag": {
+                "display": {"Name": "Excalibur"},
+                "Enchantments": [{"id": "minecraft:sharpness", "lvl": 5i16}]
+            }},
+            {"Slot": 4i8, "id": "minecraft:dirt", "Count": 12i8}
+        ],
+        "Pos": [1.5, 64.0, -3.5],
+        "abilities": {"flying": false}
+    })
+}
+
+#[test]
+fn parse() {
+    let parsed = path("Inventory[0].tag.display.Name");
+    assert_eq!(
+        parsed.segments(),
+        [
+            PathSegment::Key("Inventory".to_owned()),
+            PathSegment::Index(0),
+            PathSegment::Key("tag".to_owned()),
+            PathSegment::Key("display".to_owned()),
+            PathSegment::Key("Name".to_owned()),
+        ]
+    );
+
+    let slot = nbt!("", {"Slot": 3i8}).root_tag;
+    assert_eq!(
+        path("Items[{Slot:3b}]").segments(),
+        [
+            PathSegment::Key("Items".to_owned()),
+            PathSegment::MatchElements(slot.clone()),
+        ]
+    );
+    assert_eq!(
+        path("{Slot:3b}.\"some key\"[][-1].'Items'{Slot: 3b}").segments(),
+        [
+            PathSegment::MatchRoot(slot.clone()),
+            PathSegment::Key("some key".to_owned()),
+            PathSegment::AllElements,
+            PathSegment::IndexFromEnd(1),
+            PathSegment::MatchKey("Items".to_owned(), slot),
+        ]
+    );
+}
+
+#[test]
+fn display_roundtrip() {
+    for path_string in [
+        "Inventory[0].tag.display.Name",
+        "Items[{Slot: 3b}].Count",
+        "{Slot: 3b}.a[][-1]",
+        "\"some key\".Items{id: \"minecraft:stone\"}",
+        "\"\"[2]",
+    ] {
+        let parsed = path(path_string);
+        assert_eq!(parsed.to_string(), path_string);
+        assert_eq!(path(&parsed.to_string()), parsed);
+    }
+}
+
+#[test]
+fn parse_errors() {
+    for invalid in [
+        "", "a.", "a..b", ".a", "a[", "a[x]", "a[0", "a]", "a b", "a.{b:1}", "a[{b:1]",
+    ] {
+        let error = invalid.parse::<NbtPath>().unwrap_err();
+        assert!(
+            matches!(error, Error::InvalidPath { .. }),
+            "{invalid}: {error}"
+        );
+    }
+}
+
+#[test]
+fn query() {
+    let nbt = player();
+
+    let name = nbt.query(&path("Inventory[1].tag.display.Name"));
+    assert_eq!(name, [NbtTag::from("Excalibur")]);
+
+    let ids: Vec<_> = nbt
+        .query(&path("Inventory[].id"))
+        .into_iter()
+        .filter_map(|tag| tag.extract_string())
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            "minecraft:stone",
+            "minecraft:diamond_sword",
+            "minecraft:dirt"
+        ]
+    );
+
+    assert_eq!(
+        nbt.query(&path("Inventory[{Slot:3b}].Count")),
+        [NbtTag::Byte(1)]
+    );
+    assert_eq!(nbt.query(&path("Pos[-1]")), [NbtTag::Double(-3.5)]);
+    assert_eq!(
+        nbt.query(&path("Inventory[{tag:{Enchantments:[{lvl:5s}]}}].Slot")),
+        [NbtTag::Byte(3)]
+    );
+    assert_eq!(
+        nbt.query(&path("abilities{flying:false}.flying")),
+        [NbtTag::Byte(0)]
+    );
+    assert_eq!(nbt.query(&path("{abilities:{}}.Pos[0]")).len(), 1);
+
+    for missing in [
+        "Inventory[3]",
+        "Pos[-4]",
+        "Pos[-0].a",
+        "Inventory[{Slot:5b}]",
+        "abilities{flying:true}",
+        "{missing:1}.Pos",
+        "Pos.a",
+        "missing[]",
+    ] {
+        assert!(nbt.query(&path(missing)).is_empty(), "{missing}");
+    }
+}
+
+#[test]
+fn query_mut() {
+    let mut nbt = player();
+    for count in nbt.query_mut(&path("Inventory[].Count")) {
+        *count.extract_byte_mut().unwrap() = 1;
+    }
+    let counts = nbt.query(&path("Inventory[{Count:1b}]"));
+    assert_eq!(counts.len(), 3);
+}
+
+#[test]
+fn set_at() {
+    let mut nbt = player();
+
+    assert_eq!(
+        nbt.set_at(&path("Inventory[{Slot:3b}].tag.display.Name"), "Sting"),
+        1
+    );
+    assert_eq!(
+        nbt.get_list("Inventory")
+            .unwrap()
+            .get(1)
+            .unwrap()
+            .extract_compound()
+            .unwrap()
+            .get_compound("tag")
+            .unwrap()
+            .get_compound("display")
+            .unwrap()
+            .get_string("Name")
+            .unwrap(),
+        "Sting"
+    );
+
+    // Setting the same value doesn't change anything
+    assert_eq!(nbt.set_at(&path("abilities.flying"), false), 0);
+    assert_eq!(nbt.set_at(&path("Inventory[].Count"), 2i8), 3);
+    // Lists can't contain tags of different types
+    assert_eq!(nbt.set_at(&path("Pos[0]"), "text"), 0);
+    assert_eq!(nbt.set_at(&path("Pos[-3]"), 0.0), 1);
+    assert_eq!(nbt.set_at(&path("missing[0]"), 1), 0);
+
+    let mut compound = NbtCompound::new();
+    assert_eq!(compound.set_at(&path("a.b[].c"), 1), 1);
+    assert_eq!(compound.set_at(&path("items[{Slot:1b}].id"), "stone"), 1);
+    assert_eq!(compound.set_at(&path("items[{Slot:2b}].id"), "dirt"), 1);
+    assert_eq!(compound.set_at(&path("e{x:1}.y"), 2), 1);
+    let expected = nbt!("", {
+        "a": {"b": [{"c": 1}]},
+        "items": [{"Slot": 1i8, "id": "stone"}, {"Slot": 2i8, "id": "dirt"}],
+        "e": {"x": 1, "y": 2}
+    });
+    assert_eq!(compound, expected.root_tag);
+}
+
+#[test]
+fn remove_at() {
+    let mut nbt = player();
+
+    assert_eq!(nbt.remove_at(&path("Inventory[{Slot:0b}]")), 1);
+    assert_eq!(nbt.remove_at(&path("Inventory[].tag.display")), 1);
+    assert_eq!(nbt.remove_at(&path("Inventory[-1].Count")), 1);
+    assert_eq!(nbt.remove_at(&path("Inventory[0].missing")), 0);
+    assert_eq!(nbt.remove_at(&path("abilities{flying:true}")), 0);
+    assert_eq!(nbt.remove_at(&path("Pos[]")), 3);
+    assert_eq!(nbt.remove_at(&path("abilities{flying:false}")), 1);
+
+    let expected = nbt!("", {
+        "Inventory": [
+            {"Slot": 3i8, "id": "minecraft:diamond_sword", "Count": 1i8, "tag": {
+                "Enchantments": [{"id": "minecraft:sharpness", "lvl": 5i16}]
+            }},
+            {"Slot": 4i8, "id": "minecraft:dirt"}
+        ],
+        "Pos": []
+    });
+    assert_eq!(nbt, expected);
+}
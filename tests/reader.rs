@@ -0,0 +1,262 @@
+#![cfg(feature = "macro")]
+
+use crab_nbt::error::Error;
+use crab_nbt::{nbt, ArrayChunk, Nbt, NbtEvent, NbtFlavor, NbtReader, NbtTag, ReadOptions};
+use std::io::Read;
+
+fn read_events(bytes: &[u8], options: &ReadOptions) -> Vec<NbtEvent> {
+    let events = NbtReader::with_options(bytes, options)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let read_events = NbtReader::from_reader_with_options(bytes, options)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(events, read_events);
+    events
+}
+
+#[test]
+fn events() {
+    let nbt = nbt!("root", {
+        "name": "Steve",
+        "pos": [1.5, 2.5],
+        "ids": [I; 1, 2],
+        "abilities": {"flying": false}
+    });
+    let name = |name: &str| Some(name.to_owned());
+    let expected = [
+        NbtEvent::CompoundStart { name: name("root") },
+        NbtEvent::Scalar(name("name"), NbtTag::from("Steve")),
+        NbtEvent::ListStart {
+            name: name("pos"),
+            element_type: 6,
+            len: 2,
+        },
+        NbtEvent::Scalar(None, NbtTag::Double(1.5)),
+        NbtEvent::Scalar(None, NbtTag::Double(2.5)),
+        NbtEvent::End,
+        NbtEvent::ArrayStart {
+            name: name("ids"),
+            array_type: 11,
+            len: 2,
+        },
+        NbtEvent::ArrayChunk(ArrayChunk::Ints(vec![1, 2])),
+        NbtEvent::End,
+        NbtEvent::CompoundStart {
+            name: name("abilities"),
+        },
+        NbtEvent::Scalar(name("flying"), NbtTag::Byte(0)),
+        NbtEvent::End,
+        NbtEvent::End,
+    ];
+    assert_eq!(read_events(&nbt.write(), &ReadOptions::default()), expected);
+
+    let events: Vec<_> = NbtReader::new(nbt.write_unnamed())
+        .unnamed()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(events[0], NbtEvent::CompoundStart { name: None });
+    assert_eq!(events[1..], expected[1..]);
+}
+
+#[test]
+fn flavors() {
+    let bytes = include_bytes!("data/bigtest.nbt");
+    let nbt = Nbt::read(&mut bytes.as_slice()).unwrap();
+    let expected = read_events(bytes, &ReadOptions::default());
+
+    for flavor in [NbtFlavor::BedrockLE, NbtFlavor::BedrockNetwork] {
+        let options = ReadOptions::default().with_flavor(flavor);
+        let events = read_events(&nbt.write_with_flavor(flavor), &options);
+        assert_eq!(events, expected, "{flavor:?}");
+    }
+}
+
+#[test]
+fn reader_stops_after_nbt() {
+    let mut bytes = nbt!("", {"a": [L; 1, 2], "b": "text"}).write().to_vec();
+    bytes.extend_from_slice(b"rest");
+
+    let mut reader = NbtReader::from_reader(bytes.as_slice());
+    while reader.next_event().unwrap().is_some() {}
+    assert_eq!(reader.offset(), bytes.len() - 4);
+
+    let mut rest = String::new();
+    reader
+        .into_inner()
+        .into_inner()
+        .read_to_string(&mut rest)
+        .unwrap();
+    assert_eq!(rest, "rest");
+}
+
+#[test]
+fn skip_value() {
+    let nbt = nbt!("", {
+        "skipped": {"list": [[1, 2], [3]], "text": "text", "array": [L; 1, 2]},
+        "kept": [{"a": 1}, {"b": 2}, {"c": 3}],
+        "after": 1
+    });
+
+    for flavor in [NbtFlavor::JavaBE, NbtFlavor::BedrockNetwork] {
+        let bytes = nbt.write_with_flavor(flavor);
+        let options = ReadOptions::default().with_flavor(flavor);
+        let mut reader = NbtReader::from_reader_with_options(bytes.as_ref(), &options);
+
+        assert!(matches!(
+            reader.next_event().unwrap(),
+            Some(NbtEvent::CompoundStart { .. })
+        ));
+        assert!(matches!(
+            reader.next_event().unwrap(),
+            Some(NbtEvent::CompoundStart { .. })
+        ));
+        reader.skip_value().unwrap();
+        assert!(matches!(
+            reader.next_event().unwrap(),
+            Some(NbtEvent::ListStart { len: 3, .. })
+        ));
+        assert!(matches!(
+            reader.next_event().unwrap(),
+            Some(NbtEvent::CompoundStart { name: None })
+        ));
+        reader.skip_value().unwrap();
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(NbtEvent::CompoundStart { name: None })
+        );
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(NbtEvent::Scalar(Some("b".to_owned()), NbtTag::Int(2)))
+        );
+        // Skipping after reading a value skips the rest of the compound
+        reader.skip_value().unwrap();
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(NbtEvent::CompoundStart { name: None })
+        );
+        reader.skip_value().unwrap();
+        assert_eq!(reader.next_event().unwrap(), Some(NbtEvent::End));
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(NbtEvent::Scalar(Some("after".to_owned()), NbtTag::Int(1)))
+        );
+        assert_eq!(reader.next_event().unwrap(), Some(NbtEvent::End));
+        assert_eq!(reader.next_event().unwrap(), None);
+        assert_eq!(reader.offset(), bytes.len());
+    }
+}
+
+#[test]
+fn large_arrays_are_chunked() {
+    let longs: Vec<i64> = (0..10_000).collect();
+    let nbt = Nbt::new(
+        String::new(),
+        [("longs".to_owned(), NbtTag::LongArray(longs.clone()))]
+            .into_iter()
+            .collect(),
+    );
+
+    let mut chunk_lens = Vec::new();
+    let mut read = Vec::new();
+    for event in read_events(&nbt.write(), &ReadOptions::default()) {
+        if let NbtEvent::ArrayChunk(ArrayChunk::Longs(chunk)) = event {
+            chunk_lens.push(chunk.len());
+            read.extend(chunk);
+        }
+    }
+    assert_eq!(chunk_lens, [4096, 4096, 1808]);
+    assert_eq!(read, longs);
+}
+
+#[test]
+fn errors() {
+    let bytes = nbt!("", {"a": 1, "b": "text"}).write();
+    let truncated = &bytes[..bytes.len() - 3];
+
+    for mut reader in [
+        NbtReader::new(truncated).collect::<Vec<_>>(),
+        NbtReader::from_reader(truncated).collect::<Vec<_>>(),
+    ]
+    .map(Vec::into_iter)
+    {
+        assert!(reader.by_ref().take(2).all(|event| event.is_ok()));
+        let error = reader.next().unwrap().unwrap_err();
+        assert!(matches!(error.inner(), Error::NoDataRemaining { .. }));
+        assert_eq!(error.offset(), Some(17));
+        assert!(reader.next().is_none());
+    }
+
+    let options = ReadOptions::default().with_max_total_bytes(bytes.len() - 1);
+    let result: Result<Vec<_>, _> =
+        NbtReader::from_reader_with_options(bytes.as_ref(), &options).collect();
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::SizeLimitExceeded(_))
+    ));
+
+    let mut reader = NbtReader::from_reader(b"\x08\x00\x00".as_slice());
+    assert!(matches!(
+        reader.next_event().map_err(Error::into_inner),
+        Err(Error::NoRootCompound(8))
+    ));
+}
+
+#[test]
+fn nbt_from_reader() {
+    let bytes = include_bytes!("data/bigtest.nbt");
+    let nbt = Nbt::read(&mut bytes.as_slice()).unwrap();
+    let reader = std::io::BufReader::new(bytes.as_slice());
+    assert_eq!(Nbt::from_reader(reader).unwrap(), nbt);
+
+    let unnamed = nbt.write_unnamed();
+    assert_eq!(
+        Nbt::from_reader_unnamed(unnamed.as_ref()).unwrap(),
+        Nbt::read_unnamed(&mut unnamed.clone()).unwrap()
+    );
+
+    let options = ReadOptions::default().with_flavor(NbtFlavor::BedrockNetwork);
+    let network = nbt.write_with_flavor(NbtFlavor::BedrockNetwork);
+    assert_eq!(
+        Nbt::from_reader_with_options(network.as_ref(), &options).unwrap(),
+        nbt
+    );
+}
+
+#[test]
+fn from_reader_reads_sequentially() {
+    let first = nbt!("first", {"list": [[1, 2], [3]], "array": [L; 1, 2]});
+    let second = nbt!("second", {"text": "text"});
+    let mut bytes = first.write().to_vec();
+    bytes.extend_from_slice(&second.write());
+
+    let mut reader = bytes.as_slice();
+    assert_eq!(Nbt::from_reader(&mut reader).unwrap(), first);
+    assert_eq!(Nbt::from_reader(&mut reader).unwrap(), second);
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn tag_from_reader() {
+    let nbt = nbt!("", {"compound": {"list": [{"a": 1}], "ints": [I; 1, 2]}});
+    let tag = nbt.get("compound").unwrap();
+    let bytes = tag.serialize();
+    assert_eq!(NbtTag::from_reader(bytes.as_ref()).unwrap(), *tag);
+    assert_eq!(NbtTag::deserialize(&mut bytes.clone()).unwrap(), *tag);
+}
+
+#[test]
+fn from_reader_errors() {
+    let bytes = nbt!("", {"a": 1, "b": "text"}).write();
+    assert!(matches!(
+        Nbt::from_reader(&bytes[..bytes.len() - 3]).map_err(Error::into_inner),
+        Err(Error::NoDataRemaining { .. })
+    ));
+
+    let mut nested = vec![10, 0, 0];
+    nested.extend(std::iter::repeat_n([10, 0, 0], 100_000).flatten());
+    assert!(matches!(
+        Nbt::from_reader(nested.as_slice()).map_err(Error::into_inner),
+        Err(Error::DepthLimitExceeded(_))
+    ));
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "region")]
+
+use bytes::Bytes;
+use crab_nbt::error::Error;
+use crab_nbt::region::RegionFile;
+use crab_nbt::{nbt, Compression, Nbt, ReadOptions};
+use std::io::Cursor;
+
+fn chunk() -> Nbt {
+    Nbt::read(&mut include_bytes!("data/chunk.nbt").as_slice()).unwrap()
+}
+
+#[test]
+fn write_and_read_chunks() {
+    let mut region = RegionFile::create(Cursor::new(Vec::new())).unwrap();
+    let chunk = chunk();
+    let small = nbt!("", {"xPos": 1, "zPos": 2});
+
+    region.write_chunk(0, 0, &chunk, Compression::Zlib).unwrap();
+    region.write_chunk(1, 2, &small, Compression::Gzip).unwrap();
+    region
+        .write_chunk(-1, -1, &small, Compression::None)
+        .unwrap();
+
+    let mut region = RegionFile::open(region.into_inner()).unwrap();
+    let chunks: Vec<_> = region.chunks().collect();
+    assert_eq!(chunks, vec![(0, 0), (1, 2), (31, 31)]);
+    assert!(region.timestamp(1, 2).is_some());
+    assert!(!region.has_chunk(5, 5));
+
+    assert_eq!(region.read_chunk(0, 0).unwrap(), Some(chunk));
+    assert_eq!(region.read_chunk(1, 2).unwrap(), Some(small.clone()));
+    assert_eq!(region.read_chunk(31, 31).unwrap(), Some(small));
+    assert_eq!(region.read_chunk(5, 5).unwrap(), None);
+}
+
+#[test]
+fn reallocate_growing_chunk() {
+    let mut region = RegionFile::create(Cursor::new(Vec::new())).unwrap();
+    let small = nbt!("", {"value": 1});
+    let big = chunk();
+
+    region.write_chunk(0, 0, &small, Compression::None).unwrap();
+    region.write_chunk(1, 0, &small, Compression::None).unwrap();
+    // Doesn't fit in a single sector anymore
+    region.write_chunk(0, 0, &big, Compression::None).unwrap();
+    // Reuses the sector freed by the chunk above
+    region.write_chunk(2, 0, &small, Compression::None).unwrap();
+
+    assert_eq!(region.read_chunk(0, 0).unwrap(), Some(big));
+    assert_eq!(region.read_chunk(1, 0).unwrap(), Some(small.clone()));
+    assert_eq!(region.read_chunk(2, 0).unwrap(), Some(small));
+
+    let bytes = region.into_inner().into_inner();
+    assert_eq!(bytes.len() % 4096, 0);
+    // Header, two small chunks and the big one
+    assert_eq!(bytes.len() / 4096, 2 + 2 + 44331_usize.div_ceil(4096));
+}
+
+#[test]
+fn external_chunk() {
+    let directory = std::env::temp_dir().join(format!("crab_nbt_region_{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let mut region = RegionFile::create(Cursor::new(Vec::new()))
+        .unwrap()
+        .with_external_chunks(&directory, -1, 2);
+    let data = Bytes::from(vec![7; 2 * 1024 * 1024]);
+    let huge = nbt!("", { "data": data });
+
+    region.write_chunk(3, 4, &huge, Compression::None).unwrap();
+    assert!(directory.join("c.-29.68.mcc").exists());
+    assert_eq!(region.read_chunk(3, 4).unwrap(), Some(huge));
+
+    // The external file is removed once the chunk fits in the region again
+    let small = nbt!("", {"value": 1});
+    region.write_chunk(3, 4, &small, Compression::Zlib).unwrap();
+    assert!(!directory.join("c.-29.68.mcc").exists());
+    assert_eq!(region.read_chunk(3, 4).unwrap(), Some(small));
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn read_options() {
+    let mut region = RegionFile::create(Cursor::new(Vec::new())).unwrap();
+    region
+        .write_chunk(0, 0, &chunk(), Compression::Zlib)
+        .unwrap();
+
+    let options = ReadOptions::default().with_max_total_bytes(1024);
+    let mut region = RegionFile::open(region.into_inner())
+        .unwrap()
+        .with_read_options(&options);
+    assert!(matches!(
+        region.read_chunk(0, 0).map_err(Error::into_inner),
+        Err(Error::SizeLimitExceeded(1024))
+    ));
+}
+
+#[test]
+fn invalid_locations() {
+    let mut region = RegionFile::create(Cursor::new(Vec::new())).unwrap();
+    region
+        .write_chunk(0, 0, &nbt!("", {"xPos": 0}), Compression::Zlib)
+        .unwrap();
+    let bytes = region.into_inner().into_inner();
+
+    // Chunk starting in the header
+    let mut in_header = bytes.clone();
+    in_header[..4].copy_from_slice(&[0, 0, 1, 1]);
+    let mut region = RegionFile::open(Cursor::new(in_header)).unwrap();
+    assert!(matches!(
+        region.read_chunk(0, 0),
+        Err(Error::InvalidRegion(_))
+    ));
+
+    // Chunk longer than its sectors
+    let mut too_long = bytes;
+    too_long[8192..8196].copy_from_slice(&u32::MAX.to_be_bytes());
+    let mut region = RegionFile::open(Cursor::new(too_long)).unwrap();
+    assert!(matches!(
+        region.read_chunk(0, 0),
+        Err(Error::InvalidRegion(_))
+    ));
+}
@@ -1,15 +1,73 @@
-use std::assert_matches;
+use crab_nbt::{Nbt, ReadOptions};
 
 #[test]
 pub fn adversarial() {
     let bytes = include_bytes!("./data/adversarial.dat").to_vec();
     let nbt = crab_nbt::NbtTag::deserialize(&mut bytes.as_slice());
-    assert_matches!(nbt, Ok(_));
+    assert!(nbt.is_ok());
     let nbt = nbt.unwrap();
     let mut reserialized = nbt.serialize();
 
     let nbt2 = crab_nbt::NbtTag::deserialize(&mut reserialized);
-    assert_matches!(nbt2, Ok(_));
+    assert!(nbt2.is_ok());
     let nbt2 = nbt2.unwrap();
     assert_eq!(nbt, nbt2);
 }
+
+// The SNBT contains `\N{...}` escapes
+#[cfg(feature = "unicode_names")]
+#[test]
+pub fn adversarial_snbt() {
+    use crab_nbt::{NbtCompound, NbtList, NbtTag};
+
+    let bytes = include_bytes!("./data/adversarial.dat").to_vec();
+    let binary = Nbt::read(&mut bytes.as_slice()).unwrap();
+
+    let snbt: NbtCompound = include_str!("./data/adversarial.snbt").parse().unwrap();
+
+    for key in ["bools", "☃", "uuids", "not_numbers"] {
+        assert_eq!(snbt.get(key), binary.get(key), "{key}");
+    }
+
+    // Everything that was parsed survives formatting and parsing again
+    let reparsed: NbtCompound = snbt.to_string().parse().unwrap();
+    assert_eq!(reparsed, snbt);
+
+    assert_eq!(
+        snbt.get_list("het_lists"),
+        Some(
+            &NbtList::try_from_tags(vec![
+                NbtTag::Byte(0),
+                NbtTag::Byte(1),
+                NbtTag::Short(1),
+                NbtTag::Int(2),
+                NbtTag::Long(3),
+                NbtTag::Float(4e8),
+                NbtTag::Double(5.0),
+            ])
+            .unwrap()
+        )
+    );
+}
+
+#[test]
+pub fn adversarial_mixed_lists() {
+    let bytes = include_bytes!("./data/adversarial.dat").to_vec();
+    let options = ReadOptions::default().with_unwrap_mixed_lists(true);
+    let nbt = Nbt::read_with_options(&mut bytes.as_slice(), &options).unwrap();
+
+    assert_eq!(
+        nbt.get("het_lists").unwrap().to_string(),
+        "[0b, 1s, 2, 3L, 400000000.0f, 5.0d]"
+    );
+    assert_eq!(nbt.write(), bytes);
+}
+
+#[test]
+pub fn snbt_display_roundtrip() {
+    let bytes = include_bytes!("./data/bigtest.nbt");
+    let nbt = Nbt::read(&mut bytes.as_slice()).unwrap();
+
+    let parsed: Nbt = nbt.to_string().parse().unwrap();
+    assert_eq!(parsed, nbt);
+}
@@ -1,9 +1,12 @@
 use bytes::BytesMut;
+use crab_nbt::error::Error;
 use crab_nbt::serde::arrays::IntArray;
 use crab_nbt::serde::bool::deserialize_option_bool;
-use crab_nbt::serde::de::from_bytes_unnamed;
-use crab_nbt::serde::ser::to_bytes_unnamed;
-use crab_nbt::{nbt, Nbt, NbtCompound};
+use crab_nbt::serde::de::{
+    from_bytes_unnamed, from_bytes_unnamed_with_options, from_bytes_with_flavor,
+};
+use crab_nbt::serde::ser::{to_bytes_unnamed, to_bytes_with_flavor};
+use crab_nbt::{nbt, Nbt, NbtCompound, NbtFlavor, ReadOptions};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -108,3 +111,62 @@ fn boolean_flatten() {
         }
     )
 }
+
+#[test]
+fn test_flavors() {
+    let test = Test {
+        str: "hi ❤️".to_string(),
+        boolean: true,
+        array: vec![5, -6, 7],
+        list: vec![1, 2, 3],
+        sub: Inner { int: 5 },
+        sub_vec: vec![Inner { int: -5 }],
+    };
+
+    for flavor in [NbtFlavor::BedrockLE, NbtFlavor::BedrockNetwork] {
+        let mut bytes = to_bytes_with_flavor(&test, "root".to_owned(), flavor).unwrap();
+        let nbt = Nbt::read_with_flavor(&mut bytes.clone(), flavor).unwrap();
+        assert_eq!(nbt.name, "root");
+        let parsed: Test = from_bytes_with_flavor(&mut bytes, flavor).unwrap();
+        assert_eq!(parsed, test);
+    }
+}
+
+#[test]
+fn test_read_options() {
+    let test = Test {
+        str: "limits".to_string(),
+        boolean: true,
+        array: vec![1, 2, 3],
+        list: vec![4, 5],
+        sub: Inner { int: 6 },
+        sub_vec: vec![Inner { int: 7 }],
+    };
+    let bytes = to_bytes_unnamed(&test).unwrap();
+
+    let options = ReadOptions::network();
+    let read: Test = from_bytes_unnamed_with_options(&mut bytes.clone(), &options).unwrap();
+    assert_eq!(read, test);
+
+    // `sub_vec` contains compounds nested in a list, nested in the root compound
+    let options = ReadOptions::default().with_max_depth(2);
+    let result: Result<Test, _> = from_bytes_unnamed_with_options(&mut bytes.clone(), &options);
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::DepthLimitExceeded(2))
+    ));
+
+    let options = ReadOptions::default().with_max_array_len(2);
+    let result: Result<Test, _> = from_bytes_unnamed_with_options(&mut bytes.clone(), &options);
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::ArrayTooLong { len: 3, max: 2 })
+    ));
+
+    let options = ReadOptions::default().with_max_total_bytes(bytes.len() - 1);
+    let result: Result<Test, _> = from_bytes_unnamed_with_options(&mut bytes.clone(), &options);
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::SizeLimitExceeded(_))
+    ));
+}
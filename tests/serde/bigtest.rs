@@ -1,7 +1,7 @@
 use crate::serde::test_data_definitions::BigTest;
 use bytes::BytesMut;
 use crab_nbt::serde::{
-    de::{from_bytes, from_bytes_unnamed},
+    de::{from_bytes, from_bytes_unnamed, from_reader, from_reader_unnamed, from_slice},
     ser::{to_bytes, to_bytes_unnamed},
 };
 use std::mem;
@@ -30,3 +30,23 @@ fn test_roundtrip_bigtest_unnamed() {
         bytes2.len()
     );
 }
+
+#[test]
+fn test_from_reader_bigtest() {
+    let bytes = include_bytes!("../data/bigtest.nbt");
+    let expected = from_bytes::<BigTest>(&mut BytesMut::from(&bytes[..])).unwrap();
+    let deserialized: BigTest = from_reader(std::io::BufReader::new(&bytes[..])).unwrap();
+    assert_eq!(deserialized, expected);
+
+    let unnamed = to_bytes_unnamed(&expected).unwrap();
+    let deserialized: BigTest = from_reader_unnamed(&unnamed[..]).unwrap();
+    assert_eq!(deserialized, expected);
+}
+
+#[test]
+fn test_from_slice_bigtest() {
+    let bytes = include_bytes!("../data/bigtest.nbt");
+    let expected = from_bytes::<BigTest>(&mut BytesMut::from(&bytes[..])).unwrap();
+    let deserialized: BigTest = from_slice(bytes).unwrap();
+    assert_eq!(deserialized, expected);
+}
@@ -0,0 +1,86 @@
+use crab_nbt::error::Error;
+use crab_nbt::serde::de::{from_slice, from_slice_unnamed, from_slice_unnamed_with_options};
+use crab_nbt::{nbt, NbtFlavor, ReadOptions};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Player<'a> {
+    name: &'a str,
+    #[serde(borrow)]
+    title: Cow<'a, str>,
+    tags: Vec<&'a str>,
+    data: &'a [u8],
+    #[serde(borrow)]
+    stats: HashMap<&'a str, i32>,
+}
+
+fn contains(bytes: &[u8], slice: &[u8]) -> bool {
+    bytes.as_ptr_range().contains(&slice.as_ptr())
+}
+
+#[test]
+fn borrowed() {
+    let nbt = nbt!("player", {
+        "name": "Steve",
+        "title": "Miner",
+        "tags": ["a", "b"],
+        "data": [B; 1, 2, 3],
+        "stats": {"kills": 5}
+    });
+    let bytes = nbt.write();
+    let player: Player = from_slice(&bytes).unwrap();
+    assert_eq!(
+        player,
+        Player {
+            name: "Steve",
+            title: Cow::Borrowed("Miner"),
+            tags: vec!["a", "b"],
+            data: &[1, 2, 3],
+            stats: HashMap::from([("kills", 5)]),
+        }
+    );
+    assert!(contains(&bytes, player.name.as_bytes()));
+    assert!(matches!(player.title, Cow::Borrowed(title) if contains(&bytes, title.as_bytes())));
+    assert!(contains(&bytes, player.tags[1].as_bytes()));
+    assert!(contains(&bytes, player.data));
+
+    let bytes = nbt.write_unnamed_with_flavor(NbtFlavor::BedrockNetwork);
+    let options = ReadOptions::default().with_flavor(NbtFlavor::BedrockNetwork);
+    let player: Player = from_slice_unnamed_with_options(&bytes, &options).unwrap();
+    assert!(contains(&bytes, player.name.as_bytes()));
+    assert!(contains(&bytes, player.data));
+}
+
+#[test]
+fn owned_fallback() {
+    #[derive(Deserialize, Debug)]
+    struct Text<'a> {
+        #[serde(borrow)]
+        text: Cow<'a, str>,
+    }
+
+    // Modified UTF-8 encodes characters outside the BMP as surrogate pairs
+    let compound = nbt!("", {"text": "clef: 𝄞"});
+    let bytes = compound.write_unnamed();
+    let text: Text = from_slice_unnamed(&bytes).unwrap();
+    assert_eq!(text.text, Cow::<str>::Owned("clef: 𝄞".to_owned()));
+
+    #[derive(Deserialize, Debug)]
+    struct BorrowedText<'a> {
+        #[allow(dead_code)]
+        text: &'a str,
+    }
+    let result = from_slice_unnamed::<BorrowedText>(&bytes);
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::SerdeError(_))
+    ));
+
+    // Bedrock Edition uses regular UTF-8
+    let bytes = compound.write_unnamed_with_flavor(NbtFlavor::BedrockLE);
+    let options = ReadOptions::default().with_flavor(NbtFlavor::BedrockLE);
+    let text: BorrowedText = from_slice_unnamed_with_options(&bytes, &options).unwrap();
+    assert!(contains(&bytes, text.text.as_bytes()));
+}
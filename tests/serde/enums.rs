@@ -0,0 +1,160 @@
+use crab_nbt::error::Error;
+use crab_nbt::serde::de::{from_bytes, from_bytes_unnamed};
+use crab_nbt::serde::ser::{to_bytes, to_bytes_unnamed};
+use crab_nbt::{nbt, Nbt};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Component {
+    Unbreakable,
+    CustomName(String),
+    Color(i32, i32, i32),
+    Enchantment { id: String, level: i16 },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Item {
+    id: String,
+    components: Vec<Component>,
+    main: Component,
+}
+
+#[test]
+fn externally_tagged() {
+    let item = Item {
+        id: "minecraft:diamond_sword".to_owned(),
+        components: vec![
+            Component::CustomName("Sword".to_owned()),
+            Component::Color(255, 0, 128),
+            Component::Enchantment {
+                id: "minecraft:sharpness".to_owned(),
+                level: 5,
+            },
+        ],
+        main: Component::Unbreakable,
+    };
+
+    let mut bytes = to_bytes_unnamed(&item).unwrap();
+    assert_eq!(
+        Nbt::read_unnamed(&mut bytes.clone()).unwrap(),
+        nbt!("", {
+            "id": "minecraft:diamond_sword",
+            "components": [
+                {"CustomName": "Sword"},
+                {"Color": [I; 255, 0, 128]},
+                {"Enchantment": {"id": "minecraft:sharpness", "level": 5i16}}
+            ],
+            "main": "Unbreakable"
+        })
+    );
+    let deserialized: Item = from_bytes_unnamed(&mut bytes).unwrap();
+    assert_eq!(deserialized, item);
+}
+
+#[test]
+fn enum_at_root() {
+    let component = Component::Enchantment {
+        id: "minecraft:unbreaking".to_owned(),
+        level: 3,
+    };
+    let mut bytes = to_bytes(&component, "root".to_owned()).unwrap();
+    let deserialized: Component = from_bytes(&mut bytes).unwrap();
+    assert_eq!(deserialized, component);
+
+    let component = Component::CustomName("name".to_owned());
+    let mut bytes = to_bytes_unnamed(&component).unwrap();
+    let deserialized: Component = from_bytes_unnamed(&mut bytes).unwrap();
+    assert_eq!(deserialized, component);
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "type")]
+enum Shape {
+    Circle { radius: f64 },
+    Rectangle { width: i32, height: i32 },
+    Empty,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Drawing {
+    shapes: Vec<Shape>,
+}
+
+#[test]
+fn internally_tagged() {
+    let drawing = Drawing {
+        shapes: vec![
+            Shape::Circle { radius: 1.5 },
+            Shape::Rectangle {
+                width: 2,
+                height: 3,
+            },
+            Shape::Empty,
+        ],
+    };
+    let mut bytes = to_bytes_unnamed(&drawing).unwrap();
+    assert_eq!(
+        Nbt::read_unnamed(&mut bytes.clone()).unwrap(),
+        nbt!("", {
+            "shapes": [
+                {"type": "Circle", "radius": 1.5},
+                {"type": "Rectangle", "width": 2, "height": 3},
+                {"type": "Empty"}
+            ]
+        })
+    );
+    let deserialized: Drawing = from_bytes_unnamed(&mut bytes).unwrap();
+    assert_eq!(deserialized, drawing);
+
+    let shape = Shape::Rectangle {
+        width: 4,
+        height: 5,
+    };
+    let mut bytes = to_bytes(&shape, "shape".to_owned()).unwrap();
+    let deserialized: Shape = from_bytes(&mut bytes).unwrap();
+    assert_eq!(deserialized, shape);
+}
+
+#[test]
+fn invalid_variants() {
+    let mut bytes = nbt!("", {"CustomName": "a", "Color": [1, 2, 3]}).write_unnamed();
+    let result: Result<Component, _> = from_bytes_unnamed(&mut bytes);
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::SerdeError(_))
+    ));
+
+    let mut bytes = nbt!("", {"CustomName": 5}).write_unnamed();
+    let error = from_bytes_unnamed::<Component>(&mut bytes).unwrap_err();
+    assert_eq!(error.path().unwrap().to_string(), "CustomName");
+}
+
+#[test]
+fn variant_with_data_as_map_key() {
+    use std::collections::HashMap;
+
+    #[derive(Serialize, PartialEq, Eq, Hash)]
+    enum Key {
+        Named(String),
+        Pair(i32, i32),
+        Struct { id: i32 },
+    }
+
+    for key in [
+        Key::Named("a".to_owned()),
+        Key::Pair(1, 2),
+        Key::Struct { id: 3 },
+    ] {
+        #[derive(Serialize)]
+        struct Map {
+            map: HashMap<Key, i32>,
+        }
+        let result = to_bytes_unnamed(&Map {
+            map: HashMap::from([(key, 1)]),
+        });
+        assert!(matches!(
+            result.map_err(Error::into_inner),
+            Err(Error::SerdeError(_))
+        ));
+    }
+}
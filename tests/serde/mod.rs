@@ -1,6 +1,11 @@
 mod basic;
 mod bigtest;
+mod borrowed;
 mod complex_text;
+mod enums;
 mod map;
+mod options;
 mod test_data_definitions;
+mod tuples;
 mod unit_variant;
+mod unsigned;
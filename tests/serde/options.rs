@@ -0,0 +1,114 @@
+use crab_nbt::error::Error;
+use crab_nbt::serde::de::from_bytes_unnamed;
+use crab_nbt::serde::ser::to_bytes_unnamed;
+use crab_nbt::{nbt, Nbt};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Inner {
+    value: i32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Options {
+    first: Option<i32>,
+    name: Option<String>,
+    list: Option<Vec<i32>>,
+    inner: Option<Inner>,
+    map: BTreeMap<String, Option<i16>>,
+    last: Option<f64>,
+}
+
+#[test]
+fn none_fields_are_omitted() {
+    let options = Options {
+        first: None,
+        name: Some("name".to_owned()),
+        list: None,
+        inner: Some(Inner { value: 5 }),
+        map: BTreeMap::from([("a".to_owned(), None), ("b".to_owned(), Some(2))]),
+        last: None,
+    };
+    let mut bytes = to_bytes_unnamed(&options).unwrap();
+    assert_eq!(
+        Nbt::read_unnamed(&mut bytes.clone()).unwrap(),
+        nbt!("", {"name": "name", "inner": {"value": 5}, "map": {"b": 2i16}})
+    );
+    let deserialized: Options = from_bytes_unnamed(&mut bytes).unwrap();
+    assert_eq!(
+        deserialized,
+        Options {
+            map: BTreeMap::from([("b".to_owned(), Some(2))]),
+            ..options
+        }
+    );
+
+    let options = Options {
+        first: Some(1),
+        name: None,
+        list: Some(vec![1, 2]),
+        inner: None,
+        map: BTreeMap::new(),
+        last: Some(1.5),
+    };
+    let mut bytes = to_bytes_unnamed(&options).unwrap();
+    let deserialized: Options = from_bytes_unnamed(&mut bytes).unwrap();
+    assert_eq!(deserialized, options);
+}
+
+#[test]
+fn missing_keys() {
+    let mut bytes = nbt!("", {"map": {}}).write_unnamed();
+    let deserialized: Options = from_bytes_unnamed(&mut bytes).unwrap();
+    assert_eq!(
+        deserialized,
+        Options {
+            first: None,
+            name: None,
+            list: None,
+            inner: None,
+            map: BTreeMap::new(),
+            last: None,
+        }
+    );
+}
+
+#[test]
+fn none_elements() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct List {
+        values: Vec<Option<i32>>,
+    }
+
+    let list = List {
+        values: vec![Some(1), Some(2)],
+    };
+    let mut bytes = to_bytes_unnamed(&list).unwrap();
+    let deserialized: List = from_bytes_unnamed(&mut bytes).unwrap();
+    assert_eq!(deserialized, list);
+
+    for values in [vec![None, Some(1)], vec![Some(1), None]] {
+        let result = to_bytes_unnamed(&List { values });
+        assert!(matches!(
+            result.map_err(Error::into_inner),
+            Err(Error::SerdeError(_))
+        ));
+    }
+
+    #[derive(Serialize)]
+    struct Tuple {
+        value: (Option<i32>, i32),
+    }
+    let result = to_bytes_unnamed(&Tuple { value: (None, 1) });
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::SerdeError(_))
+    ));
+
+    let result = to_bytes_unnamed(&None::<Inner>);
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::SerdeError(_))
+    ));
+}
@@ -0,0 +1,121 @@
+use crab_nbt::error::Error;
+use crab_nbt::serde::de::{from_bytes_unnamed, from_bytes_unnamed_with_flavor};
+use crab_nbt::serde::ser::{to_bytes_unnamed, to_bytes_unnamed_with_flavor};
+use crab_nbt::{nbt, Nbt, NbtFlavor, NbtList, NbtTag, ReadOptions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Ticks(i64);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Seed(i64, i64);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Entity {
+    pos: (f64, f64, f64),
+    uuid: (i32, i32, i32, i32),
+    seed: Seed,
+    age: Ticks,
+    names: (String, String),
+    path: Vec<(i16, i16)>,
+}
+
+fn entity() -> Entity {
+    Entity {
+        pos: (1.5, 64.0, -3.5),
+        uuid: (1, -2, 3, -4),
+        seed: Seed(10, 20),
+        age: Ticks(1200),
+        names: ("Zombie".to_owned(), "Bob".to_owned()),
+        path: vec![(0, 1), (2, 3)],
+    }
+}
+
+#[test]
+fn tuples() {
+    let entity = entity();
+    let mut bytes = to_bytes_unnamed(&entity).unwrap();
+    assert_eq!(
+        Nbt::read_unnamed(&mut bytes.clone()).unwrap(),
+        nbt!("", {
+            "pos": [1.5, 64.0, -3.5],
+            "uuid": [I; 1, -2, 3, -4],
+            "seed": [L; 10, 20],
+            "age": 1200i64,
+            "names": ["Zombie", "Bob"],
+            "path": [[0i16, 1i16], [2i16, 3i16]]
+        })
+    );
+    let deserialized: Entity = from_bytes_unnamed(&mut bytes).unwrap();
+    assert_eq!(deserialized, entity);
+
+    for flavor in [NbtFlavor::BedrockLE, NbtFlavor::BedrockNetwork] {
+        let mut bytes = to_bytes_unnamed_with_flavor(&entity, flavor).unwrap();
+        let deserialized: Entity = from_bytes_unnamed_with_flavor(&mut bytes, flavor).unwrap();
+        assert_eq!(deserialized, entity, "{flavor:?}");
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Wrapper(Inner);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Inner {
+    value: i32,
+}
+
+#[test]
+fn newtype_at_root() {
+    let wrapper = Wrapper(Inner { value: 5 });
+    let mut bytes = to_bytes_unnamed(&wrapper).unwrap();
+    assert_eq!(
+        Nbt::read_unnamed(&mut bytes.clone()).unwrap(),
+        nbt!("", {"value": 5})
+    );
+    let deserialized: Wrapper = from_bytes_unnamed(&mut bytes).unwrap();
+    assert_eq!(deserialized, wrapper);
+}
+
+#[test]
+fn mixed_tuple() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Mixed {
+        value: (i32, String, Inner),
+        pair: Seed,
+    }
+
+    let mixed = Mixed {
+        value: (1, "text".to_owned(), Inner { value: 2 }),
+        pair: Seed(3, 4),
+    };
+    let mut bytes = to_bytes_unnamed(&mixed).unwrap();
+    let options = ReadOptions::default().with_unwrap_mixed_lists(true);
+    let nbt = Nbt::read_unnamed_with_options(&mut bytes.clone(), &options).unwrap();
+    let elements = vec![
+        NbtTag::Int(1),
+        NbtTag::String("text".to_owned()),
+        NbtTag::Compound(nbt!("", {"value": 2}).root_tag),
+    ];
+    assert_eq!(
+        nbt.get("value"),
+        Some(&NbtTag::List(NbtList::try_from_tags(elements).unwrap()))
+    );
+    let deserialized: Mixed = from_bytes_unnamed(&mut bytes).unwrap();
+    assert_eq!(deserialized, mixed);
+
+    for flavor in [NbtFlavor::BedrockLE, NbtFlavor::BedrockNetwork] {
+        let mut bytes = to_bytes_unnamed_with_flavor(&mixed, flavor).unwrap();
+        let deserialized: Mixed = from_bytes_unnamed_with_flavor(&mut bytes, flavor).unwrap();
+        assert_eq!(deserialized, mixed, "{flavor:?}");
+    }
+
+    // Wrappers of tuple elements can't contain other keys
+    let mut bytes =
+        nbt!("", {"value": [{"": 1}, {"": "text", "a": 1}, {"": {"value": 2}}], "pair": [L; 3, 4]})
+            .write_unnamed();
+    let result = from_bytes_unnamed::<Mixed>(&mut bytes);
+    assert!(matches!(
+        result.map_err(Error::into_inner),
+        Err(Error::SerdeError(_))
+    ));
+}
@@ -0,0 +1,111 @@
+use crab_nbt::error::Error;
+use crab_nbt::serde::de::from_bytes_unnamed;
+use crab_nbt::serde::ser::{to_bytes_unnamed, Serializer, UnsignedPolicy};
+use crab_nbt::{nbt, Nbt, NbtFlavor};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Unsigned {
+    byte: u8,
+    short: u16,
+    color: u32,
+    uuid: (u64, u64),
+    letter: char,
+}
+
+fn to_bytes_with_policy<T: Serialize>(value: &T, policy: UnsignedPolicy) -> Result<Nbt, Error> {
+    let mut serializer = Serializer::new(None, NbtFlavor::JavaBE).with_unsigned_policy(policy);
+    value.serialize(&mut serializer)?;
+    Nbt::read_unnamed(&mut serializer.into_inner())
+}
+
+#[test]
+fn reinterpret() {
+    let value = Unsigned {
+        byte: 200,
+        short: u16::MAX,
+        color: u32::MAX,
+        uuid: (u64::MAX, 1),
+        letter: 'ż',
+    };
+    let nbt = to_bytes_with_policy(&value, UnsignedPolicy::Reinterpret).unwrap();
+    let (byte, short, color) = (-56i8, -1i16, -1i32);
+    assert_eq!(
+        nbt,
+        nbt!("", {
+            "byte": byte,
+            "short": short,
+            "color": color,
+            "uuid": [L; -1, 1],
+            "letter": "ż"
+        })
+    );
+    let deserialized: Unsigned = from_bytes_unnamed(&mut nbt.write_unnamed()).unwrap();
+    assert_eq!(deserialized, value);
+}
+
+#[test]
+fn widen() {
+    let value = Unsigned {
+        byte: 200,
+        short: u16::MAX,
+        color: u32::MAX,
+        uuid: (i64::MAX as u64, 1),
+        letter: 'a',
+    };
+    let nbt = to_bytes_with_policy(&value, UnsignedPolicy::Widen).unwrap();
+    assert_eq!(
+        nbt,
+        nbt!("", {
+            "byte": 200i16,
+            "short": 65535,
+            "color": 4294967295i64,
+            "uuid": [L; 9223372036854775807, 1],
+            "letter": "a"
+        })
+    );
+    let deserialized: Unsigned = from_bytes_unnamed(&mut nbt.write_unnamed()).unwrap();
+    assert_eq!(deserialized, value);
+
+    let value = Unsigned {
+        uuid: (u64::MAX, 1),
+        ..value
+    };
+    assert!(matches!(
+        to_bytes_with_policy(&value, UnsignedPolicy::Widen).map_err(Error::into_inner),
+        Err(Error::SerdeError(_))
+    ));
+}
+
+#[test]
+fn unsigned_errors() {
+    let value = Unsigned {
+        byte: 1,
+        short: 2,
+        color: 3,
+        uuid: (4, 5),
+        letter: 'a',
+    };
+    assert!(matches!(
+        to_bytes_unnamed(&value).map_err(Error::into_inner),
+        Err(Error::UnsupportedType(_))
+    ));
+
+    // Negative values of wider tags don't fit in unsigned fields
+    #[derive(Deserialize, Debug)]
+    struct Byte {
+        #[allow(dead_code)]
+        byte: u8,
+    }
+    let byte = -1i16;
+    let mut bytes = nbt!("", {"byte": byte}).write_unnamed();
+    assert!(from_bytes_unnamed::<Byte>(&mut bytes).is_err());
+
+    #[derive(Deserialize, Debug)]
+    struct Letter {
+        #[allow(dead_code)]
+        letter: char,
+    }
+    let mut bytes = nbt!("", {"letter": "ab"}).write_unnamed();
+    assert!(from_bytes_unnamed::<Letter>(&mut bytes).is_err());
+}
@@ -1,10 +1,10 @@
 use bytes::Bytes;
-use crab_nbt::{nbt, NbtTag};
+use crab_nbt::{nbt, Nbt, NbtCompound, NbtTag};
 
 #[test]
 fn compound_with_basic_numbers() {
-    let test_string = "{\"\": {a: 0b, b: 1s, c: 2, d: 3L, e: 1.0f, f: 1.5f, g: 2.0d, h: 3.14d}}";
-    let nbt = nbt!("", {"a": 0i8, "b": 1i16, "c": 2i32, "d": 3i64, "e": 1.0f32, "f": 1.5f32, "g": 2.0f64, "h": 3.14f64});
+    let test_string = "{\"\": {a: 0b, b: 1s, c: 2, d: 3L, e: 1.0f, f: 1.5f, g: 2.0d, h: 3.25d}}";
+    let nbt = nbt!("", {"a": 0i8, "b": 1i16, "c": 2i32, "d": 3i64, "e": 1.0f32, "f": 1.5f32, "g": 2.0f64, "h": 3.25f64});
     assert_eq!(nbt.to_string(), test_string)
 }
 
@@ -59,3 +59,72 @@ fn complex_compound() {
     });
     assert_eq!(nbt.to_string(), test_string)
 }
+
+#[test]
+fn parse_compound() {
+    let nbt =
+        Nbt::parse_snbt("{a: 0b, 'b': 1s, \"c\": 2, d: 3l, e: 1.5F, f: 2D, g: hand}").unwrap();
+    let expected = nbt!("", {"a": 0i8, "b": 1i16, "c": 2i32, "d": 3i64, "e": 1.5f32, "f": 2.0f64, "g": "hand"});
+    assert_eq!(nbt, expected)
+}
+
+#[test]
+fn parse_duplicate_keys() {
+    let compound: NbtCompound = "{k: 1, a: 2b, k: 3}".parse().unwrap();
+    assert_eq!(compound.get("k"), Some(&NbtTag::Int(3)));
+    assert_eq!(compound.get("a"), Some(&NbtTag::Byte(2)));
+}
+
+#[test]
+fn parse_arrays() {
+    let tag: NbtTag = "[B; 45B, -37B, 111B, -90B]".parse().unwrap();
+    assert_eq!(tag, NbtTag::ByteArray(Bytes::from(vec![45, 219, 111, 166])));
+
+    let tag: NbtTag = "[I; 1906, -165s, 1b]".parse().unwrap();
+    assert_eq!(tag, NbtTag::IntArray(vec![1906, -165, 1]));
+
+    let tag: NbtTag = "[L; 1906L, -165L]".parse().unwrap();
+    assert_eq!(tag, NbtTag::LongArray(vec![1906, -165]));
+}
+
+#[test]
+fn parse_display_output() {
+    let nbt = nbt!("root 'name'", {
+        "string": "quotes \" and ' \\",
+        "list": [1.0f32, 2.5f32],
+        "empty": [],
+        "minecraft:unbreakable": {},
+    });
+    let parsed: Nbt = nbt.to_string().parse().unwrap();
+    assert_eq!(parsed, nbt)
+}
+
+#[test]
+fn parse_invalid() {
+    assert!("{a: 1".parse::<NbtTag>().is_err());
+    assert!("{a 1}".parse::<NbtTag>().is_err());
+    assert!("[B; 1, 1000]".parse::<NbtTag>().is_err());
+    assert!("\"unterminated".parse::<NbtTag>().is_err());
+    assert!("{a: 1} trailing".parse::<NbtTag>().is_err());
+    assert!("1".parse::<NbtCompound>().is_err());
+}
+
+#[test]
+fn parse_non_finite_display_output() {
+    let (nan_f, nan_d) = (f32::NAN, f64::NAN);
+    let (inf_f, neg_inf_d) = (f32::INFINITY, f64::NEG_INFINITY);
+    let nbt = nbt!("", {
+        "nan_f": nan_f,
+        "nan_d": nan_d,
+        "inf_f": inf_f,
+        "neg_inf_d": neg_inf_d,
+    });
+    let parsed: Nbt = nbt.to_string().parse().unwrap();
+    assert!(matches!(parsed.get("nan_f"), Some(NbtTag::Float(value)) if value.is_nan()));
+    assert!(matches!(parsed.get("nan_d"), Some(NbtTag::Double(value)) if value.is_nan()));
+    assert_eq!(parsed.get("inf_f"), Some(&NbtTag::Float(f32::INFINITY)));
+    assert_eq!(
+        parsed.get("neg_inf_d"),
+        Some(&NbtTag::Double(f64::NEG_INFINITY))
+    );
+}
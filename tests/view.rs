@@ -0,0 +1,92 @@
+#![cfg(feature = "macro")]
+
+use crab_nbt::{nbt, Nbt, NbtRef, NbtTag};
+
+#[test]
+fn matches_owned_read() {
+    for bytes in [
+        include_bytes!("data/bigtest.nbt").as_slice(),
+        include_bytes!("data/chunk.nbt").as_slice(),
+        include_bytes!("data/registry-data-packet.nbt").as_slice(),
+    ] {
+        let owned = Nbt::read(&mut &bytes[..]).unwrap();
+        let view = NbtRef::read(bytes).unwrap();
+        assert_eq!(view.byte_len(), bytes.len());
+        assert_eq!(view.len(), owned.len());
+        assert_eq!(view.to_nbt(), owned);
+    }
+}
+
+#[test]
+fn accessors() {
+    let bytes = include_bytes!("data/bigtest.nbt");
+    let nbt = NbtRef::read(bytes).unwrap();
+
+    assert_eq!(nbt.name(), "Level");
+    assert_eq!(nbt.get_int("intTest"), Some(2147483647));
+    assert_eq!(nbt.get_long("longTest"), Some(9223372036854775807));
+    assert_eq!(nbt.get_short("shortTest"), Some(32767));
+    assert_eq!(nbt.get_byte("byteTest"), Some(127));
+    assert_eq!(nbt.get_int("missing"), None);
+    assert_eq!(nbt.get_long("intTest"), None);
+
+    let egg_name = nbt
+        .get_compound("nested compound test")
+        .and_then(|compound| compound.get_compound("egg"))
+        .and_then(|compound| compound.get_string("name"))
+        .unwrap();
+    assert_eq!(egg_name, "Eggbert");
+
+    let longs: Vec<i64> = nbt
+        .get_list("listTest (long)")
+        .unwrap()
+        .iter()
+        .filter_map(|tag| tag.extract_long())
+        .collect();
+    assert_eq!(longs, vec![11, 12, 13, 14, 15]);
+
+    let compounds = nbt.get_list("listTest (compound)").unwrap();
+    assert_eq!(compounds.len(), 2);
+    let second = compounds
+        .get(1)
+        .and_then(|tag| tag.extract_compound())
+        .unwrap();
+    assert_eq!(
+        second.get("name").map(|tag| tag.to_tag()),
+        Some(NbtTag::String("Compound tag #1".to_owned()))
+    );
+}
+
+#[test]
+fn unnamed() {
+    let bytes = include_bytes!("data/registry-data-packet.nbt");
+    let owned = Nbt::read(&mut &bytes[..]).unwrap();
+    let unnamed = owned.write_unnamed();
+
+    let view = NbtRef::read_unnamed(&unnamed).unwrap();
+    assert_eq!(view.byte_len(), unnamed.len());
+    assert_eq!(view.to_nbt(), owned);
+}
+
+#[test]
+fn truncated_data() {
+    let bytes = nbt!("", {"list": [1, 2, 3], "string": "abc"}).write();
+    for len in 0..bytes.len() {
+        assert!(NbtRef::read(&bytes[..len]).is_err(), "{len}");
+    }
+}
+
+#[test]
+fn list_index() {
+    let bytes = nbt!("", {"list": [1, 2, 3], "strings": ["a", "b"]}).write();
+    let nbt = NbtRef::read(&bytes).unwrap();
+    let list = nbt.get_list("list").unwrap();
+    assert_eq!(list.get(2).and_then(|tag| tag.extract_int()), Some(3));
+    assert!(list.get(3).is_none());
+
+    let strings = nbt.get_list("strings").unwrap();
+    assert_eq!(
+        strings.get(1).and_then(|tag| tag.extract_string()),
+        Some("b".into())
+    );
+}
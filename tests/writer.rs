@@ -0,0 +1,145 @@
+#![cfg(feature = "macro")]
+
+use crab_nbt::error::Error;
+use crab_nbt::{nbt, Nbt, NbtFlavor, NbtWriter};
+use std::cell::Cell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+#[test]
+fn matches_tree_writes() {
+    let nbt = nbt!("player", {
+        "name": "Steve",
+        "health": 20.0f32,
+        "pos": [1.5, 64.0, -3.5],
+        "ids": [I; 1, 2],
+        "inventory": [{"id": "minecraft:stone", "Count": 64i8}],
+        "abilities": {"flying": false}
+    });
+
+    for flavor in [
+        NbtFlavor::JavaBE,
+        NbtFlavor::BedrockLE,
+        NbtFlavor::BedrockNetwork,
+    ] {
+        let mut writer = NbtWriter::with_flavor(Vec::new(), flavor);
+        writer.begin_compound("player").unwrap();
+        writer.write_string("name", "Steve").unwrap();
+        writer.write_float("health", 20.0).unwrap();
+        writer.begin_list("pos", 6, 3).unwrap();
+        for coordinate in [1.5, 64.0, -3.5] {
+            writer.write_double("", coordinate).unwrap();
+        }
+        writer.end().unwrap();
+        writer.write_int_array("ids", &[1, 2]).unwrap();
+        writer.begin_list("inventory", 10, 1).unwrap();
+        writer.begin_compound("").unwrap();
+        writer.write_string("id", "minecraft:stone").unwrap();
+        writer.write_byte("Count", 64).unwrap();
+        writer.end().unwrap();
+        writer.end().unwrap();
+        writer
+            .write_tag("abilities", &nbt.get("abilities").unwrap().clone())
+            .unwrap();
+        writer.end().unwrap();
+
+        let bytes = writer.finish().unwrap();
+        assert_eq!(bytes, nbt.write_with_flavor(flavor), "{flavor:?}");
+    }
+}
+
+#[test]
+fn write_to_writer() {
+    let bytes = include_bytes!("data/chunk.nbt");
+    let nbt = Nbt::read(&mut bytes.as_slice()).unwrap();
+
+    let mut written = Vec::new();
+    nbt.write_to_writer(&mut written).unwrap();
+    assert_eq!(written, nbt.write());
+
+    let mut written = Vec::new();
+    nbt.write_unnamed_to_writer(&mut written).unwrap();
+    assert_eq!(written, nbt.write_unnamed());
+}
+
+/// Sink counting the bytes written to it.
+struct CountingSink(Rc<Cell<usize>>);
+
+impl Write for CountingSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.set(self.0.get() + buf.len());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn writes_directly_to_sink() {
+    let written = Rc::new(Cell::new(0));
+    let mut writer = NbtWriter::new(CountingSink(written.clone()));
+    writer.begin_compound("").unwrap();
+    writer.begin_list("longs", 4, 10_000).unwrap();
+    for i in 0..10_000 {
+        writer.write_long("", i).unwrap();
+    }
+    // Most of the list was written before it ended
+    assert!(written.get() > 70_000);
+    writer.end().unwrap();
+    writer.end().unwrap();
+    writer.finish().unwrap();
+    assert_eq!(written.get(), 3 + 1 + 2 + 5 + 5 + 80_000 + 1);
+}
+
+#[test]
+fn nesting_is_validated() {
+    let mut writer = NbtWriter::new(Vec::new());
+    assert!(matches!(
+        writer.write_int("a", 1),
+        Err(Error::NoRootCompound(3))
+    ));
+    assert!(matches!(writer.end(), Err(Error::InvalidWrite(_))));
+
+    writer.begin_compound("").unwrap();
+    writer.begin_list("list", 3, 2).unwrap();
+    assert!(matches!(
+        writer.write_string("", "text"),
+        Err(Error::ListTypeMismatch {
+            list_type: 3,
+            tag_type: 8
+        })
+    ));
+    writer.write_int("", 1).unwrap();
+    assert!(matches!(writer.end(), Err(Error::InvalidWrite(_))));
+    writer.write_int("", 2).unwrap();
+    assert!(matches!(
+        writer.write_int("", 3),
+        Err(Error::InvalidWrite(_))
+    ));
+    writer.end().unwrap();
+    assert!(matches!(
+        writer.begin_list("empty", 0, 1),
+        Err(Error::MissingListType(1))
+    ));
+    assert!(matches!(
+        writer.begin_list("long", 3, i32::MAX as usize + 1),
+        Err(Error::InvalidWrite(_))
+    ));
+    writer.end().unwrap();
+    assert!(matches!(
+        writer.begin_compound(""),
+        Err(Error::InvalidWrite(_))
+    ));
+
+    let bytes = writer.finish().unwrap();
+    assert_eq!(
+        Nbt::read(&mut bytes.as_slice()).unwrap(),
+        nbt!("", {"list": [1, 2]})
+    );
+
+    let mut writer = NbtWriter::new(Vec::new());
+    writer.begin_compound("").unwrap();
+    assert!(matches!(writer.finish(), Err(Error::InvalidWrite(_))));
+}